@@ -1,19 +1,115 @@
 use codec::Encode;
 use handlebars::Handlebars;
 use serde::Serialize;
-use sp_crypto_hashing::blake2_256;
+use sp_crypto_hashing::{blake2_256, twox_128, twox_64};
 use std::io::prelude::*;
-use std::{fs::File, path::PathBuf};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// Governance path through which the preimage is enacted in the chopsticks script, so that
+/// the simulation dispatches it with the same origin and wrapper as the real proposal.
+#[derive(Clone, Debug)]
+pub enum Enactment {
+    /// Scheduled with a `Root` origin, as by a referendum on the Root track
+    Root,
+    /// Dispatched by a `WhitelistedCaller` referendum, with the call already whitelisted.
+    /// `dispatch` is the encoded `Whitelist.dispatch_whitelisted_call_with_preimage` call.
+    WhitelistedCaller { dispatch: Vec<u8> },
+    /// Whitelisted by the Fellowship over XCM from the Collectives chain, then dispatched
+    /// by a `WhitelistedCaller` referendum
+    FellowshipWhitelist { dispatch: Vec<u8> },
+    /// Signed by the sudo key (testnets only), the preimage being a `Sudo.sudo` call
+    Sudo,
+}
+
+impl Enactment {
+    fn template_name(&self) -> &'static str {
+        match self {
+            Enactment::Root => "root",
+            Enactment::WhitelistedCaller { .. } => "whitelisted-caller",
+            Enactment::FellowshipWhitelist { .. } => "fellowship-whitelist",
+            Enactment::Sudo => "sudo",
+        }
+    }
+
+    fn dispatch(&self) -> Option<&[u8]> {
+        match self {
+            Enactment::WhitelistedCaller { dispatch }
+            | Enactment::FellowshipWhitelist { dispatch } => Some(dispatch),
+            Enactment::Root | Enactment::Sudo => None,
+        }
+    }
+}
+
+/// The chopsticks templates: a script per enactment path, and the xcm-mode config and
+/// verify script.
+///
+/// The registry is strict, so that a value missing from the data fails rendering instead of
+/// leaving a blank in the generated script.
+fn registry() -> Result<Handlebars<'static>, Box<dyn std::error::Error>> {
+    let mut registry = Handlebars::new();
+
+    // Disable HTML escaping
+    registry.register_escape_fn(|s| -> String { s.to_string() });
+    registry.set_strict_mode(true);
+
+    for (name, template) in [
+        (
+            "root",
+            include_str!("../templates/chopsticks-execute-upgrade.js.hbs"),
+        ),
+        (
+            "whitelisted-caller",
+            include_str!("../templates/chopsticks-execute-whitelisted-caller.js.hbs"),
+        ),
+        (
+            "fellowship-whitelist",
+            include_str!("../templates/chopsticks-execute-fellowship-whitelist.js.hbs"),
+        ),
+        (
+            "sudo",
+            include_str!("../templates/chopsticks-execute-sudo.js.hbs"),
+        ),
+        (
+            "xcm-config",
+            include_str!("../templates/chopsticks-xcm-config.yml.hbs"),
+        ),
+        (
+            "xcm-verify",
+            include_str!("../templates/chopsticks-xcm-verify.mjs.hbs"),
+        ),
+    ] {
+        registry.register_template_string(name, template)?;
+    }
+
+    Ok(registry)
+}
 
 #[derive(Clone, Serialize, Debug)]
 struct TemplateData {
     preimage: Preimage,
+    /// Referendum proposal wrapping the preimage, for the whitelisted paths
+    dispatch: Option<Preimage>,
+    /// Raw `Whitelist.WhitelistedCall` storage key of the preimage
+    whitelisted_call_key: String,
 }
 
 impl TemplateData {
-    fn new(preimage: &[u8]) -> Self {
+    fn new(preimage: &[u8], enactment: &Enactment) -> Self {
+        let hash = blake2_256(preimage);
+        let whitelisted_call_key = [
+            &twox_128(b"Whitelist")[..],
+            &twox_128(b"WhitelistedCall"),
+            &twox_64(&hash),
+            &hash,
+        ]
+        .concat();
         TemplateData {
             preimage: preimage.into(),
+            dispatch: enactment.dispatch().map(Into::into),
+            whitelisted_call_key: as_hex_literal(&whitelisted_call_key),
         }
     }
 }
@@ -23,6 +119,8 @@ struct Preimage {
     hash: String,
     bytes: String,
     size: String,
+    /// The call itself, without the length prefix of `bytes`
+    call: String,
 }
 
 impl From<&[u8]> for Preimage {
@@ -31,6 +129,7 @@ impl From<&[u8]> for Preimage {
             hash: as_hex_literal(&blake2_256(data)),
             size: format!("{}", data.len()),
             bytes: as_hex_literal(&data.to_owned().encode()),
+            call: as_hex_literal(data),
         }
     }
 }
@@ -39,20 +138,290 @@ fn as_hex_literal(s: &[u8]) -> String {
     format!("0x{}", hex::encode(s))
 }
 
+/// The chopsticks script enacting `preimage` through `enactment`.
+fn render_script(
+    registry: &Handlebars,
+    preimage: &[u8],
+    enactment: &Enactment,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let data = TemplateData::new(preimage, enactment);
+    Ok(registry.render(enactment.template_name(), &data)?)
+}
+
 pub fn generate_chopsticks_script(
     preimage: &[u8],
+    enactment: &Enactment,
     output_path: PathBuf,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut registry = Handlebars::new();
-
-    // Disable HTML escaping
-    registry.register_escape_fn(|s| -> String { s.to_string() });
-
-    let template = include_str!("../templates/chopsticks-execute-upgrade.js.hbs");
-    let data = TemplateData::new(preimage);
-    let output = registry.render_template(template, &data)?;
+    let output = render_script(&registry()?, preimage, enactment)?;
     let mut file = File::create(output_path)?;
     file.write_all(output.as_bytes())?;
 
     Ok(())
 }
+
+/// Chain a storage expectation is checked on.
+#[derive(Copy, Clone, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Chain {
+    AssetHub,
+    BridgeHub,
+}
+
+/// Raw storage value expected once the preimage has been dispatched.
+#[derive(Clone, Serialize, Debug)]
+pub struct StorageExpectation {
+    chain: Chain,
+    label: String,
+    key: String,
+    value: String,
+}
+
+impl StorageExpectation {
+    pub fn new(chain: Chain, label: impl Into<String>, key: &[u8], value: &[u8]) -> Self {
+        StorageExpectation {
+            chain,
+            label: label.into(),
+            key: as_hex_literal(key),
+            value: as_hex_literal(value),
+        }
+    }
+
+    pub fn chain(&self) -> Chain {
+        self.chain
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// The chains forked by the chopsticks xcm-mode configs.
+pub struct XcmEndpoints<'a> {
+    pub relay: &'a str,
+    pub asset_hub: &'a str,
+    pub bridge_hub: &'a str,
+    /// Parachain id of AssetHub, whose XCMs are followed to BridgeHub
+    pub asset_hub_id: u32,
+    /// Parachain id of BridgeHub
+    pub bridge_hub_id: u32,
+}
+
+#[derive(Clone, Serialize, Debug)]
+struct XcmConfigData<'a> {
+    endpoint: &'a str,
+    port: u16,
+    db: &'a str,
+    relay: bool,
+}
+
+#[derive(Clone, Serialize, Debug)]
+struct XcmScriptData<'a> {
+    enactment: &'static str,
+    /// The chopsticks script of the enactment path, run by the verify script
+    enactment_script: String,
+    expectations: &'a [StorageExpectation],
+    asset_hub_id: u32,
+    bridge_hub_id: u32,
+}
+
+/// The chopsticks configs for the relay chain, AssetHub and BridgeHub, and the verify
+/// script, by file name.
+fn render_xcm(
+    registry: &Handlebars,
+    preimage: &[u8],
+    enactment: &Enactment,
+    expectations: &[StorageExpectation],
+    endpoints: &XcmEndpoints,
+) -> Result<Vec<(&'static str, String)>, Box<dyn std::error::Error>> {
+    let mut files = vec![];
+    for (file, data) in [
+        (
+            "asset-hub.yml",
+            XcmConfigData {
+                endpoint: endpoints.asset_hub,
+                port: 8000,
+                db: "assethub.sqlite",
+                relay: false,
+            },
+        ),
+        (
+            "bridge-hub.yml",
+            XcmConfigData {
+                endpoint: endpoints.bridge_hub,
+                port: 8001,
+                db: "bridgehub.sqlite",
+                relay: false,
+            },
+        ),
+        (
+            "relay.yml",
+            XcmConfigData {
+                endpoint: endpoints.relay,
+                port: 8002,
+                db: "relay.sqlite",
+                relay: true,
+            },
+        ),
+    ] {
+        files.push((file, registry.render("xcm-config", &data)?));
+    }
+
+    let data = XcmScriptData {
+        enactment: enactment.template_name(),
+        enactment_script: render_script(registry, preimage, enactment)?,
+        expectations,
+        asset_hub_id: endpoints.asset_hub_id,
+        bridge_hub_id: endpoints.bridge_hub_id,
+    };
+    files.push(("verify-preimage.mjs", registry.render("xcm-verify", &data)?));
+    Ok(files)
+}
+
+/// Write chopsticks configs for the relay chain, AssetHub and BridgeHub, and a script which
+/// dispatches the preimage on AssetHub with the chopsticks script of `enactment`, follows
+/// the XCMs it sends to BridgeHub and asserts the outcome, exiting non-zero on any mismatch.
+pub fn generate_chopsticks_xcm(
+    preimage: &[u8],
+    enactment: &Enactment,
+    expectations: &[StorageExpectation],
+    endpoints: &XcmEndpoints,
+    output_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let files = render_xcm(&registry()?, preimage, enactment, expectations, endpoints)?;
+    std::fs::create_dir_all(output_dir)?;
+    for (file, output) in files {
+        std::fs::write(output_dir.join(file), output)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREIMAGE: &[u8] = &[0x35, 0x00, 0x01];
+    const DISPATCH: &[u8] = &[0x40, 0x03, 0x35, 0x00, 0x01];
+
+    fn enactments() -> [Enactment; 4] {
+        [
+            Enactment::Root,
+            Enactment::WhitelistedCaller {
+                dispatch: DISPATCH.to_vec(),
+            },
+            Enactment::FellowshipWhitelist {
+                dispatch: DISPATCH.to_vec(),
+            },
+            Enactment::Sudo,
+        ]
+    }
+
+    fn endpoints() -> XcmEndpoints<'static> {
+        XcmEndpoints {
+            relay: "wss://relay.example",
+            asset_hub: "wss://asset-hub.example",
+            bridge_hub: "wss://bridge-hub.example",
+            asset_hub_id: 1000,
+            bridge_hub_id: 1002,
+        }
+    }
+
+    #[test]
+    fn render_scripts() {
+        let registry = registry().unwrap();
+        let preimage = Preimage::from(PREIMAGE);
+        let dispatch = Preimage::from(DISPATCH);
+        for enactment in enactments() {
+            let script = render_script(&registry, PREIMAGE, &enactment).unwrap();
+            assert!(!script.contains("{{"), "{script}");
+            match enactment {
+                Enactment::Root => {
+                    assert!(script.contains(&preimage.hash));
+                    assert!(script.contains(&preimage.bytes));
+                }
+                Enactment::WhitelistedCaller { .. } => {
+                    assert!(script.contains(&dispatch.bytes));
+                    assert!(script
+                        .contains(&TemplateData::new(PREIMAGE, &enactment).whitelisted_call_key));
+                }
+                Enactment::FellowshipWhitelist { .. } => {
+                    assert!(script.contains(&dispatch.bytes));
+                    assert!(script.contains(&format!("whitelistCall(\"{}\")", preimage.hash)));
+                }
+                Enactment::Sudo => assert!(script.contains(&preimage.call)),
+            }
+        }
+    }
+
+    #[test]
+    fn whitelisted_call_key() {
+        let data = TemplateData::new(PREIMAGE, &Enactment::Root);
+        let hash = blake2_256(PREIMAGE);
+        assert_eq!(
+            data.whitelisted_call_key,
+            format!(
+                "0x{}{}{}{}",
+                hex::encode(twox_128(b"Whitelist")),
+                hex::encode(twox_128(b"WhitelistedCall")),
+                hex::encode(twox_64(&hash)),
+                hex::encode(hash)
+            )
+        );
+        assert_eq!(Preimage::from(PREIMAGE).bytes, "0x0c350001");
+        assert_eq!(Preimage::from(PREIMAGE).size, "3");
+    }
+
+    #[test]
+    fn strict_mode_rejects_missing_values() {
+        // The whitelisted paths need the dispatch call
+        let registry = registry().unwrap();
+        let data = TemplateData::new(PREIMAGE, &Enactment::Root);
+        for name in ["whitelisted-caller", "fellowship-whitelist"] {
+            assert!(registry.render(name, &data).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn render_xcm_files() {
+        let registry = registry().unwrap();
+        let expectations = [StorageExpectation::new(
+            Chain::BridgeHub,
+            "EthereumSystem.PricingParameters",
+            &[0xaa, 0xbb],
+            &[0x01],
+        )];
+        for enactment in enactments() {
+            let files =
+                render_xcm(&registry, PREIMAGE, &enactment, &expectations, &endpoints()).unwrap();
+            let names: Vec<&str> = files.iter().map(|(name, _)| *name).collect();
+            assert_eq!(
+                names,
+                [
+                    "asset-hub.yml",
+                    "bridge-hub.yml",
+                    "relay.yml",
+                    "verify-preimage.mjs"
+                ]
+            );
+            assert!(files[0].1.contains("endpoint: wss://asset-hub.example"));
+            assert!(files[1].1.contains("port: 8001"));
+            assert!(!files[1].1.contains("ParasDisputes"));
+            assert!(files[2].1.contains("ParasDisputes"));
+
+            let script = &files[3].1;
+            assert!(!script.contains("{{"), "{script}");
+            assert!(script.contains(&format!(
+                "const enactment = \"{}\";",
+                enactment.template_name()
+            )));
+            assert!(script.contains("const ASSET_HUB_ID = 1000;"));
+            assert!(script.contains("const BRIDGE_HUB_ID = 1002;"));
+            assert!(script.contains(
+                "{ chain: \"bridgeHub\", label: \"EthereumSystem.PricingParameters\", \
+                 key: \"0xaabb\", value: \"0x01\" },"
+            ));
+            // The verify script runs the enactment script
+            assert!(script.contains(&render_script(&registry, PREIMAGE, &enactment).unwrap()));
+        }
+    }
+}
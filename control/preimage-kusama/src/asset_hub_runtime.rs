@@ -1,2 +1,4 @@
-pub use asset_hub_kusama_runtime::runtime_types::asset_hub_kusama_runtime::RuntimeCall;
+pub use asset_hub_kusama_runtime::runtime_types::asset_hub_kusama_runtime::{
+    OriginCaller, RuntimeCall,
+};
 pub use asset_hub_kusama_runtime::*;
@@ -0,0 +1,117 @@
+use crate::asset_hub_runtime::runtime_types::{
+    frame_support::dispatch::RawOrigin, pallet_assets, pallet_utility,
+    staging_xcm::v5::location::Location,
+};
+use crate::asset_hub_runtime::{OriginCaller, RuntimeCall as AssetHubRuntimeCall};
+use crate::commands::get_asset_id;
+use crate::constants::CHAIN_ID;
+use crate::{Context, FreezeTargetArgs};
+use codec::{Decode, DecodeAll, Encode};
+use snowbridge_token_list::storage::map_key;
+use sp_crypto_hashing::blake2_128;
+use subxt::utils::{AccountId32, MultiAddress};
+
+/// `pallet_assets::AssetStatus::Frozen`
+const ASSET_STATUS_FROZEN: u8 = 1;
+
+/// The fields of `pallet_assets::AssetDetails` needed to freeze an asset, in storage order.
+#[derive(Decode)]
+struct AssetDetails {
+    _owner: [u8; 32],
+    _issuer: [u8; 32],
+    admin: [u8; 32],
+    freezer: [u8; 32],
+    _supply: u128,
+    _deposit: u128,
+    _min_balance: u128,
+    _is_sufficient: bool,
+    _accounts: u32,
+    _sufficients: u32,
+    _approvals: u32,
+    status: u8,
+}
+
+/// Freeze (or thaw, with `freeze` false) a bridged asset in `ForeignAssets` on Kusama
+/// AssetHub.
+///
+/// `pallet_assets` only lets the asset's freezer freeze it and its admin thaw it, so
+/// `freeze_asset`/`thaw_asset`, or `freeze`/`thaw` for single accounts, are dispatched as
+/// that account with `Utility.dispatch_as`. With `check_status`, freezing a frozen asset or
+/// thawing one that is not frozen is refused.
+pub async fn freeze_asset(
+    context: &Context,
+    params: &FreezeTargetArgs,
+    freeze: bool,
+    check_status: bool,
+) -> Result<Vec<AssetHubRuntimeCall>, Box<dyn std::error::Error>> {
+    let id = match (&params.token, &params.location) {
+        (Some(token), _) => get_asset_id(CHAIN_ID, token.into_array()),
+        // Both encode as an XCM v5 location
+        (None, Some(location)) => Location::decode_all(&mut &location.encode()[..])?,
+        (None, None) => unreachable!("required by clap"),
+    };
+    let key = map_key("ForeignAssets", "Asset", &id.encode());
+    let storage = context.asset_hub_api.storage().at_latest().await?;
+    let details = storage
+        .fetch_raw(key)
+        .await?
+        .ok_or("Asset does not exist in ForeignAssets on Kusama AssetHub")?;
+    let details = AssetDetails::decode(&mut &details[..])?;
+    let (action, origin) = match freeze {
+        true => ("Freezing", details.freezer),
+        false => ("Thawing", details.admin),
+    };
+
+    if params.accounts.is_empty() {
+        if check_status && freeze == (details.status == ASSET_STATUS_FROZEN) {
+            let state = if freeze { "frozen" } else { "not frozen" };
+            return Err(format!("Asset is already {state}").into());
+        }
+        eprintln!("{action} the whole asset");
+        let call = match freeze {
+            true => pallet_assets::pallet::Call2::freeze_asset { id },
+            false => pallet_assets::pallet::Call2::thaw_asset { id },
+        };
+        return Ok(vec![dispatch_as(origin, call)]);
+    }
+
+    // `ForeignAssets.Account` is keyed by the asset id and the account
+    let accounts_prefix = map_key("ForeignAssets", "Account", &id.encode());
+    let mut calls = vec![];
+    for account in params.accounts.iter() {
+        let account_key = [&accounts_prefix[..], &blake2_128(account), account].concat();
+        if storage.fetch_raw(account_key).await?.is_none() {
+            eprintln!(
+                "Skipping 0x{}: no balance of this asset",
+                hex::encode(account)
+            );
+            continue;
+        }
+        eprintln!("{action} 0x{}", hex::encode(account));
+        let who = MultiAddress::<AccountId32, ()>::Id(AccountId32(*account));
+        let call = match freeze {
+            true => pallet_assets::pallet::Call2::freeze {
+                id: id.clone(),
+                who,
+            },
+            false => pallet_assets::pallet::Call2::thaw {
+                id: id.clone(),
+                who,
+            },
+        };
+        calls.push(dispatch_as(origin, call));
+    }
+    if calls.is_empty() {
+        return Err("None of the accounts hold the asset".into());
+    }
+    Ok(calls)
+}
+
+fn dispatch_as(account: [u8; 32], call: pallet_assets::pallet::Call2) -> AssetHubRuntimeCall {
+    AssetHubRuntimeCall::Utility(pallet_utility::pallet::Call::dispatch_as {
+        as_origin: Box::new(OriginCaller::system(RawOrigin::Signed(AccountId32(
+            account,
+        )))),
+        call: Box::new(AssetHubRuntimeCall::ForeignAssets(call)),
+    })
+}
@@ -1,17 +1,20 @@
 mod asset_hub_runtime;
 mod commands;
 mod constants;
+mod freeze;
 mod helpers;
+mod registrations;
 mod relay_runtime;
 mod xcm_helper;
 
+use alloy_primitives::{Address, FixedBytes};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use codec::Encode;
 use constants::{ASSET_HUB_API, RELAY_API};
 use helpers::send_xcm_asset_hub;
-use snowbridge_preimage_chopsticks::generate_chopsticks_script;
+use snowbridge_preimage_chopsticks::{generate_chopsticks_script, Enactment};
 use sp_crypto_hashing::blake2_256;
-use std::io::Write;
+use std::{io::Write, path::PathBuf};
 use subxt::{OnlineClient, PolkadotConfig};
 
 #[derive(Debug, Parser)]
@@ -35,6 +38,49 @@ pub enum Command {
     RegisterERC20s,
     /// Register ERC20 token metadata and WUD on Kusama AssetHub
     RegisterErc20TokenMetadataAndWUD,
+    /// Register the ERC20 tokens of a token list on Kusama AssetHub
+    RegisterTokens(RegisterTokensArgs),
+    /// Freeze a bridged asset on Kusama AssetHub, or single accounts holding it
+    FreezeAsset(FreezeAssetArgs),
+    /// Thaw an asset or accounts frozen by `freeze-asset`
+    ThawAsset(FreezeTargetArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RegisterTokensArgs {
+    /// Token list (Uniswap token list JSON)
+    #[arg(value_name = "FILE")]
+    token_list: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct FreezeAssetArgs {
+    #[command(flatten)]
+    target: FreezeTargetArgs,
+    /// Also write the preimage thawing the asset again to FILE, as hex. It is built whether or
+    /// not the asset is frozen now.
+    #[arg(long, value_name = "FILE")]
+    thaw_out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct FreezeTargetArgs {
+    /// Address of the bridged ERC20 token
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        value_parser = parse_eth_address_without_validation,
+        required_unless_present = "location",
+        conflicts_with = "location"
+    )]
+    token: Option<Address>,
+    /// Location of the asset in `ForeignAssets` relative to Kusama AssetHub, e.g.
+    /// `../../GlobalConsensus(Ethereum(1))` for Ether
+    #[arg(long, value_name = "LOCATION")]
+    location: Option<snowbridge_token_list::Location>,
+    /// Freeze or thaw only these accounts instead of the whole asset
+    #[arg(long, value_name = "ACCOUNT", value_delimiter = ',', value_parser = parse_account_id)]
+    accounts: Vec<[u8; 32]>,
 }
 
 #[derive(Debug, Args)]
@@ -52,6 +98,24 @@ pub enum Format {
     Binary,
 }
 
+fn parse_eth_address_without_validation(v: &str) -> Result<Address, String> {
+    v.parse::<Address>()
+        .map_err(|_| "invalid ethereum address".to_owned())
+}
+
+/// An account given as 32-byte hex or SS58 address.
+fn parse_account_id(v: &str) -> Result<[u8; 32], String> {
+    if v.starts_with("0x") {
+        return v
+            .parse::<FixedBytes<32>>()
+            .map(|bytes| bytes.0)
+            .map_err(|_| "invalid 32-byte hex value".to_owned());
+    }
+    v.parse::<subxt::utils::AccountId32>()
+        .map(|account| account.0)
+        .map_err(|_| "invalid account, expected 32-byte hex or an SS58 address".to_owned())
+}
+
 struct Context {
     asset_hub_api: Box<OnlineClient<PolkadotConfig>>,
     _relay_api: Box<OnlineClient<PolkadotConfig>>,
@@ -82,6 +146,8 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         _relay_api: Box::new(relay_api),
     };
 
+    // The thaw preimage built by `freeze-asset --thaw-out`, written once the preimage is emitted
+    let mut thaw = None;
     let call = match &cli.command {
         Command::RegisterERC20s => {
             let reg_call = send_xcm_asset_hub(&context, commands::token_registrations()).await?;
@@ -91,13 +157,33 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
             let metadata_calls = commands::register_erc20_token_metadata_and_wud();
             send_xcm_asset_hub(&context, metadata_calls).await?
         }
+        Command::RegisterTokens(params) => {
+            let calls = registrations::register_tokens(&context, params).await?;
+            send_xcm_asset_hub(&context, calls).await?
+        }
+        Command::FreezeAsset(params) => {
+            if let Some(path) = &params.thaw_out {
+                let calls = freeze::freeze_asset(&context, &params.target, false, false).await?;
+                thaw = Some((send_xcm_asset_hub(&context, calls).await?, path));
+            }
+            let calls = freeze::freeze_asset(&context, &params.target, true, true).await?;
+            send_xcm_asset_hub(&context, calls).await?
+        }
+        Command::ThawAsset(params) => {
+            let calls = freeze::freeze_asset(&context, params, false, true).await?;
+            send_xcm_asset_hub(&context, calls).await?
+        }
     };
 
     let final_call = call;
 
     let preimage = final_call.encode();
 
-    generate_chopsticks_script(&preimage, "chopsticks-execute-upgrade.js".into())?;
+    generate_chopsticks_script(
+        &preimage,
+        &Enactment::Root,
+        "chopsticks-execute-upgrade.js".into(),
+    )?;
 
     eprintln!("Preimage Hash: 0x{}", hex::encode(blake2_256(&preimage)));
     eprintln!("Preimage Size: {}", preimage.len());
@@ -111,5 +197,17 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    if let Some((call, path)) = thaw {
+        let preimage = call.encode();
+        std::fs::write(path, format!("0x{}\n", hex::encode(&preimage)))
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        eprintln!(
+            "Thaw preimage 0x{} ({} bytes) written to {}",
+            hex::encode(blake2_256(&preimage)),
+            preimage.len(),
+            path.display()
+        );
+    }
+
     Ok(())
 }
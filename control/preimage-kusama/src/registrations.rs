@@ -0,0 +1,80 @@
+use crate::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use crate::commands::{get_asset_id, register_asset_metadata, register_asset_with_metadata};
+use crate::constants::CHAIN_ID;
+use crate::{Context, RegisterTokensArgs};
+use codec::Encode;
+use snowbridge_token_list::storage::{decode_asset_metadata, map_key};
+use snowbridge_token_list::{load_token_list, Token};
+
+/// Register the ERC20 tokens of a token list on Kusama AssetHub. Tokens that already exist
+/// only have their metadata updated, unless it is identical.
+pub async fn register_tokens(
+    context: &Context,
+    params: &RegisterTokensArgs,
+) -> Result<Vec<AssetHubRuntimeCall>, Box<dyn std::error::Error>> {
+    let tokens = load_token_list(&params.token_list, CHAIN_ID)?;
+    let storage = context.asset_hub_api.storage().at_latest().await?;
+
+    let mut calls = vec![];
+    for token in tokens.iter() {
+        let (address, metadata, min_balance, is_sufficient) = match token {
+            Token::Erc20 {
+                address,
+                metadata,
+                min_balance,
+                is_sufficient,
+            } => (address, metadata, min_balance, is_sufficient),
+            Token::Native { metadata, .. } => {
+                eprintln!(
+                    "Skipping {}: PNAs are registered on Polkadot BridgeHub",
+                    metadata.symbol
+                );
+                continue;
+            }
+        };
+        let symbol = &metadata.symbol;
+        let asset_id = get_asset_id(CHAIN_ID, address.into_array());
+        let key = asset_id.encode();
+
+        if storage
+            .fetch_raw(map_key("ForeignAssets", "Asset", &key))
+            .await?
+            .is_none()
+        {
+            eprintln!("Registering {symbol} on AssetHub");
+            let (create, set_metadata) = register_asset_with_metadata(
+                asset_id,
+                min_balance.unwrap_or(1),
+                metadata.name.clone(),
+                metadata.symbol.clone(),
+                metadata.decimals,
+                is_sufficient.unwrap_or(false),
+            );
+            calls.push(create);
+            calls.push(set_metadata);
+            continue;
+        }
+
+        let current = storage
+            .fetch_raw(map_key("ForeignAssets", "Metadata", &key))
+            .await?
+            .map(|value| decode_asset_metadata(&value))
+            .transpose()?;
+        if current.as_ref() == Some(metadata) {
+            eprintln!("Skipping {symbol}: already registered with identical metadata");
+            continue;
+        }
+        eprintln!("Updating the metadata of {symbol} on AssetHub");
+        calls.push(register_asset_metadata(
+            asset_id,
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.decimals,
+        ));
+    }
+
+    if calls.is_empty() {
+        return Err("Every token in the list is already registered".into());
+    }
+    Ok(calls)
+}
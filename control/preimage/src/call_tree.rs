@@ -0,0 +1,84 @@
+use serde::Serialize;
+use std::fmt;
+
+/// A node in the decoded call tree.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CallNode {
+    pub label: String,
+    pub children: Vec<CallNode>,
+}
+
+impl CallNode {
+    pub fn new(label: impl Into<String>, children: Vec<CallNode>) -> Self {
+        CallNode {
+            label: label.into(),
+            children,
+        }
+    }
+
+    pub fn leaf(label: impl Into<String>) -> Self {
+        CallNode::new(label, vec![])
+    }
+
+    /// A line-based diff of two trees, with `-` for lines only in `self`, `+` for lines only
+    /// in `other`, and the ancestors of every difference as context. Empty if they are equal.
+    pub fn diff(&self, other: &CallNode) -> Vec<String> {
+        let mut lines = vec![];
+        diff_nodes(Some(self), Some(other), 0, &mut lines);
+        lines
+    }
+
+    fn lines(&self, prefix: &str, depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!("{prefix} {}{}", "  ".repeat(depth), self.label));
+        for child in self.children.iter() {
+            child.lines(prefix, depth + 1, lines);
+        }
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{}{}", "  ".repeat(depth), self.label)?;
+        for child in self.children.iter() {
+            child.write_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CallNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+fn diff_nodes(
+    old: Option<&CallNode>,
+    new: Option<&CallNode>,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    match (old, new) {
+        (Some(old), Some(new)) if old.label == new.label => {
+            let mut children = vec![];
+            for i in 0..old.children.len().max(new.children.len()) {
+                diff_nodes(
+                    old.children.get(i),
+                    new.children.get(i),
+                    depth + 1,
+                    &mut children,
+                );
+            }
+            if !children.is_empty() {
+                lines.push(format!("  {}{}", "  ".repeat(depth), old.label));
+                lines.append(&mut children);
+            }
+        }
+        (old, new) => {
+            if let Some(old) = old {
+                old.lines("-", depth, lines);
+            }
+            if let Some(new) = new {
+                new.lines("+", depth, lines);
+            }
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Loading and validating beacon checkpoints for `EthereumBeaconClient::force_checkpoint`.
+//!
+//! The checks mirror those of the pallet's `force_checkpoint`, so that a bad checkpoint is
+//! caught before the proposal is submitted instead of after it is enacted.
+
+use serde_json::Value;
+use snowbridge_beacon_primitives::verify_merkle_branch;
+use std::path::Path;
+use std::time::Duration;
+
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+
+pub type CheckpointUpdate = snowbridge_beacon_primitives::CheckpointUpdate<SYNC_COMMITTEE_SIZE>;
+
+const SLOTS_PER_EPOCH: u64 = 32;
+
+/// Generalized indices of the current sync committee and the block roots in the beacon
+/// state, before and since Electra. The fork is told apart by the branch length, as Electra
+/// grew the state beyond 32 fields.
+const CURRENT_SYNC_COMMITTEE_INDICES: [u64; 2] = [54, 86];
+const BLOCK_ROOTS_INDICES: [u64; 2] = [37, 69];
+
+/// Timeout of each beacon API request.
+const BEACON_API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Read the checkpoint at `path` and check it is internally consistent. With `beacon_api`,
+/// also check that its header is finalized on that beacon node.
+pub async fn load(
+    path: &Path,
+    beacon_api: Option<&str>,
+) -> Result<CheckpointUpdate, Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read checkpoint {}: {e}", path.display()))?;
+    let checkpoint =
+        verify(&data).map_err(|e| format!("Invalid checkpoint {}: {e}", path.display()))?;
+
+    let slot = checkpoint.header.slot;
+    let header_root = checkpoint
+        .header
+        .hash_tree_root()
+        .map_err(|e| format!("Failed to hash the checkpoint header: {e:?}"))?;
+    let header_root = format!("0x{}", hex::encode(header_root.as_bytes()));
+    eprintln!(
+        "Checkpoint at slot {slot} (epoch {}), header root {header_root}: sync committee and block \
+         roots verified against state root 0x{}",
+        slot / SLOTS_PER_EPOCH,
+        hex::encode(checkpoint.header.state_root.as_bytes())
+    );
+
+    if let Some(beacon_api) = beacon_api {
+        verify_finalized(beacon_api, slot, &header_root).await?;
+    }
+    Ok(checkpoint)
+}
+
+/// Parse a checkpoint and check its sync committee size, its slot, and its sync committee
+/// and block roots branches against the header's state root.
+fn verify(data: &str) -> Result<CheckpointUpdate, String> {
+    let value: Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    // Checked before deserializing, which would only report an invalid length
+    let pubkeys = value["current_sync_committee"]["pubkeys"]
+        .as_array()
+        .map(Vec::len)
+        .ok_or("no current_sync_committee.pubkeys")?;
+    if pubkeys != SYNC_COMMITTEE_SIZE {
+        return Err(format!(
+            "sync committee has {pubkeys} public keys, expected {SYNC_COMMITTEE_SIZE}"
+        ));
+    }
+    let checkpoint: CheckpointUpdate = serde_json::from_value(value).map_err(|e| e.to_string())?;
+
+    let slot = checkpoint.header.slot;
+    if !slot.is_multiple_of(SLOTS_PER_EPOCH) {
+        return Err(format!(
+            "slot {slot} is not at an epoch boundary (slot {} starts epoch {})",
+            slot - slot % SLOTS_PER_EPOCH,
+            slot / SLOTS_PER_EPOCH
+        ));
+    }
+
+    let state_root = checkpoint.header.state_root;
+    let sync_committee_root = checkpoint
+        .current_sync_committee
+        .hash_tree_root()
+        .map_err(|e| format!("failed to hash the sync committee: {e:?}"))?;
+    let branches = [
+        (
+            "current_sync_committee_branch",
+            sync_committee_root,
+            &checkpoint.current_sync_committee_branch,
+            CURRENT_SYNC_COMMITTEE_INDICES,
+        ),
+        (
+            "block_roots_branch",
+            checkpoint.block_roots_root,
+            &checkpoint.block_roots_branch,
+            BLOCK_ROOTS_INDICES,
+        ),
+    ];
+    for (name, leaf, branch, indices) in branches {
+        let (subtree_index, depth) = branch_position(name, branch.len(), indices)?;
+        if !verify_merkle_branch(leaf, branch, subtree_index, depth, state_root) {
+            return Err(format!(
+                "{name} does not prove against the header's state root"
+            ));
+        }
+    }
+    Ok(checkpoint)
+}
+
+/// The index within its subtree and the depth of whichever of `indices` matches a branch of
+/// `branch_len` nodes.
+fn branch_position(
+    name: &str,
+    branch_len: usize,
+    indices: [u64; 2],
+) -> Result<(usize, usize), String> {
+    let index = indices
+        .into_iter()
+        .find(|index| index.ilog2() as usize == branch_len)
+        .ok_or_else(|| {
+            format!(
+                "{name} has {branch_len} nodes, expected {} or {}",
+                indices[0].ilog2(),
+                indices[1].ilog2()
+            )
+        })?;
+    let depth = index.ilog2() as usize;
+    Ok(((index - (1 << depth)) as usize, depth))
+}
+
+/// Check that the header at `slot` is finalized and canonical on the beacon node, with the
+/// same root as the checkpoint.
+async fn verify_finalized(beacon_api: &str, slot: u64, header_root: &str) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(BEACON_API_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create the beacon API client: {e}"))?;
+    let finalized = beacon_get(&client, beacon_api, "/eth/v1/beacon/headers/finalized").await?;
+    let finalized_slot = header_slot(&finalized)?;
+    if slot > finalized_slot {
+        return Err(format!(
+            "Checkpoint slot {slot} is not finalized on {beacon_api} (finalized slot {finalized_slot})"
+        ));
+    }
+    let header = match slot == finalized_slot {
+        true => finalized,
+        false => {
+            beacon_get(
+                &client,
+                beacon_api,
+                &format!("/eth/v1/beacon/headers/{slot}"),
+            )
+            .await?
+        }
+    };
+    if header["data"]["canonical"] == Value::Bool(false) {
+        return Err(format!(
+            "Block at slot {slot} is not canonical on {beacon_api}"
+        ));
+    }
+    let root = header["data"]["root"]
+        .as_str()
+        .ok_or_else(|| format!("No root for slot {slot} from {beacon_api}"))?;
+    if !root.eq_ignore_ascii_case(header_root) {
+        return Err(format!(
+            "Checkpoint header root {header_root} differs from the finalized root {root} at slot \
+             {slot} on {beacon_api}"
+        ));
+    }
+    eprintln!("Checkpoint header root matches {beacon_api} (finalized slot {finalized_slot})");
+    Ok(())
+}
+
+fn header_slot(response: &Value) -> Result<u64, String> {
+    response["data"]["header"]["message"]["slot"]
+        .as_str()
+        .and_then(|slot| slot.parse().ok())
+        .ok_or_else(|| format!("Unexpected beacon API response: {response}"))
+}
+
+/// GET a JSON response from a beacon node's REST API, over HTTP or HTTPS.
+async fn beacon_get(
+    client: &reqwest::Client,
+    beacon_api: &str,
+    path: &str,
+) -> Result<Value, String> {
+    let url = format!("{}{path}", beacon_api.trim_end_matches('/'));
+    let error = |e: reqwest::Error| format!("Beacon API request to {url} failed: {e}");
+    let response = client
+        .get(&url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(error)?;
+    let status = response.status();
+    let body = response.text().await.map_err(error)?;
+    if !status.is_success() {
+        return Err(format!("{url} returned {status}: {body}"));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Invalid JSON from {url}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sp_crypto_hashing::sha2_256;
+
+    const SYNC_COMMITTEE_LEAF: usize = 22;
+    const BLOCK_ROOTS_LEAF: usize = 5;
+
+    fn hash_pair(left: &[u8], right: &[u8]) -> [u8; 32] {
+        sha2_256(&[left, right].concat())
+    }
+
+    /// Root of a power-of-two number of leaves, and the branch of `index`.
+    fn merkleize(leaves: &[[u8; 32]], index: usize) -> ([u8; 32], Vec<[u8; 32]>) {
+        let (mut level, mut index, mut branch) = (leaves.to_vec(), index, vec![]);
+        while level.len() > 1 {
+            branch.push(level[index ^ 1]);
+            level = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            index /= 2;
+        }
+        (level[0], branch)
+    }
+
+    /// SSZ `hash_tree_root` of a 48-byte BLS public key.
+    fn pubkey_root(pubkey: &[u8; 48]) -> [u8; 32] {
+        let mut chunks = [0u8; 64];
+        chunks[..48].copy_from_slice(pubkey);
+        sha2_256(&chunks)
+    }
+
+    fn hex32(bytes: &[u8]) -> String {
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    /// A checkpoint whose branches prove against a beacon state of `depth` levels: 5 before
+    /// Electra, 6 since.
+    fn checkpoint(depth: u32) -> Value {
+        let pubkeys: Vec<[u8; 48]> = (0..SYNC_COMMITTEE_SIZE).map(|i| [i as u8; 48]).collect();
+        let aggregate_pubkey = [0xaa; 48];
+        let pubkey_roots: Vec<_> = pubkeys.iter().map(pubkey_root).collect();
+        let sync_committee_root = hash_pair(
+            &merkleize(&pubkey_roots, 0).0,
+            &pubkey_root(&aggregate_pubkey),
+        );
+        let block_roots_root = [0xbb; 32];
+
+        let mut fields: Vec<[u8; 32]> = (0..1u8 << depth).map(|i| [i; 32]).collect();
+        fields[SYNC_COMMITTEE_LEAF] = sync_committee_root;
+        fields[BLOCK_ROOTS_LEAF] = block_roots_root;
+        let (state_root, sync_committee_branch) = merkleize(&fields, SYNC_COMMITTEE_LEAF);
+        let (_, block_roots_branch) = merkleize(&fields, BLOCK_ROOTS_LEAF);
+
+        json!({
+            "header": {
+                "slot": 8_000_000,
+                "proposer_index": 1,
+                "parent_root": hex32(&[1; 32]),
+                "state_root": hex32(&state_root),
+                "body_root": hex32(&[2; 32]),
+            },
+            "current_sync_committee": {
+                "pubkeys": pubkeys.iter().map(|pubkey| hex32(pubkey)).collect::<Vec<_>>(),
+                "aggregate_pubkey": hex32(&aggregate_pubkey),
+            },
+            "current_sync_committee_branch":
+                sync_committee_branch.iter().map(|node| hex32(node)).collect::<Vec<_>>(),
+            "validators_root": hex32(&[3; 32]),
+            "block_roots_root": hex32(&block_roots_root),
+            "block_roots_branch":
+                block_roots_branch.iter().map(|node| hex32(node)).collect::<Vec<_>>(),
+        })
+    }
+
+    fn verify_error(checkpoint: Value) -> String {
+        verify(&checkpoint.to_string()).unwrap_err()
+    }
+
+    #[test]
+    fn branch_position_selects_the_fork_by_branch_length() {
+        let sync_committee = CURRENT_SYNC_COMMITTEE_INDICES;
+        assert_eq!(branch_position("branch", 5, sync_committee), Ok((22, 5)));
+        assert_eq!(branch_position("branch", 6, sync_committee), Ok((22, 6)));
+        assert_eq!(
+            branch_position("branch", 5, BLOCK_ROOTS_INDICES),
+            Ok((5, 5))
+        );
+        assert_eq!(
+            branch_position("branch", 6, BLOCK_ROOTS_INDICES),
+            Ok((5, 6))
+        );
+        assert_eq!(
+            branch_position("branch", 4, sync_committee),
+            Err("branch has 4 nodes, expected 5 or 6".to_string())
+        );
+    }
+
+    #[test]
+    fn accepts_pre_electra_checkpoint() {
+        let checkpoint = verify(&checkpoint(5).to_string()).unwrap();
+        assert_eq!(checkpoint.header.slot, 8_000_000);
+    }
+
+    #[test]
+    fn accepts_electra_checkpoint() {
+        verify(&checkpoint(6).to_string()).unwrap();
+    }
+
+    #[test]
+    fn rejects_tampered_sync_committee() {
+        let mut tampered = checkpoint(6);
+        tampered["current_sync_committee"]["pubkeys"][7] = json!(hex32(&[0xff; 48]));
+        assert_eq!(
+            verify_error(tampered),
+            "current_sync_committee_branch does not prove against the header's state root"
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_block_roots_branch() {
+        let mut tampered = checkpoint(5);
+        tampered["block_roots_branch"][0] = json!(hex32(&[0xff; 32]));
+        assert_eq!(
+            verify_error(tampered),
+            "block_roots_branch does not prove against the header's state root"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_branch() {
+        let mut truncated = checkpoint(5);
+        truncated["block_roots_branch"]
+            .as_array_mut()
+            .unwrap()
+            .pop();
+        assert_eq!(
+            verify_error(truncated),
+            "block_roots_branch has 4 nodes, expected 5 or 6"
+        );
+    }
+
+    #[test]
+    fn rejects_slot_off_epoch_boundary() {
+        let mut checkpoint = checkpoint(6);
+        checkpoint["header"]["slot"] = json!(8_000_001);
+        assert_eq!(
+            verify_error(checkpoint),
+            "slot 8000001 is not at an epoch boundary (slot 8000000 starts epoch 250000)"
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_sync_committee() {
+        let mut checkpoint = checkpoint(6);
+        checkpoint["current_sync_committee"]["pubkeys"]
+            .as_array_mut()
+            .unwrap()
+            .pop();
+        assert_eq!(
+            verify_error(checkpoint),
+            "sync committee has 511 public keys, expected 512"
+        );
+    }
+}
@@ -1,42 +1,50 @@
-use crate::helpers::calculate_delivery_fee;
+use super::constants::*;
+use super::helpers::{calculate_delivery_fee, command_fees, parse_units_polkadot};
+use crate::checkpoint;
+use crate::ethereum::selector;
+use crate::initializer;
+use crate::report::{
+    CommandFeeReport, CurrentPricingReport, InitializerReport, PricingReport, UpgradeReport,
+};
+use crate::snapshot::FeeSnapshot;
 use crate::{
-    constants::*, Context, ForceCheckpointArgs, GatewayAddressArgs, GatewayOperatingModeEnum,
-    OperatingModeEnum, PricingParametersArgs, RegisterEtherArgs, UpdateAssetArgs, UpgradeArgs,
+    Context, ForceCheckpointArgs, GatewayAddressArgs, GatewayOperatingModeEnum, OperatingModeEnum,
+    PricingParametersArgs, RegisterEtherArgs, UpdateAssetArgs, UpgradeArgs,
 };
-use alloy_primitives::{utils::format_units, U256};
-use codec::Encode;
+use alloy_primitives::{keccak256, utils::format_units, Address, U256};
+use codec::{Decode, Encode};
 use snowbridge_router_primitives::inbound::GlobalConsensusEthereumConvertsFor;
 use sp_arithmetic::FixedU128;
 use sp_crypto_hashing::twox_128;
-use std::{fs::File, io::Read};
-use subxt::utils::MultiAddress;
 use subxt::utils::Static;
+use subxt::utils::{MultiAddress, H256};
 
-type CheckpointUpdate = snowbridge_beacon_primitives::CheckpointUpdate<512>;
-
-use crate::asset_hub_runtime::runtime_types::pallet_assets;
-use crate::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::asset_hub_runtime::runtime_types::pallet_assets;
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
 
-use crate::bridge_hub_runtime::runtime_types::{
+use super::bridge_hub_runtime::runtime_types::{
     snowbridge_core::{
         operating_mode::BasicOperatingMode,
         pricing::{PricingParameters, Rewards},
     },
-    snowbridge_outbound_queue_primitives::{v1::message::Initializer, OperatingMode},
+    snowbridge_outbound_queue_primitives::{
+        v1::message::{Fee, Initializer},
+        v2::message::Initializer as InitializerV2,
+        OperatingMode,
+    },
     snowbridge_pallet_ethereum_client, snowbridge_pallet_inbound_queue,
-    snowbridge_pallet_inbound_queue_v2, snowbridge_pallet_outbound_queue,
-    snowbridge_pallet_system, snowbridge_pallet_system_v2,
+    snowbridge_pallet_inbound_queue_v2, snowbridge_pallet_outbound_queue, snowbridge_pallet_system,
+    snowbridge_pallet_system_v2,
 };
-use crate::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
 
-use crate::asset_hub_runtime::runtime_types::{
+use super::asset_hub_runtime::runtime_types::{
     snowbridge_core::operating_mode::BasicOperatingMode as AssetHubBasicOperatingMode,
     snowbridge_pallet_system_frontend,
 };
 
-#[cfg(feature = "polkadot")]
-pub mod asset_hub_polkadot_types {
-    pub use crate::asset_hub_runtime::runtime_types::staging_xcm::v5::{
+pub mod asset_hub_types {
+    pub use super::super::asset_hub_runtime::runtime_types::staging_xcm::v5::{
         junction::Junction::AccountKey20,
         junction::Junction::GlobalConsensus,
         junction::NetworkId,
@@ -61,59 +69,6 @@ pub mod asset_hub_polkadot_types {
     }
 }
 
-#[cfg(feature = "paseo")]
-pub mod asset_hub_paseo_types {
-    pub use crate::asset_hub_runtime::runtime_types::staging_xcm::v5::{
-        junction::Junction::AccountKey20,
-        junction::Junction::GlobalConsensus,
-        junction::NetworkId,
-        junctions::Junctions::{X1, X2},
-        location::Location,
-    };
-
-    pub fn get_ether_id(chain_id: u64) -> Location {
-        return Location {
-            parents: 2,
-            interior: X1([GlobalConsensus(NetworkId::Ethereum { chain_id })]),
-        };
-    }
-    pub fn get_asset_id(chain_id: u64, key: [u8; 20]) -> Location {
-        return Location {
-            parents: 2,
-            interior: X2([
-                GlobalConsensus(NetworkId::Ethereum { chain_id }),
-                AccountKey20 { network: None, key },
-            ]),
-        };
-    }
-}
-
-#[cfg(feature = "westend")]
-pub mod asset_hub_westend_types {
-    pub use crate::asset_hub_runtime::runtime_types::staging_xcm::v5::{
-        junction::Junction::AccountKey20,
-        junction::Junction::GlobalConsensus,
-        junction::NetworkId,
-        junctions::Junctions::{X1, X2},
-        location::Location,
-    };
-    pub fn get_ether_id(chain_id: u64) -> Location {
-        return Location {
-            parents: 2,
-            interior: X1([GlobalConsensus(NetworkId::Ethereum { chain_id })]),
-        };
-    }
-    pub fn get_asset_id(chain_id: u64, key: [u8; 20]) -> Location {
-        return Location {
-            parents: 2,
-            interior: X2([
-                GlobalConsensus(NetworkId::Ethereum { chain_id }),
-                AccountKey20 { network: None, key },
-            ]),
-        };
-    }
-}
-
 pub fn gateway_operating_mode(operating_mode: &GatewayOperatingModeEnum) -> BridgeHubRuntimeCall {
     let mode = match operating_mode {
         GatewayOperatingModeEnum::Normal => OperatingMode::Normal,
@@ -201,21 +156,160 @@ pub fn system_frontend_operating_mode(param: &OperatingModeEnum) -> AssetHubRunt
     )
 }
 
-pub fn upgrade(params: &UpgradeArgs) -> BridgeHubRuntimeCall {
-    BridgeHubRuntimeCall::EthereumSystem(snowbridge_pallet_system::pallet::Call::upgrade {
-        impl_address: params.logic_address.into_array().into(),
-        impl_code_hash: params.logic_code_hash.0.into(),
-        initializer: Some(Initializer {
-            params: params.initializer_params.clone().into(),
-            maximum_required_gas: params.initializer_gas,
-        }),
-    })
+pub async fn upgrade(
+    context: &Context,
+    params: &UpgradeArgs,
+) -> Result<BridgeHubRuntimeCall, Box<dyn std::error::Error>> {
+    let gateway = verify_logic_code(context, params).await?;
+
+    let (initializer_params, decoded) = match (&params.initializer_abi, &params.initializer_type) {
+        (Some(abi), Some(name)) => {
+            let args = params.initializer_args.clone().unwrap_or_default();
+            let initializer = initializer::encode(abi, name, &args)?;
+            eprintln!("Initializer params, encoded as the inputs of '{name}':");
+            for line in initializer.decoded.iter() {
+                eprintln!("  {line}");
+            }
+            (initializer.params, initializer.decoded)
+        }
+        _ => (
+            params
+                .initializer_params
+                .clone()
+                .unwrap_or_default()
+                .to_vec(),
+            vec![],
+        ),
+    };
+    let estimated = params.initializer_gas.is_none();
+    let initializer_gas = match (params.initializer_gas, gateway) {
+        (Some(gas), _) => gas,
+        (None, Some(gateway)) => {
+            let estimate = initializer::estimate_gas(
+                context.ethereum_api()?,
+                gateway,
+                params.logic_address,
+                params.logic_code_hash,
+                &initializer_params,
+                params.v2,
+            )
+            .await?;
+            let margin = params.initializer_gas_margin;
+            let gas = initializer::with_margin(estimate, margin);
+            eprintln!("Initializer gas: {gas} (estimated {estimate} on {gateway}, plus {margin}%)");
+            gas
+        }
+        (None, None) => {
+            return Err("--initializer-gas is required offline or with --skip-code-check".into())
+        }
+    };
+
+    context.report.borrow_mut().initializer = Some(InitializerReport {
+        params: format!("0x{}", hex::encode(&initializer_params)),
+        decoded,
+        maximum_required_gas: initializer_gas,
+        estimated,
+        gas_margin_percent: estimated.then_some(params.initializer_gas_margin),
+    });
+
+    if params.v2 {
+        return Ok(BridgeHubRuntimeCall::EthereumSystemV2(
+            snowbridge_pallet_system_v2::pallet::Call::upgrade {
+                impl_address: params.logic_address.into_array().into(),
+                impl_code_hash: params.logic_code_hash.0.into(),
+                initializer: InitializerV2 {
+                    params: initializer_params,
+                    maximum_required_gas: initializer_gas,
+                },
+            },
+        ));
+    }
+    Ok(BridgeHubRuntimeCall::EthereumSystem(
+        snowbridge_pallet_system::pallet::Call::upgrade {
+            impl_address: params.logic_address.into_array().into(),
+            impl_code_hash: params.logic_code_hash.0.into(),
+            initializer: Some(Initializer {
+                params: initializer_params,
+                maximum_required_gas: initializer_gas,
+            }),
+        },
+    ))
+}
+
+/// Check that the code deployed at the logic address hashes to `logic_code_hash`, as the
+/// Gateway rejects the upgrade otherwise, but only after governance has enacted it. This only
+/// needs `--ethereum-api`, and is skipped only with `--skip-code-check`. With a BridgeHub
+/// connection, also reports the Gateway's current implementation and returns the Gateway
+/// address.
+async fn verify_logic_code(
+    context: &Context,
+    params: &UpgradeArgs,
+) -> Result<Option<Address>, Box<dyn std::error::Error>> {
+    let logic_address = params.logic_address;
+    if params.skip_code_check {
+        eprintln!("Warning: not verifying the code hash of {logic_address} (--skip-code-check)");
+        return Ok(None);
+    }
+    let ethereum = context.ethereum_api().map_err(|_| {
+        format!(
+            "Verifying the code hash of {logic_address} requires --ethereum-api, \
+             or pass --skip-code-check"
+        )
+    })?;
+
+    let code = ethereum.get_code(logic_address).await?;
+    if code.is_empty() {
+        return Err(format!("No code is deployed at {logic_address}").into());
+    }
+    let code_hash = keccak256(&code);
+    if code_hash != params.logic_code_hash {
+        return Err(format!(
+            "Code at {logic_address} hashes to {code_hash}, not {}",
+            params.logic_code_hash
+        )
+        .into());
+    }
+    eprintln!("Code at {logic_address} matches {code_hash}");
+
+    let Ok(bridge_hub) = context.bridge_hub_api() else {
+        eprintln!("Offline: not reading the Gateway address from BridgeHub");
+        return Ok(None);
+    };
+    let gateway = bridge_hub
+        .storage()
+        .at_latest()
+        .await?
+        .fetch_raw(twox_128(b":EthereumGatewayAddress:").to_vec())
+        .await?
+        .ok_or("EthereumGatewayAddress is not set on BridgeHub")?;
+    let gateway = Address::from(<[u8; 20]>::decode(&mut &gateway[..])?);
+    let implementation = ethereum
+        .call(gateway, &selector("implementation()"))
+        .await?;
+    if implementation.len() != 32 {
+        return Err(format!("Gateway {gateway} returned an invalid implementation()").into());
+    }
+    let implementation = Address::from_slice(&implementation[12..]);
+    eprintln!("Gateway {gateway} implementation: {implementation}");
+    if implementation == logic_address {
+        eprintln!("Warning: {logic_address} is already the Gateway implementation");
+    }
+
+    context.report.borrow_mut().upgrade = Some(UpgradeReport {
+        gateway: gateway.to_string(),
+        current_implementation: implementation.to_string(),
+        logic_address: logic_address.to_string(),
+        logic_code_hash: code_hash.to_string(),
+    });
+    Ok(Some(gateway))
 }
 
 pub async fn pricing_parameters(
     context: &Context,
     params: &PricingParametersArgs,
 ) -> Result<(BridgeHubRuntimeCall, AssetHubRuntimeCall), Box<dyn std::error::Error>> {
+    let local_reward = parse_units_polkadot(&params.local_reward)?;
+
     // BridgeHub parameters
     let pricing_params: PricingParameters<u128> = PricingParameters {
         exchange_rate: Static(FixedU128::from_rational(
@@ -226,53 +320,34 @@ pub async fn pricing_parameters(
             params.multiplier_numerator.into(),
             params.multiplier_denominator.into(),
         )),
-        fee_per_gas: crate::bridge_hub_runtime::runtime_types::primitive_types::U256(
+        fee_per_gas: super::bridge_hub_runtime::runtime_types::primitive_types::U256(
             params.fee_per_gas.into_limbs(),
         ),
         rewards: Rewards {
-            local: params.local_reward.to::<u128>(),
-            remote: crate::bridge_hub_runtime::runtime_types::primitive_types::U256(
+            local: local_reward,
+            remote: super::bridge_hub_runtime::runtime_types::primitive_types::U256(
                 params.remote_reward.into_limbs(),
             ),
         },
     };
 
     let outbound_delivery_fee =
-        calculate_delivery_fee(&context.bridge_hub_api, &pricing_params).await?;
+        calculate_delivery_fee(context.bridge_hub_api()?, &pricing_params).await?;
 
     let total_outbound_fee = outbound_delivery_fee.local + outbound_delivery_fee.remote;
 
     // Adjust outbound fee up by 10% as a buffer
     let total_outbound_fee_adjusted = total_outbound_fee.saturating_add(total_outbound_fee / 10);
 
-    eprintln!("BridgeHub:");
-    eprintln!(
-        "  ExchangeRate: {} ETH/{}",
-        params.exchange_rate_numerator as f64 / params.exchange_rate_denominator as f64,
-        POLKADOT_SYMBOL
-    );
-    eprintln!(
-        "  FeePerGas: {} GWEI",
-        format_units(params.fee_per_gas, "gwei").unwrap(),
-    );
-    eprintln!(
-        "  LocalReward: {} {} [{} PLANCK]",
-        format_units(U256::from(params.local_reward), POLKADOT_DECIMALS).unwrap(),
-        POLKADOT_SYMBOL,
-        params.local_reward,
-    );
-    eprintln!(
-        "  RemoteReward: {} ETH [{} WEI]",
-        format_units(params.remote_reward, "eth").unwrap(),
-        params.remote_reward
-    );
-    eprintln!("AssetHub:");
-    eprintln!(
-        "  BaseFee: {} {}, [{} PLANCK]",
-        format_units(U256::from(total_outbound_fee_adjusted), POLKADOT_DECIMALS).unwrap(),
-        POLKADOT_SYMBOL,
-        total_outbound_fee_adjusted
+    let current = current_pricing(context, params).await?;
+    print_pricing_diff(
+        &current,
+        &pricing_params,
+        total_outbound_fee,
+        total_outbound_fee_adjusted,
     );
+    let command_fees = command_fees(context.bridge_hub_api()?, &pricing_params).await?;
+    print_command_fees(&command_fees, total_outbound_fee_adjusted);
 
     // AssetHub parameters
     let asset_hub_outbound_fee_storage_key: Vec<u8> =
@@ -284,6 +359,43 @@ pub async fn pricing_parameters(
         hex::encode(&asset_hub_outbound_fee_storage_key)
     );
 
+    context.report.borrow_mut().pricing.push(PricingReport {
+        exchange_rate_numerator: params.exchange_rate_numerator,
+        exchange_rate_denominator: params.exchange_rate_denominator,
+        multiplier_numerator: params.multiplier_numerator,
+        multiplier_denominator: params.multiplier_denominator,
+        fee_per_gas: params.fee_per_gas.to_string(),
+        local_reward: local_reward.to_string(),
+        remote_reward: params.remote_reward.to_string(),
+        delivery_fee_local: outbound_delivery_fee.local.to_string(),
+        delivery_fee_remote: outbound_delivery_fee.remote.to_string(),
+        asset_hub_base_fee: total_outbound_fee_adjusted.to_string(),
+        asset_hub_base_fee_storage_key: format!(
+            "0x{}",
+            hex::encode(&asset_hub_outbound_fee_storage_key)
+        ),
+        current: CurrentPricingReport {
+            bridge_hub_block: format!("0x{}", hex::encode(current.bridge_hub_block)),
+            asset_hub_block: format!("0x{}", hex::encode(current.asset_hub_block)),
+            exchange_rate: fixed_point(&current.params.exchange_rate),
+            multiplier: fixed_point(&current.params.multiplier),
+            fee_per_gas: U256::from_limbs(current.params.fee_per_gas.0).to_string(),
+            local_reward: current.params.rewards.local.to_string(),
+            remote_reward: U256::from_limbs(current.params.rewards.remote.0).to_string(),
+            delivery_fee_local: current.delivery_fee.local.to_string(),
+            delivery_fee_remote: current.delivery_fee.remote.to_string(),
+            asset_hub_base_fee: current.asset_hub_base_fee.map(|fee| fee.to_string()),
+        },
+        command_fees: command_fees
+            .iter()
+            .map(|(command, fee)| CommandFeeReport {
+                command: command.to_string(),
+                local: fee.local.to_string(),
+                remote: fee.remote.to_string(),
+            })
+            .collect(),
+    });
+
     Ok((
         BridgeHubRuntimeCall::EthereumSystem(
             snowbridge_pallet_system::pallet::Call::set_pricing_parameters {
@@ -291,7 +403,7 @@ pub async fn pricing_parameters(
             },
         ),
         AssetHubRuntimeCall::System(
-            crate::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
+            super::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
                 items: vec![(
                     asset_hub_outbound_fee_storage_key,
                     asset_hub_outbound_fee_encoded,
@@ -301,6 +413,233 @@ pub async fn pricing_parameters(
     ))
 }
 
+/// Pricing on chain before the proposal is enacted.
+struct CurrentPricing {
+    bridge_hub_block: H256,
+    asset_hub_block: H256,
+    params: PricingParameters<u128>,
+    /// Delivery fee for the current parameters, calculated by the latest BridgeHub runtime
+    delivery_fee: Fee<u128>,
+    asset_hub_base_fee: Option<u128>,
+}
+
+/// Read the current `EthereumSystem::PricingParameters` and AssetHub `BridgeHubEthereumBaseFee`
+/// at the blocks given by `--bridge-hub-at` and `--asset-hub-at`, or the latest blocks.
+async fn current_pricing(
+    context: &Context,
+    params: &PricingParametersArgs,
+) -> Result<CurrentPricing, Box<dyn std::error::Error>> {
+    let bridge_hub = context.bridge_hub_api()?;
+    let bridge_hub_block = match params.bridge_hub_at {
+        Some(hash) => H256(hash.0),
+        None => bridge_hub.blocks().at_latest().await?.hash(),
+    };
+    let current_params = bridge_hub
+        .storage()
+        .at(bridge_hub_block)
+        .fetch_or_default(
+            &super::bridge_hub_runtime::storage()
+                .ethereum_system()
+                .pricing_parameters(),
+        )
+        .await?;
+    let delivery_fee = calculate_delivery_fee(bridge_hub, &current_params).await?;
+
+    let asset_hub = context.asset_hub_api()?;
+    let asset_hub_block = match params.asset_hub_at {
+        Some(hash) => H256(hash.0),
+        None => asset_hub.blocks().at_latest().await?.hash(),
+    };
+    let asset_hub_base_fee = match asset_hub
+        .storage()
+        .at(asset_hub_block)
+        .fetch_raw(twox_128(b":BridgeHubEthereumBaseFee:").to_vec())
+        .await?
+    {
+        Some(value) => Some(u128::decode(&mut &value[..])?),
+        None => None,
+    };
+
+    Ok(CurrentPricing {
+        bridge_hub_block,
+        asset_hub_block,
+        params: current_params,
+        delivery_fee,
+        asset_hub_base_fee,
+    })
+}
+
+/// Print the current and proposed pricing side by side, with the percentage change.
+fn print_pricing_diff(
+    current: &CurrentPricing,
+    proposed: &PricingParameters<u128>,
+    delivery_fee: u128,
+    asset_hub_base_fee: u128,
+) {
+    let polkadot = |amount: u128| format_units(U256::from(amount), POLKADOT_DECIMALS).unwrap();
+    let ether = |amount: U256| format_units(amount, "ether").unwrap();
+    let gwei = |amount: U256| format_units(amount, "gwei").unwrap();
+    let current_delivery_fee = current.delivery_fee.local + current.delivery_fee.remote;
+
+    let rows = [
+        (
+            format!("ExchangeRate (ETH/{POLKADOT_SYMBOL})"),
+            Some(fixed_point(&current.params.exchange_rate)),
+            fixed_point(&proposed.exchange_rate),
+            Some(U256::from(current.params.exchange_rate.0.into_inner())),
+            U256::from(proposed.exchange_rate.0.into_inner()),
+        ),
+        (
+            "Multiplier".to_string(),
+            Some(fixed_point(&current.params.multiplier)),
+            fixed_point(&proposed.multiplier),
+            Some(U256::from(current.params.multiplier.0.into_inner())),
+            U256::from(proposed.multiplier.0.into_inner()),
+        ),
+        (
+            "FeePerGas (GWEI)".to_string(),
+            Some(gwei(U256::from_limbs(current.params.fee_per_gas.0))),
+            gwei(U256::from_limbs(proposed.fee_per_gas.0)),
+            Some(U256::from_limbs(current.params.fee_per_gas.0)),
+            U256::from_limbs(proposed.fee_per_gas.0),
+        ),
+        (
+            format!("LocalReward ({POLKADOT_SYMBOL})"),
+            Some(polkadot(current.params.rewards.local)),
+            polkadot(proposed.rewards.local),
+            Some(U256::from(current.params.rewards.local)),
+            U256::from(proposed.rewards.local),
+        ),
+        (
+            "RemoteReward (ETH)".to_string(),
+            Some(ether(U256::from_limbs(current.params.rewards.remote.0))),
+            ether(U256::from_limbs(proposed.rewards.remote.0)),
+            Some(U256::from_limbs(current.params.rewards.remote.0)),
+            U256::from_limbs(proposed.rewards.remote.0),
+        ),
+        (
+            format!("DeliveryFee ({POLKADOT_SYMBOL})"),
+            Some(polkadot(current_delivery_fee)),
+            polkadot(delivery_fee),
+            Some(U256::from(current_delivery_fee)),
+            U256::from(delivery_fee),
+        ),
+        (
+            "DeliveryFee (ETH)".to_string(),
+            Some(ether(to_ether(current_delivery_fee, &current.params))),
+            ether(to_ether(delivery_fee, proposed)),
+            Some(to_ether(current_delivery_fee, &current.params)),
+            to_ether(delivery_fee, proposed),
+        ),
+        (
+            format!("AssetHub BaseFee ({POLKADOT_SYMBOL})"),
+            current.asset_hub_base_fee.map(polkadot),
+            polkadot(asset_hub_base_fee),
+            current.asset_hub_base_fee.map(U256::from),
+            U256::from(asset_hub_base_fee),
+        ),
+    ];
+
+    eprintln!(
+        "BridgeHub block: 0x{}",
+        hex::encode(current.bridge_hub_block)
+    );
+    eprintln!("AssetHub block: 0x{}", hex::encode(current.asset_hub_block));
+    eprintln!(
+        "{:<24} {:>28} {:>28} {:>10}",
+        "", "Current", "Proposed", "Change"
+    );
+    for (name, before, after, before_value, after_value) in rows {
+        let change = match before_value {
+            Some(value) if !value.is_zero() => format!(
+                "{:+.2}%",
+                (f64::from(after_value) - f64::from(value)) / f64::from(value) * 100.0
+            ),
+            _ => "-".to_string(),
+        };
+        eprintln!(
+            "{name:<24} {:>28} {after:>28} {change:>10}",
+            before.unwrap_or_else(|| "unset".to_string())
+        );
+    }
+}
+
+/// Print the proposed delivery fee of each kind of V1 command next to the AssetHub base fee,
+/// which users pay for any of them.
+fn print_command_fees(fees: &[(&str, Fee<u128>)], asset_hub_base_fee: u128) {
+    let polkadot = |amount: u128| format_units(U256::from(amount), POLKADOT_DECIMALS).unwrap();
+    eprintln!(
+        "{:<24} {:>20} {:>20} {:>20} {:>20} {:>10}",
+        format!("Fee ({POLKADOT_SYMBOL})"),
+        "Local",
+        "Remote",
+        "Total",
+        "AssetHub BaseFee",
+        "Margin"
+    );
+    for (command, fee) in fees {
+        let total = fee.local + fee.remote;
+        let margin = match total {
+            0 => "-".to_string(),
+            _ => format!(
+                "{:+.2}%",
+                (asset_hub_base_fee as f64 - total as f64) / total as f64 * 100.0
+            ),
+        };
+        eprintln!(
+            "{command:<24} {:>20} {:>20} {:>20} {:>20} {margin:>10}",
+            polkadot(fee.local),
+            polkadot(fee.remote),
+            polkadot(total),
+            polkadot(asset_hub_base_fee),
+        );
+    }
+}
+
+/// Decimal representation of a `FixedU128`.
+fn fixed_point(value: &Static<FixedU128>) -> String {
+    format_units(U256::from(value.0.into_inner()), 18).unwrap()
+}
+
+/// Convert an amount in planck to wei at the exchange rate (ETH per relay chain token) of
+/// `params`.
+fn to_ether(amount: u128, params: &PricingParameters<u128>) -> U256 {
+    U256::from(amount) * U256::from(params.exchange_rate.0.into_inner())
+        / U256::from(10u64).pow(U256::from(POLKADOT_DECIMALS))
+}
+
+/// Read the current AssetHub outbound fees (V1 and V2) at block `at`, or the latest block.
+pub async fn asset_hub_fees(
+    context: &Context,
+    at: Option<H256>,
+) -> Result<FeeSnapshot, Box<dyn std::error::Error>> {
+    let api = context.asset_hub_api()?;
+    let block_hash = match at {
+        Some(hash) => hash,
+        None => api.blocks().at_latest().await?.hash(),
+    };
+    let storage = api.storage().at(block_hash);
+    let mut fees = vec![];
+    for key in [
+        &b":BridgeHubEthereumBaseFee:"[..],
+        &b":BridgeHubEthereumBaseFeeV2:"[..],
+    ] {
+        let fee = match storage.fetch_raw(twox_128(key).to_vec()).await? {
+            Some(value) => Some(u128::decode(&mut &value[..])?.to_string()),
+            None => None,
+        };
+        fees.push(fee);
+    }
+    let base_fee_v2 = fees.pop().unwrap();
+    let base_fee = fees.pop().unwrap();
+    Ok(FeeSnapshot {
+        network: NETWORK.to_string(),
+        block: format!("0x{}", hex::encode(block_hash)),
+        base_fee,
+        base_fee_v2,
+    })
+}
+
 pub fn set_assethub_fee(fee: u128) -> AssetHubRuntimeCall {
     let asset_hub_outbound_fee_storage_key: Vec<u8> =
         twox_128(b":BridgeHubEthereumBaseFee:").to_vec();
@@ -312,7 +651,7 @@ pub fn set_assethub_fee(fee: u128) -> AssetHubRuntimeCall {
     );
 
     AssetHubRuntimeCall::System(
-        crate::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
+        super::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
             items: vec![(
                 asset_hub_outbound_fee_storage_key,
                 asset_hub_outbound_fee_encoded,
@@ -332,7 +671,7 @@ pub fn set_assethub_fee_v2(fee: u128) -> AssetHubRuntimeCall {
     );
 
     AssetHubRuntimeCall::System(
-        crate::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
+        super::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
             items: vec![(
                 asset_hub_outbound_fee_storage_key,
                 asset_hub_outbound_fee_encoded,
@@ -341,24 +680,22 @@ pub fn set_assethub_fee_v2(fee: u128) -> AssetHubRuntimeCall {
     )
 }
 
-pub fn force_checkpoint(params: &ForceCheckpointArgs) -> BridgeHubRuntimeCall {
-    let mut file = File::open(params.checkpoint.clone()).expect("File not found");
-    let mut data = String::new();
-    file.read_to_string(&mut data)
-        .expect("Failed to read the file");
-    let checkpoint: CheckpointUpdate = serde_json::from_str(&data).unwrap();
-    BridgeHubRuntimeCall::EthereumBeaconClient(
+pub async fn force_checkpoint(
+    params: &ForceCheckpointArgs,
+) -> Result<BridgeHubRuntimeCall, Box<dyn std::error::Error>> {
+    let checkpoint = checkpoint::load(&params.checkpoint, params.beacon_api.as_deref()).await?;
+    Ok(BridgeHubRuntimeCall::EthereumBeaconClient(
         snowbridge_pallet_ethereum_client::pallet::Call::force_checkpoint {
             update: Box::new(Static(checkpoint)),
         },
-    )
+    ))
 }
 
 pub fn set_gateway_address(params: &GatewayAddressArgs) -> BridgeHubRuntimeCall {
     let storage_key = sp_crypto_hashing::twox_128(b":EthereumGatewayAddress:").to_vec();
     let storage_value = params.gateway_address.into_array().encode();
     BridgeHubRuntimeCall::System(
-        crate::bridge_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
+        super::bridge_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
             items: vec![(storage_key, storage_value)],
         },
     )
@@ -366,13 +703,8 @@ pub fn set_gateway_address(params: &GatewayAddressArgs) -> BridgeHubRuntimeCall
 
 pub fn make_asset_sufficient(params: &UpdateAssetArgs) -> AssetHubRuntimeCall {
     use subxt::utils::AccountId32;
-    let chain_id = crate::bridge_hub_runtime::CHAIN_ID;
-    #[cfg(feature = "paseo")]
-    use asset_hub_paseo_types::*;
-    #[cfg(feature = "polkadot")]
-    use asset_hub_polkadot_types::*;
-    #[cfg(feature = "westend")]
-    use asset_hub_westend_types::*;
+    let chain_id = super::bridge_hub_runtime::CHAIN_ID;
+    use asset_hub_types::*;
     let asset_id = get_asset_id(chain_id, params.contract_id.into_array().into());
     let owner = GlobalConsensusEthereumConvertsFor::<[u8; 32]>::from_chain_id(&chain_id);
     AssetHubRuntimeCall::ForeignAssets(pallet_assets::pallet::Call2::force_asset_status {
@@ -388,13 +720,8 @@ pub fn make_asset_sufficient(params: &UpdateAssetArgs) -> AssetHubRuntimeCall {
 }
 
 pub fn force_set_metadata(params: &UpdateAssetArgs) -> AssetHubRuntimeCall {
-    let chain_id = crate::bridge_hub_runtime::CHAIN_ID;
-    #[cfg(feature = "paseo")]
-    use asset_hub_paseo_types::*;
-    #[cfg(feature = "polkadot")]
-    use asset_hub_polkadot_types::*;
-    #[cfg(feature = "westend")]
-    use asset_hub_westend_types::*;
+    let chain_id = super::bridge_hub_runtime::CHAIN_ID;
+    use asset_hub_types::*;
     let asset_id = get_asset_id(chain_id, params.contract_id.into_array().into());
     AssetHubRuntimeCall::ForeignAssets(pallet_assets::pallet::Call2::force_set_metadata {
         id: asset_id,
@@ -407,13 +734,8 @@ pub fn force_set_metadata(params: &UpdateAssetArgs) -> AssetHubRuntimeCall {
 
 pub fn register_ether(params: &RegisterEtherArgs) -> (AssetHubRuntimeCall, AssetHubRuntimeCall) {
     use subxt::utils::AccountId32;
-    let chain_id = crate::bridge_hub_runtime::CHAIN_ID;
-    #[cfg(feature = "paseo")]
-    use asset_hub_paseo_types::*;
-    #[cfg(feature = "polkadot")]
-    use asset_hub_polkadot_types::*;
-    #[cfg(feature = "westend")]
-    use asset_hub_westend_types::*;
+    let chain_id = super::bridge_hub_runtime::CHAIN_ID;
+    use asset_hub_types::*;
 
     let asset_id = get_ether_id(chain_id);
     let owner = GlobalConsensusEthereumConvertsFor::<[u8; 32]>::from_chain_id(&chain_id);
@@ -437,14 +759,13 @@ pub fn register_ether(params: &RegisterEtherArgs) -> (AssetHubRuntimeCall, Asset
     return (force_register, metadata);
 }
 
-#[cfg(feature = "polkadot")]
 fn register_polkadot_native_asset(
-    location: crate::bridge_hub_runtime::runtime_types::xcm::VersionedLocation,
+    location: super::bridge_hub_runtime::runtime_types::xcm::VersionedLocation,
     name: &'static str,
     symbol: &'static str,
     decimals: u8,
 ) -> BridgeHubRuntimeCall {
-    use crate::bridge_hub_runtime::runtime_types::{bounded_collections, snowbridge_core};
+    use super::bridge_hub_runtime::runtime_types::{bounded_collections, snowbridge_core};
 
     let call = BridgeHubRuntimeCall::EthereumSystem(
         snowbridge_pallet_system::pallet::Call::register_token {
@@ -460,7 +781,6 @@ fn register_polkadot_native_asset(
     return call;
 }
 
-#[cfg(feature = "polkadot")]
 pub fn register_erc20_token_metadata() -> Vec<AssetHubRuntimeCall> {
     use alloy_primitives::Address;
     use hex_literal::hex;
@@ -617,9 +937,8 @@ pub fn register_erc20_token_metadata() -> Vec<AssetHubRuntimeCall> {
         .collect()
 }
 
-#[cfg(feature = "polkadot")]
 pub fn token_registrations() -> Vec<BridgeHubRuntimeCall> {
-    use crate::bridge_hub_runtime::runtime_types::{
+    use super::bridge_hub_runtime::runtime_types::{
         staging_xcm::v5::{
             junction::Junction::*, junction::NetworkId::*, junctions::Junctions::*,
             location::Location,
@@ -901,8 +1220,8 @@ pub fn token_registrations() -> Vec<BridgeHubRuntimeCall> {
     ];
 }
 
-pub fn replay_sep_2025_xcm() -> crate::asset_hub_runtime::RuntimeCall {
-    use crate::asset_hub_runtime::runtime_types::{
+pub fn replay_sep_2025_xcm() -> super::asset_hub_runtime::RuntimeCall {
+    use super::asset_hub_runtime::runtime_types::{
         pallet_xcm,
         staging_xcm::v5::{
             asset::{Asset, AssetId, Assets, Fungibility, WildAsset},
@@ -989,8 +1308,8 @@ pub fn replay_sep_2025_xcm() -> crate::asset_hub_runtime::RuntimeCall {
         };
 
         all_instructions.push(ExportMessage {
-            network: crate::asset_hub_runtime::runtime_types::staging_xcm::v5::junction::NetworkId::Ethereum {
-                chain_id: crate::bridge_hub_runtime::CHAIN_ID,
+            network: super::asset_hub_runtime::runtime_types::staging_xcm::v5::junction::NetworkId::Ethereum {
+                chain_id: super::bridge_hub_runtime::CHAIN_ID,
             },
             destination: Junctions::Here,
             xcm: Xcm(vec![
@@ -1007,7 +1326,7 @@ pub fn replay_sep_2025_xcm() -> crate::asset_hub_runtime::RuntimeCall {
                     weight_limit: WeightLimit::Unlimited,
                 },
                 DepositAsset {
-                    assets: crate::asset_hub_runtime::runtime_types::staging_xcm::v5::asset::AssetFilter::Wild(WildAsset::AllCounted(1)),
+                    assets: super::asset_hub_runtime::runtime_types::staging_xcm::v5::asset::AssetFilter::Wild(WildAsset::AllCounted(1)),
                     beneficiary: Location {
                         parents: 0,
                         interior: Junctions::X1([Junction::AccountKey20 {
@@ -1023,10 +1342,10 @@ pub fn replay_sep_2025_xcm() -> crate::asset_hub_runtime::RuntimeCall {
     }
 
     let asset_hub_xcm =
-        crate::asset_hub_runtime::RuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send {
+        super::asset_hub_runtime::RuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send {
             dest: Box::new(VersionedLocation::V5(Location {
                 parents: 1,
-                interior: Junctions::X1([Junction::Parachain(crate::constants::BRIDGE_HUB_ID)]),
+                interior: Junctions::X1([Junction::Parachain(super::constants::BRIDGE_HUB_ID)]),
             })),
             message: Box::new(VersionedXcm::V5(Xcm(all_instructions))),
         });
@@ -1040,7 +1359,7 @@ pub fn replay_sep_2025_xcm() -> crate::asset_hub_runtime::RuntimeCall {
 /// This sends XCM from BridgeHub→AssetHub with ReserveAssetDeposited to mint
 /// the USDT back to the beneficiary.
 pub fn mint_feb_2026_xcm() -> BridgeHubRuntimeCall {
-    use crate::bridge_hub_runtime::runtime_types::{
+    use super::bridge_hub_runtime::runtime_types::{
         pallet_xcm,
         staging_xcm::v5::{
             asset::{Asset, AssetFilter, AssetId, Assets, Fungibility, WildAsset},
@@ -1068,8 +1387,8 @@ pub fn mint_feb_2026_xcm() -> BridgeHubRuntimeCall {
         parents: 2,
         interior: Junctions::X2([
             Junction::GlobalConsensus(
-                crate::bridge_hub_runtime::runtime_types::staging_xcm::v5::junction::NetworkId::Ethereum {
-                    chain_id: crate::bridge_hub_runtime::CHAIN_ID,
+                super::bridge_hub_runtime::runtime_types::staging_xcm::v5::junction::NetworkId::Ethereum {
+                    chain_id: super::bridge_hub_runtime::CHAIN_ID,
                 },
             ),
             Junction::AccountKey20 {
@@ -1114,15 +1433,14 @@ pub fn mint_feb_2026_xcm() -> BridgeHubRuntimeCall {
     BridgeHubRuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send {
         dest: Box::new(VersionedLocation::V5(Location {
             parents: 1,
-            interior: Junctions::X1([Junction::Parachain(crate::constants::ASSET_HUB_ID)]),
+            interior: Junctions::X1([Junction::Parachain(super::constants::ASSET_HUB_ID)]),
         })),
         message: Box::new(VersionedXcm::V5(Xcm(instructions))),
     })
 }
 
-#[cfg(feature = "polkadot")]
 pub fn frequency_token_registrations() -> Vec<BridgeHubRuntimeCall> {
-    use crate::bridge_hub_runtime::runtime_types::{
+    use super::bridge_hub_runtime::runtime_types::{
         staging_xcm::v5::{junction::Junction::*, junctions::Junctions::*, location::Location},
         xcm::VersionedLocation,
     };
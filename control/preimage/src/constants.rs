@@ -1,5 +1,63 @@
-#[cfg(feature = "polkadot")]
-mod polkadot {
+use clap::ValueEnum;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+pub enum Network {
+    Polkadot,
+    Westend,
+    Paseo,
+}
+
+impl Network {
+    pub fn asset_hub_api(&self) -> &'static str {
+        match self {
+            Network::Polkadot => polkadot::ASSET_HUB_API,
+            Network::Westend => westend::ASSET_HUB_API,
+            Network::Paseo => paseo::ASSET_HUB_API,
+        }
+    }
+
+    pub fn bridge_hub_api(&self) -> &'static str {
+        match self {
+            Network::Polkadot => polkadot::BRIDGE_HUB_API,
+            Network::Westend => westend::BRIDGE_HUB_API,
+            Network::Paseo => paseo::BRIDGE_HUB_API,
+        }
+    }
+
+    pub fn relay_api(&self) -> &'static str {
+        match self {
+            Network::Polkadot => polkadot::RELAY_API,
+            Network::Westend => westend::RELAY_API,
+            Network::Paseo => paseo::RELAY_API,
+        }
+    }
+
+    /// Decimals of the relay chain token
+    pub fn decimals(&self) -> u8 {
+        match self {
+            Network::Polkadot => polkadot::POLKADOT_DECIMALS,
+            Network::Westend => westend::POLKADOT_DECIMALS,
+            Network::Paseo => paseo::POLKADOT_DECIMALS,
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Network::Polkadot => "polkadot",
+            Network::Westend => "westend",
+            Network::Paseo => "paseo",
+        };
+        f.write_str(name)
+    }
+}
+
+pub mod polkadot {
+    use super::Network;
+    use snowbridge_token_list::NetworkId;
+
+    pub const NETWORK: Network = Network::Polkadot;
     pub const POLKADOT_SYMBOL: &str = "DOT";
     pub const POLKADOT_DECIMALS: u8 = 10;
     pub const ASSET_HUB_ID: u32 = 1000;
@@ -7,13 +65,19 @@ mod polkadot {
     pub const BRIDGE_HUB_ID: u32 = 1002;
     pub const BRIDGE_HUB_API: &str = "wss://polkadot-bridge-hub-rpc.polkadot.io";
     pub const RELAY_API: &str = "wss://polkadot.api.onfinality.io/public-ws";
+    /// Network of the relay chain in XCM locations
+    pub const RELAY_NETWORK: NetworkId = NetworkId::Polkadot;
+    /// Stablecoins on AssetHub that treasury spends can be made in, by symbol: their index in
+    /// the `Assets` pallet and decimals
+    pub const TREASURY_STABLECOINS: &[(&str, u128, u8)] = &[("USDC", 1337, 6), ("USDT", 1984, 6)];
 }
 
-#[cfg(feature = "polkadot")]
-pub use polkadot::*;
+pub mod westend {
+    use super::Network;
+    use hex_literal::hex;
+    use snowbridge_token_list::NetworkId;
 
-#[cfg(feature = "westend")]
-mod westend {
+    pub const NETWORK: Network = Network::Westend;
     pub const POLKADOT_SYMBOL: &str = "WND";
     pub const POLKADOT_DECIMALS: u8 = 12;
     pub const ASSET_HUB_ID: u32 = 1000;
@@ -21,13 +85,21 @@ mod westend {
     pub const BRIDGE_HUB_ID: u32 = 1002;
     pub const BRIDGE_HUB_API: &str = "wss://bridge-hub-westend-rpc.dwellir.com";
     pub const RELAY_API: &str = "wss://westend-rpc.dwellir.com";
+    /// Network of the relay chain in XCM locations
+    pub const RELAY_NETWORK: NetworkId = NetworkId::ByGenesis(hex!(
+        "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e"
+    ));
+    /// Stablecoins on AssetHub that treasury spends can be made in. None are set up on this
+    /// testnet, so spends give the asset by location.
+    pub const TREASURY_STABLECOINS: &[(&str, u128, u8)] = &[];
 }
 
-#[cfg(feature = "westend")]
-pub use westend::*;
+pub mod paseo {
+    use super::Network;
+    use hex_literal::hex;
+    use snowbridge_token_list::NetworkId;
 
-#[cfg(feature = "paseo")]
-mod paseo {
+    pub const NETWORK: Network = Network::Paseo;
     pub const POLKADOT_SYMBOL: &str = "PAS";
     pub const POLKADOT_DECIMALS: u8 = 10;
     pub const ASSET_HUB_ID: u32 = 1000;
@@ -35,7 +107,11 @@ mod paseo {
     pub const BRIDGE_HUB_ID: u32 = 1002;
     pub const BRIDGE_HUB_API: &str = "wss://bridge-hub-paseo.dotters.network";
     pub const RELAY_API: &str = "wss://paseo-rpc.dwellir.com";
+    /// Network of the relay chain in XCM locations
+    pub const RELAY_NETWORK: NetworkId = NetworkId::ByGenesis(hex!(
+        "77afd6190f1554ad45fd0d31aee62aacc33c6db0ea801129acb813f913e0764f"
+    ));
+    /// Stablecoins on AssetHub that treasury spends can be made in. None are set up on this
+    /// testnet, so spends give the asset by location.
+    pub const TREASURY_STABLECOINS: &[(&str, u128, u8)] = &[];
 }
-
-#[cfg(feature = "paseo")]
-pub use paseo::*;
@@ -0,0 +1,760 @@
+use super::asset_hub_runtime::runtime_types::{
+    frame_system, pallet_scheduler, pallet_treasury, pallet_utility, pallet_xcm,
+    parachains_common::pay::VersionedLocatableAccount,
+    polkadot_runtime_common::impls::VersionedLocatableAsset,
+    snowbridge_pallet_system_frontend,
+    staging_xcm::v5::{
+        asset::{AssetId, Fungibility},
+        junction::{Junction, NetworkId},
+        junctions::Junctions,
+        location::Location,
+        Instruction, Xcm,
+    },
+    xcm::{VersionedLocation, VersionedXcm},
+};
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::bridge_hub_runtime::runtime_types::{
+    frame_system as bh_frame_system, snowbridge_core::pricing::PricingParameters,
+    snowbridge_pallet_system, snowbridge_pallet_system_v2,
+};
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::constants::{
+    ASSET_HUB_ID, BRIDGE_HUB_ID, POLKADOT_DECIMALS, POLKADOT_SYMBOL, TREASURY_STABLECOINS,
+};
+use super::helpers::call_name;
+use crate::call_tree::CallNode;
+use crate::metadata_check::{call_name_by_index, ChainCall};
+use crate::{Chain, DecodeArgs};
+use alloy_primitives::{utils::format_units, U256};
+use codec::{Decode, DecodeAll};
+use sp_arithmetic::FixedPointNumber;
+use sp_crypto_hashing::twox_128;
+
+/// Chain a `Transact` is executed on, derived from the `pallet_xcm::send` destination.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Destination {
+    BridgeHub,
+    AssetHub,
+    Relay,
+    Unknown,
+}
+
+/// Read a preimage given either as a `0x`-prefixed hex string, or as the path to a file
+/// containing hex (as written by `--format hex`) or raw bytes (`--format binary`).
+pub fn read_preimage(input: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if let Some(data) = input.strip_prefix("0x") {
+        return Ok(hex::decode(data.trim())?);
+    }
+    let data = std::fs::read(input).map_err(|e| format!("Failed to read preimage {input}: {e}"))?;
+    if let Ok(text) = std::str::from_utf8(&data) {
+        if let Some(data) = text.trim().strip_prefix("0x") {
+            return Ok(hex::decode(data)?);
+        }
+    }
+    Ok(data)
+}
+
+pub fn decode_preimage(preimage: &[u8]) -> Result<CallNode, Box<dyn std::error::Error>> {
+    let call = AssetHubRuntimeCall::decode_all(&mut &preimage[..])
+        .map_err(|e| format!("Failed to decode preimage as an AssetHub call: {e}"))?;
+    Ok(asset_hub_call(&call))
+}
+
+pub fn decode(params: &DecodeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let preimage = read_preimage(&params.preimage)?;
+    let tree = decode_preimage(&preimage)?;
+    print!("{tree}");
+    Ok(())
+}
+
+/// Every call in a preimage, including those nested in wrappers, batches, the scheduler and
+/// `Transact`s, with the chain it is dispatched on.
+pub fn used_calls(call: &AssetHubRuntimeCall) -> Vec<ChainCall> {
+    let mut calls = vec![];
+    asset_hub_used_calls(call, &mut calls);
+    calls
+}
+
+fn asset_hub_used_calls(call: &AssetHubRuntimeCall, calls: &mut Vec<ChainCall>) {
+    calls.push(ChainCall {
+        chain: Chain::AssetHub,
+        call: call_name(call),
+    });
+    let inner =
+        super::unwrap_sudo(call).or_else(|| super::wrappers::unwrap(call).map(|(_, call)| call));
+    if let Some(inner) = inner {
+        return asset_hub_used_calls(inner, calls);
+    }
+    match call {
+        AssetHubRuntimeCall::Utility(
+            pallet_utility::pallet::Call::batch { calls: nested }
+            | pallet_utility::pallet::Call::batch_all { calls: nested }
+            | pallet_utility::pallet::Call::force_batch { calls: nested },
+        ) => {
+            for nested in nested {
+                asset_hub_used_calls(nested, calls);
+            }
+        }
+        AssetHubRuntimeCall::Scheduler(
+            pallet_scheduler::pallet::Call::schedule { call: nested, .. }
+            | pallet_scheduler::pallet::Call::schedule_named { call: nested, .. },
+        ) => asset_hub_used_calls(nested, calls),
+        AssetHubRuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send { dest, message }) => {
+            let VersionedXcm::V5(Xcm(instructions)) = message.as_ref() else {
+                return;
+            };
+            for instruction in instructions {
+                let Instruction::Transact { call, .. } = instruction else {
+                    continue;
+                };
+                let encoded = &call.encoded[..];
+                let (chain, name) = match destination(dest) {
+                    Destination::BridgeHub => (
+                        Chain::BridgeHub,
+                        BridgeHubRuntimeCall::decode_all(&mut &encoded[..])
+                            .ok()
+                            .map(|call| call_name(&call)),
+                    ),
+                    Destination::AssetHub => (
+                        Chain::AssetHub,
+                        AssetHubRuntimeCall::decode_all(&mut &encoded[..])
+                            .ok()
+                            .map(|call| call_name(&call)),
+                    ),
+                    Destination::Relay => (
+                        Chain::Relay,
+                        call_name_by_index(super::relay_runtime::METADATA, encoded),
+                    ),
+                    Destination::Unknown => continue,
+                };
+                if let Some(call) = name {
+                    calls.push(ChainCall { chain, call });
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn asset_hub_call(call: &AssetHubRuntimeCall) -> CallNode {
+    if let Some(call) = super::unwrap_sudo(call) {
+        return CallNode::new("Sudo.sudo", vec![asset_hub_call(call)]);
+    }
+    if let Some((name, call)) = super::wrappers::unwrap(call) {
+        return CallNode::new(name, vec![asset_hub_call(call)]);
+    }
+    match call {
+        AssetHubRuntimeCall::Utility(pallet_utility::pallet::Call::batch { calls }) => {
+            CallNode::new("Utility.batch", calls.iter().map(asset_hub_call).collect())
+        }
+        AssetHubRuntimeCall::Utility(pallet_utility::pallet::Call::batch_all { calls }) => {
+            CallNode::new(
+                "Utility.batch_all",
+                calls.iter().map(asset_hub_call).collect(),
+            )
+        }
+        AssetHubRuntimeCall::Utility(pallet_utility::pallet::Call::force_batch { calls }) => {
+            CallNode::new(
+                "Utility.force_batch",
+                calls.iter().map(asset_hub_call).collect(),
+            )
+        }
+        AssetHubRuntimeCall::Scheduler(pallet_scheduler::pallet::Call::schedule {
+            when,
+            maybe_periodic,
+            priority,
+            call,
+        }) => CallNode::new(
+            "Scheduler.schedule",
+            scheduled_call(None, *when, maybe_periodic, *priority, call),
+        ),
+        AssetHubRuntimeCall::Scheduler(pallet_scheduler::pallet::Call::schedule_named {
+            id,
+            when,
+            maybe_periodic,
+            priority,
+            call,
+        }) => CallNode::new(
+            "Scheduler.schedule_named",
+            scheduled_call(Some(id), *when, maybe_periodic, *priority, call),
+        ),
+        AssetHubRuntimeCall::Treasury(pallet_treasury::pallet::Call::spend {
+            asset_kind,
+            amount,
+            beneficiary,
+            valid_from,
+        }) => CallNode::new(
+            "Treasury.spend",
+            vec![
+                CallNode::leaf(format!("amount: {}", treasury_amount(asset_kind, *amount))),
+                CallNode::leaf(format!(
+                    "beneficiary: {}",
+                    treasury_beneficiary(beneficiary)
+                )),
+                CallNode::leaf(match valid_from {
+                    Some(block) => format!("valid_from: block {block}"),
+                    None => "valid_from: immediately".to_string(),
+                }),
+            ],
+        ),
+        AssetHubRuntimeCall::SnowbridgeSystemFrontend(
+            snowbridge_pallet_system_frontend::pallet::Call::register_token {
+                asset_id,
+                metadata,
+                fee_asset,
+            },
+        ) => {
+            let fee = match (&fee_asset.id, &fee_asset.fun) {
+                (AssetId(location), Fungibility::Fungible(fee)) if is_ether(location) => {
+                    format!("{} ETH", format_units(U256::from(*fee), "ether").unwrap())
+                }
+                _ => format!("{fee_asset:?}"),
+            };
+            CallNode::new(
+                "SnowbridgeSystemFrontend.register_token",
+                vec![
+                    CallNode::leaf(format!("asset_id: {asset_id:?}")),
+                    CallNode::leaf(format!(
+                        "metadata: {} ({}), {} decimals",
+                        String::from_utf8_lossy(&metadata.name.0),
+                        String::from_utf8_lossy(&metadata.symbol.0),
+                        metadata.decimals
+                    )),
+                    CallNode::leaf(format!("fee: {fee}")),
+                ],
+            )
+        }
+        AssetHubRuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send { dest, message }) => {
+            let destination = destination(dest);
+            let mut children = vec![CallNode::leaf(format!("dest: {}", location_label(dest)))];
+            match message.as_ref() {
+                VersionedXcm::V5(Xcm(instructions)) => children.extend(
+                    instructions
+                        .iter()
+                        .map(|instruction| xcm_instruction(instruction, destination)),
+                ),
+                other => children.push(CallNode::leaf(format!("message: {other:?}"))),
+            }
+            CallNode::new("PolkadotXcm.send", children)
+        }
+        AssetHubRuntimeCall::System(frame_system::pallet::Call::set_storage { items }) => {
+            CallNode::new(
+                "System.set_storage",
+                items
+                    .iter()
+                    .map(|(key, value)| storage_item(key, value))
+                    .collect(),
+            )
+        }
+        other => CallNode::leaf(format!("{other:?}")),
+    }
+}
+
+fn scheduled_call(
+    id: Option<&[u8; 32]>,
+    when: u32,
+    maybe_periodic: &Option<(u32, u32)>,
+    priority: u8,
+    call: &AssetHubRuntimeCall,
+) -> Vec<CallNode> {
+    let mut children = vec![];
+    if let Some(id) = id {
+        children.push(CallNode::leaf(format!("id: 0x{}", hex::encode(id))));
+    }
+    children.push(CallNode::leaf(format!("when: block {when}")));
+    if let Some((period, count)) = maybe_periodic {
+        children.push(CallNode::leaf(format!(
+            "periodic: every {period} blocks, {count} times"
+        )));
+    }
+    children.push(CallNode::leaf(format!("priority: {priority}")));
+    children.push(asset_hub_call(call));
+    children
+}
+
+/// A treasury spend amount in units of the relay chain token or a known stablecoin, as
+/// built by `treasury-proposal`, or else the raw amount and asset.
+fn treasury_amount(asset_kind: &VersionedLocatableAsset, amount: u128) -> String {
+    let VersionedLocatableAsset::V5 {
+        location:
+            Location {
+                parents: 0,
+                interior: Junctions::Here,
+            },
+        asset_id: AssetId(asset),
+    } = asset_kind
+    else {
+        return format!("{amount} of {asset_kind:?}");
+    };
+    match asset {
+        Location {
+            parents: 1,
+            interior: Junctions::Here,
+        } => format_polkadot_amount(amount),
+        Location {
+            parents: 0,
+            interior: Junctions::X2([Junction::PalletInstance(50), Junction::GeneralIndex(index)]),
+        } => match TREASURY_STABLECOINS.iter().find(|(_, id, _)| id == index) {
+            Some((symbol, _, decimals)) => format!(
+                "{} {symbol} [{amount}]",
+                format_units(U256::from(amount), *decimals).unwrap()
+            ),
+            None => format!("{amount} of asset {index}"),
+        },
+        other => format!("{amount} of {other:?}"),
+    }
+}
+
+fn treasury_beneficiary(beneficiary: &VersionedLocatableAccount) -> String {
+    match beneficiary {
+        VersionedLocatableAccount::V5 {
+            location:
+                Location {
+                    parents: 0,
+                    interior: Junctions::Here,
+                },
+            account_id:
+                Location {
+                    parents: 0,
+                    interior: Junctions::X1([Junction::AccountId32 { id, .. }]),
+                },
+        } => format!("0x{}", hex::encode(id)),
+        other => format!("{other:?}"),
+    }
+}
+
+fn is_ether(location: &Location) -> bool {
+    matches!(
+        location,
+        Location {
+            parents: 2,
+            interior: Junctions::X1([Junction::GlobalConsensus(NetworkId::Ethereum { .. })]),
+        }
+    )
+}
+
+fn bridge_hub_call(call: &BridgeHubRuntimeCall) -> CallNode {
+    match call {
+        BridgeHubRuntimeCall::EthereumSystem(
+            snowbridge_pallet_system::pallet::Call::set_pricing_parameters { params },
+        ) => CallNode::new(
+            "EthereumSystem.set_pricing_parameters",
+            pricing_parameters(params),
+        ),
+        BridgeHubRuntimeCall::EthereumSystem(snowbridge_pallet_system::pallet::Call::upgrade {
+            impl_address,
+            impl_code_hash,
+            initializer,
+        }) => {
+            let mut children = vec![
+                CallNode::leaf(format!("impl_address: {impl_address:?}")),
+                CallNode::leaf(format!("impl_code_hash: {impl_code_hash:?}")),
+            ];
+            if let Some(initializer) = initializer {
+                children.push(CallNode::leaf(format!(
+                    "initializer.params: 0x{}",
+                    hex::encode(&initializer.params)
+                )));
+                children.push(CallNode::leaf(format!(
+                    "initializer.maximum_required_gas: {}",
+                    initializer.maximum_required_gas
+                )));
+            }
+            CallNode::new("EthereumSystem.upgrade", children)
+        }
+        BridgeHubRuntimeCall::EthereumSystemV2(
+            snowbridge_pallet_system_v2::pallet::Call::upgrade {
+                impl_address,
+                impl_code_hash,
+                initializer,
+            },
+        ) => CallNode::new(
+            "EthereumSystemV2.upgrade",
+            vec![
+                CallNode::leaf(format!("impl_address: {impl_address:?}")),
+                CallNode::leaf(format!("impl_code_hash: {impl_code_hash:?}")),
+                CallNode::leaf(format!(
+                    "initializer.params: 0x{}",
+                    hex::encode(&initializer.params)
+                )),
+                CallNode::leaf(format!(
+                    "initializer.maximum_required_gas: {}",
+                    initializer.maximum_required_gas
+                )),
+            ],
+        ),
+        BridgeHubRuntimeCall::EthereumSystem(
+            snowbridge_pallet_system::pallet::Call::set_token_transfer_fees {
+                create_asset_xcm,
+                transfer_asset_xcm,
+                register_token,
+            },
+        ) => CallNode::new(
+            "EthereumSystem.set_token_transfer_fees",
+            vec![
+                CallNode::leaf(format!(
+                    "create_asset_xcm: {}",
+                    format_polkadot_amount(*create_asset_xcm)
+                )),
+                CallNode::leaf(format!(
+                    "transfer_asset_xcm: {}",
+                    format_polkadot_amount(*transfer_asset_xcm)
+                )),
+                CallNode::leaf(format!(
+                    "register_token: {} ETH",
+                    format_units(U256::from_limbs(register_token.0), "ether").unwrap()
+                )),
+            ],
+        ),
+        BridgeHubRuntimeCall::System(bh_frame_system::pallet::Call::set_storage { items }) => {
+            CallNode::new(
+                "System.set_storage",
+                items
+                    .iter()
+                    .map(|(key, value)| storage_item(key, value))
+                    .collect(),
+            )
+        }
+        other => CallNode::leaf(format!("{other:?}")),
+    }
+}
+
+fn xcm_instruction(instruction: &Instruction, destination: Destination) -> CallNode {
+    match instruction {
+        Instruction::Transact {
+            origin_kind,
+            fallback_max_weight,
+            call,
+        } => {
+            let weight = match fallback_max_weight {
+                Some(weight) => format!(
+                    "ref_time: {}, proof_size: {}",
+                    weight.ref_time, weight.proof_size
+                ),
+                None => "no fallback weight".to_string(),
+            };
+            let label = format!("Transact (origin: {origin_kind:?}, {weight})");
+            CallNode::new(label, vec![transact_call(&call.encoded, destination)])
+        }
+        other => CallNode::leaf(format!("{other:?}")),
+    }
+}
+
+fn transact_call(encoded: &[u8], destination: Destination) -> CallNode {
+    let decoded = match destination {
+        Destination::BridgeHub => {
+            BridgeHubRuntimeCall::decode_all(&mut &encoded[..]).map(|call| bridge_hub_call(&call))
+        }
+        Destination::AssetHub => {
+            AssetHubRuntimeCall::decode_all(&mut &encoded[..]).map(|call| asset_hub_call(&call))
+        }
+        Destination::Relay => {
+            let name = call_name_by_index(super::relay_runtime::METADATA, encoded);
+            return CallNode::leaf(format!(
+                "{}: 0x{}",
+                name.as_deref().unwrap_or("Unknown relay chain call"),
+                hex::encode(encoded)
+            ));
+        }
+        Destination::Unknown => Err("unknown destination".into()),
+    };
+    decoded.unwrap_or_else(|e: codec::Error| {
+        CallNode::leaf(format!(
+            "Undecodable call ({e}): 0x{}",
+            hex::encode(encoded)
+        ))
+    })
+}
+
+fn destination(dest: &VersionedLocation) -> Destination {
+    match dest {
+        VersionedLocation::V5(Location {
+            parents: 1,
+            interior: Junctions::X1([Junction::Parachain(id)]),
+        }) if *id == BRIDGE_HUB_ID => Destination::BridgeHub,
+        VersionedLocation::V5(Location {
+            parents: 1,
+            interior: Junctions::X1([Junction::Parachain(id)]),
+        }) if *id == ASSET_HUB_ID => Destination::AssetHub,
+        VersionedLocation::V5(Location {
+            parents: 1,
+            interior: Junctions::Here,
+        }) => Destination::Relay,
+        _ => Destination::Unknown,
+    }
+}
+
+fn location_label(dest: &VersionedLocation) -> String {
+    match destination(dest) {
+        Destination::BridgeHub => format!("BridgeHub ({BRIDGE_HUB_ID})"),
+        Destination::AssetHub => format!("AssetHub ({ASSET_HUB_ID})"),
+        Destination::Relay => "Relay chain".to_string(),
+        Destination::Unknown => format!("{dest:?}"),
+    }
+}
+
+fn pricing_parameters(params: &PricingParameters<u128>) -> Vec<CallNode> {
+    let fixed = |value: u128| format_units(U256::from(value), 18).unwrap();
+    vec![
+        CallNode::leaf(format!(
+            "exchange_rate: {} ETH/{}",
+            fixed(params.exchange_rate.0.into_inner()),
+            POLKADOT_SYMBOL
+        )),
+        CallNode::leaf(format!(
+            "multiplier: {}",
+            fixed(params.multiplier.0.into_inner())
+        )),
+        CallNode::leaf(format!(
+            "fee_per_gas: {} GWEI",
+            format_units(U256::from_limbs(params.fee_per_gas.0), "gwei").unwrap()
+        )),
+        CallNode::leaf(format!(
+            "rewards.local: {}",
+            format_polkadot_amount(params.rewards.local)
+        )),
+        CallNode::leaf(format!(
+            "rewards.remote: {} ETH",
+            format_units(U256::from_limbs(params.rewards.remote.0), "ether").unwrap()
+        )),
+    ]
+}
+
+/// Storage keys set by this tool, keyed by their `twox_128` hash.
+fn well_known_storage_key(key: &[u8]) -> Option<&'static str> {
+    [
+        ":BridgeHubEthereumBaseFee:",
+        ":BridgeHubEthereumBaseFeeV2:",
+        ":EthereumGatewayAddress:",
+    ]
+    .into_iter()
+    .find(|name| twox_128(name.as_bytes()) == key)
+}
+
+fn storage_item(key: &[u8], value: &[u8]) -> CallNode {
+    let value_label = match well_known_storage_key(key) {
+        Some(":EthereumGatewayAddress:") => <[u8; 20]>::decode(&mut &value[..])
+            .map(|address| format!("0x{}", hex::encode(address)))
+            .ok(),
+        Some(_) => u128::decode(&mut &value[..])
+            .map(format_polkadot_amount)
+            .ok(),
+        None => None,
+    }
+    .unwrap_or_else(|| format!("0x{}", hex::encode(value)));
+
+    match well_known_storage_key(key) {
+        Some(name) => CallNode::leaf(format!("{name} = {value_label}")),
+        None => CallNode::leaf(format!("0x{} = {value_label}", hex::encode(key))),
+    }
+}
+
+pub fn format_polkadot_amount(amount: u128) -> String {
+    if amount == u128::MAX {
+        return "u128::MAX".to_string();
+    }
+    format!(
+        "{} {} [{} PLANCK]",
+        format_units(U256::from(amount), POLKADOT_DECIMALS).unwrap(),
+        POLKADOT_SYMBOL,
+        amount
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::asset_hub_runtime::runtime_types::{
+        pallet_multisig, pallet_proxy, sp_weights::weight_v2::Weight,
+        xcm::double_encoded::DoubleEncoded, xcm::v3::OriginKind,
+    };
+    use super::super::commands::{inbound_queue_operating_mode, system_frontend_operating_mode};
+    use super::super::treasury_commands::make_treasury_spend;
+    use super::*;
+    use crate::OperatingModeEnum;
+    use codec::Encode;
+    use subxt::utils::{AccountId32, MultiAddress};
+
+    fn round_trip(call: &AssetHubRuntimeCall) -> CallNode {
+        decode_preimage(&call.encode()).unwrap()
+    }
+
+    fn labels(node: &CallNode) -> Vec<&str> {
+        node.children
+            .iter()
+            .map(|child| child.label.as_str())
+            .collect()
+    }
+
+    fn halt() -> AssetHubRuntimeCall {
+        system_frontend_operating_mode(&OperatingModeEnum::Halted)
+    }
+
+    #[test]
+    fn decode_batches() {
+        let tree = round_trip(&AssetHubRuntimeCall::Utility(
+            pallet_utility::pallet::Call::batch_all {
+                calls: vec![halt(), halt()],
+            },
+        ));
+        assert_eq!(tree.label, "Utility.batch_all");
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0], asset_hub_call(&halt()));
+    }
+
+    #[test]
+    fn decode_origin_wrappers() {
+        let proxy = AssetHubRuntimeCall::Proxy(pallet_proxy::pallet::Call::proxy {
+            real: MultiAddress::<AccountId32, ()>::Id([1; 32].into()),
+            force_proxy_type: None,
+            call: Box::new(halt()),
+        });
+        let multisig = AssetHubRuntimeCall::Multisig(pallet_multisig::pallet::Call::as_multi {
+            threshold: 2,
+            other_signatories: vec![AccountId32([2; 32])],
+            maybe_timepoint: None,
+            call: Box::new(proxy),
+            max_weight: Weight {
+                ref_time: 1,
+                proof_size: 1,
+            },
+        });
+        let tree = round_trip(&multisig);
+        assert_eq!(tree.label, "Multisig.as_multi");
+        assert_eq!(labels(&tree), ["Proxy.proxy"]);
+        assert_eq!(tree.children[0].children, [asset_hub_call(&halt())]);
+
+        if let Ok(sudo) = super::super::sudo(Box::new(halt())) {
+            let tree = round_trip(&sudo);
+            assert_eq!(tree.label, "Sudo.sudo");
+            assert_eq!(tree.children, [asset_hub_call(&halt())]);
+        }
+    }
+
+    #[test]
+    fn decode_scheduled_calls() {
+        let tree = round_trip(&AssetHubRuntimeCall::Scheduler(
+            pallet_scheduler::pallet::Call::schedule {
+                when: 100,
+                maybe_periodic: None,
+                priority: 0,
+                call: Box::new(halt()),
+            },
+        ));
+        assert_eq!(tree.label, "Scheduler.schedule");
+        assert_eq!(labels(&tree)[..2], ["when: block 100", "priority: 0"]);
+        assert_eq!(tree.children[2], asset_hub_call(&halt()));
+
+        let tree = round_trip(&AssetHubRuntimeCall::Scheduler(
+            pallet_scheduler::pallet::Call::schedule_named {
+                id: [0xab; 32],
+                when: 100,
+                maybe_periodic: Some((10, 3)),
+                priority: 1,
+                call: Box::new(halt()),
+            },
+        ));
+        assert_eq!(tree.label, "Scheduler.schedule_named");
+        assert_eq!(
+            labels(&tree)[..4],
+            [
+                format!("id: 0x{}", "ab".repeat(32)).as_str(),
+                "when: block 100",
+                "periodic: every 10 blocks, 3 times",
+                "priority: 1"
+            ]
+        );
+        assert_eq!(tree.children[4], asset_hub_call(&halt()));
+    }
+
+    #[test]
+    fn decode_transacts_by_destination() {
+        let resume = inbound_queue_operating_mode(&OperatingModeEnum::Normal);
+        let send = |parachain, encoded| {
+            AssetHubRuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send {
+                dest: Box::new(VersionedLocation::V5(Location {
+                    parents: 1,
+                    interior: Junctions::X1([Junction::Parachain(parachain)]),
+                })),
+                message: Box::new(VersionedXcm::V5(Xcm(vec![Instruction::Transact {
+                    origin_kind: OriginKind::Superuser,
+                    fallback_max_weight: None,
+                    call: DoubleEncoded { encoded },
+                }]))),
+            })
+        };
+
+        let tree = round_trip(&send(BRIDGE_HUB_ID, resume.encode()));
+        assert_eq!(tree.label, "PolkadotXcm.send");
+        assert_eq!(
+            labels(&tree),
+            [
+                format!("dest: BridgeHub ({BRIDGE_HUB_ID})").as_str(),
+                "Transact (origin: Superuser, no fallback weight)"
+            ]
+        );
+        assert_eq!(tree.children[1].children, [bridge_hub_call(&resume)]);
+
+        let tree = round_trip(&send(BRIDGE_HUB_ID, vec![0xff, 0xff]));
+        assert!(tree.children[1].children[0]
+            .label
+            .starts_with("Undecodable call"));
+
+        let tree = round_trip(&send(ASSET_HUB_ID, halt().encode()));
+        assert_eq!(tree.children[1].children, [asset_hub_call(&halt())]);
+
+        let calls = used_calls(&send(BRIDGE_HUB_ID, resume.encode()));
+        assert!(matches!(calls[0].chain, Chain::AssetHub));
+        assert!(matches!(calls[1].chain, Chain::BridgeHub));
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[test]
+    fn decode_treasury_spends_in_units() {
+        let dot = Location {
+            parents: 1,
+            interior: Junctions::Here,
+        };
+        let tree = round_trip(&make_treasury_spend(
+            [1; 32],
+            dot,
+            15 * 10u128.pow(POLKADOT_DECIMALS.into()),
+            Some(42),
+        ));
+        assert_eq!(tree.label, "Treasury.spend");
+        assert_eq!(
+            labels(&tree),
+            [
+                format!(
+                    "amount: {}",
+                    format_polkadot_amount(15 * 10u128.pow(POLKADOT_DECIMALS.into()))
+                )
+                .as_str(),
+                format!("beneficiary: 0x{}", "01".repeat(32)).as_str(),
+                "valid_from: block 42"
+            ]
+        );
+        assert!(tree.children[0]
+            .label
+            .starts_with(&format!("amount: 15.0000000000 {POLKADOT_SYMBOL}")));
+
+        for (symbol, index, decimals) in TREASURY_STABLECOINS {
+            let location = Location {
+                parents: 0,
+                interior: Junctions::X2([
+                    Junction::PalletInstance(50),
+                    Junction::GeneralIndex(*index),
+                ]),
+            };
+            let amount = 25 * 10u128.pow((*decimals).into()) / 10;
+            let tree = round_trip(&make_treasury_spend([1; 32], location, amount, None));
+            assert_eq!(
+                labels(&tree)[0],
+                format!(
+                    "amount: 2.5{} {symbol} [{amount}]",
+                    "0".repeat(*decimals as usize - 1)
+                )
+            );
+            assert_eq!(labels(&tree)[2], "valid_from: immediately");
+        }
+    }
+}
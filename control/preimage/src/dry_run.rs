@@ -0,0 +1,178 @@
+use super::asset_hub_runtime::runtime_types::{
+    frame_support::dispatch::RawOrigin,
+    pallet_utility,
+    staging_xcm::v5::{junction::Junction, junctions::Junctions, location::Location, Instruction},
+    xcm::{VersionedLocation, VersionedXcm},
+};
+use super::asset_hub_runtime::{
+    OriginCaller, RuntimeCall as AssetHubRuntimeCall, RuntimeEvent as AssetHubRuntimeEvent,
+};
+use super::bridge_hub_runtime::runtime_types::{
+    staging_xcm::v5::{
+        junction::Junction as BridgeHubJunction,
+        junctions::Junctions as BridgeHubJunctions,
+        location::Location as BridgeHubLocation,
+        traits::{Error as XcmError, InstructionError, Outcome},
+    },
+    xcm::{VersionedLocation as BridgeHubVersionedLocation, VersionedXcm as BridgeHubVersionedXcm},
+};
+use super::constants::{ASSET_HUB_ID, BRIDGE_HUB_ID};
+use super::helpers::call_name;
+use crate::Context;
+use codec::{Decode, Encode};
+use sp_crypto_hashing::twox_128;
+use subxt::utils::AccountId32;
+
+/// XCM version used for the forwarded messages returned by `dry_run_call`.
+const RESULT_XCMS_VERSION: u32 = 5;
+
+/// Dry-run the call on AssetHub with a Root origin, or signed by the sudo key for a
+/// `Sudo.sudo` call, then replay every XCM it forwards to BridgeHub through BridgeHub's
+/// `dry_run_xcm`.
+///
+/// Returns an error if any part of the proposal would fail, so that a broken preimage
+/// is never emitted.
+pub async fn dry_run(
+    context: &Context,
+    call: &AssetHubRuntimeCall,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let asset_hub_api = context.asset_hub_api()?;
+    let bridge_hub_api = context.bridge_hub_api()?;
+    let mut failures: Vec<String> = vec![];
+
+    let origin = match super::unwrap_sudo(call) {
+        Some(_) => {
+            let key = asset_hub_api
+                .storage()
+                .at_latest()
+                .await?
+                .fetch_raw([twox_128(b"Sudo"), twox_128(b"Key")].concat())
+                .await?
+                .ok_or("AssetHub has no sudo key")?;
+            let key = <[u8; 32]>::decode(&mut &key[..])?;
+            eprintln!(
+                "Dry-running Sudo.sudo signed by the sudo key 0x{}",
+                hex::encode(key)
+            );
+            RawOrigin::Signed(AccountId32(key))
+        }
+        None => RawOrigin::Root,
+    };
+    let runtime_api_call = super::asset_hub_runtime::apis().dry_run_api().dry_run_call(
+        OriginCaller::system(origin),
+        call.clone(),
+        RESULT_XCMS_VERSION,
+    );
+    let effects = asset_hub_api
+        .runtime_api()
+        .at_latest()
+        .await?
+        .call(runtime_api_call)
+        .await?
+        .map_err(|e| format!("AssetHub dry_run_call failed: {e:?}"))?;
+
+    eprintln!("Dry run on AssetHub:");
+    match &effects.execution_result {
+        Ok(_) => eprintln!("  Execution: success"),
+        Err(e) => {
+            eprintln!("  Execution: failed: {:?}", e.error);
+            failures.push(format!("AssetHub execution failed: {:?}", e.error));
+        }
+    }
+    for event in effects.emitted_events.iter() {
+        eprintln!("  Event: {}", call_name(event));
+        if let AssetHubRuntimeEvent::Utility(pallet_utility::pallet::Event::ItemFailed { error }) =
+            event
+        {
+            eprintln!("    ItemFailed: {error:?}");
+            failures.push(format!("AssetHub batch item failed: {error:?}"));
+        }
+    }
+
+    let bridge_hub_dest = VersionedLocation::V5(Location {
+        parents: 1,
+        interior: Junctions::X1([Junction::Parachain(BRIDGE_HUB_ID)]),
+    });
+    // BridgeHub sees the forwarded messages as originating from AssetHub
+    let origin_location = BridgeHubVersionedLocation::V5(BridgeHubLocation {
+        parents: 1,
+        interior: BridgeHubJunctions::X1([BridgeHubJunction::Parachain(ASSET_HUB_ID)]),
+    });
+
+    for (dest, messages) in effects.forwarded_xcms.iter() {
+        if dest.encode() != bridge_hub_dest.encode() {
+            eprintln!("Forwarded XCM to {dest:?} not replayed");
+            continue;
+        }
+        for message in messages.iter() {
+            let transacts = transact_indices(message);
+            // AssetHub and BridgeHub bindings are distinct types with the same encoding
+            let xcm = BridgeHubVersionedXcm::decode(&mut &message.encode()[..])?;
+            let runtime_api_call = super::bridge_hub_runtime::apis()
+                .dry_run_api()
+                .dry_run_xcm(origin_location.clone(), xcm);
+            let effects = bridge_hub_api
+                .runtime_api()
+                .at_latest()
+                .await?
+                .call(runtime_api_call)
+                .await?
+                .map_err(|e| format!("BridgeHub dry_run_xcm failed: {e:?}"))?;
+
+            eprintln!("Dry run on BridgeHub:");
+            // The instruction that failed, if any. Instructions after it are not executed
+            let failed = match &effects.execution_result {
+                Outcome::Complete { .. } => None,
+                Outcome::Incomplete {
+                    error: InstructionError { index, error },
+                    ..
+                }
+                | Outcome::Error(InstructionError { index, error }) => {
+                    Some((usize::from(*index), error))
+                }
+            };
+            for (number, position) in transacts.iter().enumerate() {
+                let status = match failed {
+                    None => "success".to_string(),
+                    Some((index, error)) if index == *position => format!("failed: {error:?}"),
+                    // The `ExpectTransactStatus` following the Transact rejected its outcome
+                    Some((index, XcmError::ExpectationFalse)) if index == position + 1 => {
+                        "failed (ExpectTransactStatus)".to_string()
+                    }
+                    Some((index, _)) if index > *position => "success".to_string(),
+                    Some(_) => "not executed".to_string(),
+                };
+                eprintln!("  Transact #{number}: {status}");
+            }
+            if let Some((index, error)) = failed {
+                eprintln!("  Execution stopped at instruction {index}: {error:?}");
+                failures.push(format!(
+                    "BridgeHub XCM execution failed at instruction {index}: {error:?}"
+                ));
+            }
+            for event in effects.emitted_events.iter() {
+                eprintln!("  Event: {}", call_name(event));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(format!("Dry run failed:\n  {}", failures.join("\n  ")).into());
+    }
+
+    Ok(())
+}
+
+/// Positions of the `Transact` instructions in `message`.
+fn transact_indices(message: &VersionedXcm) -> Vec<usize> {
+    match message {
+        VersionedXcm::V5(xcm) => xcm
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, instruction)| matches!(instruction, Instruction::Transact { .. }))
+            .map(|(position, _)| position)
+            .collect(),
+        _ => vec![],
+    }
+}
@@ -0,0 +1,447 @@
+//! Building arbitrary calls from JSON arguments against bundled runtime metadata.
+//!
+//! The JSON is converted to a `scale_value::Value` guided by the call's field types, so that
+//! mistakes are reported with the path of the offending argument, and the call is then
+//! encoded with subxt's dynamic encoding, which checks it against the metadata again.
+//!
+//! Conventions, by metadata type:
+//! - integers: a JSON number, or a decimal (or `0x` hex for `U256`) string for large values
+//! - byte arrays and sequences: a `0x` hex string, or an array of numbers
+//! - `AccountId32`: `0x` hex or an SS58 address
+//! - structs: an object keyed by field name, or an array; tuples: an array. A struct with a
+//!   single field takes that field's value, so `BoundedVec` and `H160` look like their contents
+//! - enums: `"Variant"` for a variant without fields, otherwise `{"Variant": fields}`
+//! - `Option`: `null` for `None`, otherwise the value itself
+
+use scale_info::{form::PortableForm, Field, PortableRegistry, Type, TypeDef, TypeDefPrimitive};
+use serde_json::Value as Json;
+use subxt::dynamic::Value;
+use subxt::ext::scale_value::{Composite, Primitive, ValueDef};
+use subxt::tx::Payload;
+use subxt::Metadata;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// SCALE-encode `pallet::call` with `args`, a JSON object keyed by argument name (or an
+/// array of positional arguments), against the runtime `metadata`.
+pub fn encode(metadata: &[u8], pallet: &str, call: &str, args: &Json) -> Result<Vec<u8>> {
+    let metadata = <Metadata as codec::Decode>::decode(&mut &metadata[..])
+        .map_err(|e| format!("Failed to decode the bundled metadata: {e}"))?;
+    let pallet_metadata = metadata.pallet_by_name(pallet).ok_or_else(|| {
+        let pallets: Vec<_> = metadata.pallets().map(|pallet| pallet.name()).collect();
+        format!(
+            "No pallet {pallet}, expected one of: {}",
+            pallets.join(", ")
+        )
+    })?;
+    let variant = pallet_metadata.call_variant_by_name(call).ok_or_else(|| {
+        let calls: Vec<_> = pallet_metadata
+            .call_variants()
+            .unwrap_or_default()
+            .iter()
+            .map(|variant| variant.name.as_str())
+            .collect();
+        format!(
+            "No call {pallet}.{call}, expected one of: {}",
+            calls.join(", ")
+        )
+    })?;
+
+    let converter = Converter {
+        types: metadata.types(),
+    };
+    let fields = converter.composite(&variant.fields, args, "args")?;
+    let payload = subxt::dynamic::tx(pallet, call, fields);
+    let encoded = payload
+        .encode_call_data(&metadata)
+        .map_err(|e| format!("Failed to encode {pallet}.{call}: {e}"))?;
+    eprintln!("{pallet}.{call}: 0x{}", hex::encode(&encoded));
+    Ok(encoded)
+}
+
+struct Converter<'a> {
+    types: &'a PortableRegistry,
+}
+
+impl Converter<'_> {
+    fn resolve(&self, id: u32) -> Result<&Type<PortableForm>> {
+        self.types
+            .resolve(id)
+            .ok_or_else(|| format!("Type {id} is missing from the metadata").into())
+    }
+
+    fn value(&self, id: u32, json: &Json, path: &str) -> Result<Value> {
+        let ty = self.resolve(id)?;
+        match &ty.type_def {
+            TypeDef::Composite(composite) => {
+                let is_account = ty.path.segments.last().map(String::as_str) == Some("AccountId32");
+                if let (true, Json::String(account)) = (is_account, json) {
+                    let account = crate::parse_account_id(account).map_err(|e| at(path, e))?;
+                    return Ok(Value::unnamed_composite([Value::from_bytes(account)]));
+                }
+                let fields = self.composite(&composite.fields, json, path)?;
+                Ok(Value {
+                    value: ValueDef::Composite(fields),
+                    context: (),
+                })
+            }
+            TypeDef::Variant(variant) => {
+                let is_option = ty.path.segments == ["Option"];
+                let (name, fields) = match json {
+                    Json::Null if is_option => ("None", &Json::Null),
+                    Json::Object(object)
+                        if object.len() == 1 && (!is_option || object.contains_key("Some")) =>
+                    {
+                        let (name, fields) = object.iter().next().unwrap();
+                        (name.as_str(), fields)
+                    }
+                    _ if is_option => ("Some", json),
+                    Json::String(name) => (name.as_str(), &Json::Null),
+                    _ => return Err(at(path, "expected \"Variant\" or {\"Variant\": ...}")),
+                };
+                let Some(variant) = variant.variants.iter().find(|v| v.name == name) else {
+                    let names: Vec<_> = variant.variants.iter().map(|v| v.name.as_str()).collect();
+                    return Err(at(
+                        path,
+                        format!(
+                            "unknown variant {name}, expected one of: {}",
+                            names.join(", ")
+                        ),
+                    ));
+                };
+                let fields = self.composite(&variant.fields, fields, &format!("{path}.{name}"))?;
+                Ok(Value::variant(name, fields))
+            }
+            TypeDef::Sequence(sequence) => self.sequence(sequence.type_param.id, None, json, path),
+            TypeDef::Array(array) => {
+                self.sequence(array.type_param.id, Some(array.len as usize), json, path)
+            }
+            TypeDef::Tuple(tuple) => {
+                let ids: Vec<u32> = tuple.fields.iter().map(|field| field.id).collect();
+                let items = match (ids.len(), json) {
+                    (0, Json::Null) => vec![],
+                    (_, Json::Array(items)) if items.len() == ids.len() => items.iter().collect(),
+                    _ => {
+                        return Err(at(
+                            path,
+                            format!("expected an array of {} items", ids.len()),
+                        ))
+                    }
+                };
+                let values = ids
+                    .iter()
+                    .zip(items)
+                    .enumerate()
+                    .map(|(i, (id, item))| self.value(*id, item, &format!("{path}[{i}]")))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Value::unnamed_composite(values))
+            }
+            TypeDef::Primitive(primitive) => primitive_value(primitive, json, path),
+            TypeDef::Compact(compact) => self.value(compact.type_param.id, json, path),
+            TypeDef::BitSequence(_) => Err(at(path, "bit sequences are not supported")),
+        }
+    }
+
+    /// The fields of a struct or enum variant.
+    fn composite(
+        &self,
+        fields: &[Field<PortableForm>],
+        json: &Json,
+        path: &str,
+    ) -> Result<Composite<()>> {
+        let named = fields.iter().all(|field| field.name.is_some());
+        // A single-field struct given as the value of its field
+        let transparent = || {
+            let value = self.value(fields[0].ty.id, json, path)?;
+            Ok(match &fields[0].name {
+                Some(name) => Composite::Named(vec![(name.clone(), value)]),
+                None => Composite::Unnamed(vec![value]),
+            })
+        };
+        match (fields, json) {
+            ([], Json::Null) => Ok(Composite::Unnamed(vec![])),
+            ([], Json::Object(object)) if object.is_empty() => Ok(Composite::Unnamed(vec![])),
+            ([], Json::Array(items)) if items.is_empty() => Ok(Composite::Unnamed(vec![])),
+            ([], _) => Err(at(path, "expected no fields")),
+            ([field], _) if field.name.is_none() => transparent(),
+            (_, Json::Object(object)) if named => {
+                let unknown = object
+                    .keys()
+                    .find(|key| !fields.iter().any(|field| field.name.as_ref() == Some(*key)));
+                match unknown {
+                    None => self.fields(fields, json, path),
+                    Some(_) if fields.len() == 1 => transparent(),
+                    Some(unknown) => Err(at(path, format!("unknown field {unknown}"))),
+                }
+            }
+            ([_], _) => transparent(),
+            (_, Json::Array(_)) => self.fields(fields, json, path),
+            _ => Err(at(
+                path,
+                match named {
+                    true => "expected an object or an array",
+                    false => "expected an array",
+                },
+            )),
+        }
+    }
+
+    /// Fields given either as an object keyed by name or as an array in declaration order.
+    fn fields(
+        &self,
+        fields: &[Field<PortableForm>],
+        json: &Json,
+        path: &str,
+    ) -> Result<Composite<()>> {
+        if let Json::Array(items) = json {
+            if items.len() != fields.len() {
+                return Err(at(
+                    path,
+                    format!("expected {} fields, got {}", fields.len(), items.len()),
+                ));
+            }
+        }
+        let mut values = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let (item, item_path) = match (json, &field.name) {
+                (Json::Object(object), Some(name)) => (
+                    object
+                        .get(name)
+                        .ok_or_else(|| at(path, format!("missing field {name}")))?,
+                    format!("{path}.{name}"),
+                ),
+                (Json::Array(items), _) => (&items[i], format!("{path}[{i}]")),
+                _ => return Err(at(path, "expected an object or an array")),
+            };
+            values.push((
+                field.name.clone(),
+                self.value(field.ty.id, item, &item_path)?,
+            ));
+        }
+        Ok(match fields.iter().all(|field| field.name.is_some()) {
+            true => Composite::Named(
+                values
+                    .into_iter()
+                    .map(|(name, value)| (name.unwrap_or_default(), value))
+                    .collect(),
+            ),
+            false => Composite::Unnamed(values.into_iter().map(|(_, value)| value).collect()),
+        })
+    }
+
+    /// A sequence, or an array of `len` items.
+    fn sequence(&self, id: u32, len: Option<usize>, json: &Json, path: &str) -> Result<Value> {
+        let is_bytes = matches!(
+            self.resolve(id)?.type_def,
+            TypeDef::Primitive(TypeDefPrimitive::U8)
+        );
+        let values = match json {
+            Json::String(data) if is_bytes => {
+                let data = data
+                    .strip_prefix("0x")
+                    .ok_or_else(|| at(path, "expected 0x-prefixed hex"))?;
+                let bytes = hex::decode(data).map_err(|e| at(path, e))?;
+                bytes
+                    .into_iter()
+                    .map(|byte| Value::u128(byte.into()))
+                    .collect()
+            }
+            Json::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| self.value(id, item, &format!("{path}[{i}]")))
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(at(path, "expected an array")),
+        };
+        if let Some(len) = len.filter(|len| *len != values.len()) {
+            return Err(at(
+                path,
+                format!("expected {len} items, got {}", values.len()),
+            ));
+        }
+        Ok(Value::unnamed_composite(values))
+    }
+}
+
+fn primitive_value(primitive: &TypeDefPrimitive, json: &Json, path: &str) -> Result<Value> {
+    let (bits, signed) = match primitive {
+        TypeDefPrimitive::Bool => {
+            let value = json
+                .as_bool()
+                .ok_or_else(|| at(path, "expected a boolean"))?;
+            return Ok(Value::bool(value));
+        }
+        TypeDefPrimitive::Char => {
+            let mut chars = json.as_str().unwrap_or_default().chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Value::char(c)),
+                _ => Err(at(path, "expected a single character")),
+            };
+        }
+        TypeDefPrimitive::Str => {
+            let value = json.as_str().ok_or_else(|| at(path, "expected a string"))?;
+            return Ok(Value::string(value));
+        }
+        TypeDefPrimitive::U256 => {
+            let value: alloy_primitives::U256 =
+                number(json, path)?.parse().map_err(|e| at(path, e))?;
+            return Ok(Value::primitive(Primitive::U256(value.to_le_bytes())));
+        }
+        TypeDefPrimitive::I256 => return Err(at(path, "I256 is not supported")),
+        TypeDefPrimitive::U8 => (8, false),
+        TypeDefPrimitive::U16 => (16, false),
+        TypeDefPrimitive::U32 => (32, false),
+        TypeDefPrimitive::U64 => (64, false),
+        TypeDefPrimitive::U128 => (128, false),
+        TypeDefPrimitive::I8 => (8, true),
+        TypeDefPrimitive::I16 => (16, true),
+        TypeDefPrimitive::I32 => (32, true),
+        TypeDefPrimitive::I64 => (64, true),
+        TypeDefPrimitive::I128 => (128, true),
+    };
+    let number = number(json, path)?;
+    let value = match signed {
+        false => number
+            .parse::<u128>()
+            .ok()
+            .filter(|value| bits == 128 || *value >> bits == 0)
+            .map(Value::u128),
+        true => number
+            .parse::<i128>()
+            .ok()
+            .filter(|value| (-1..=0).contains(&(*value >> (bits - 1))))
+            .map(Value::i128),
+    };
+    value.ok_or_else(|| {
+        let kind = match signed {
+            true => "an i",
+            false => "a u",
+        };
+        at(path, format!("expected {kind}{bits}, got {number}"))
+    })
+}
+
+/// An integer given as a JSON number or, for values beyond a `u64`, as a string.
+fn number(json: &Json, path: &str) -> Result<String> {
+    match json {
+        Json::Number(number) => Ok(number.to_string()),
+        Json::String(number) => Ok(number.clone()),
+        _ => Err(at(path, "expected a number or a string")),
+    }
+}
+
+fn at(path: &str, error: impl std::fmt::Display) -> Box<dyn std::error::Error> {
+    format!("Invalid {path}: {error}").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// V15 metadata of a runtime with a single pallet, `Test` at index 7, whose calls are:
+    ///
+    /// ```ignore
+    /// struct AccountId32([u8; 32]);
+    /// struct BoundedVec<T>(Vec<T>);
+    /// struct Window { blocks: u32 }
+    /// enum Mode { Normal, Limited(u8) }
+    /// struct Config { mode: Mode, window: Window }
+    ///
+    /// enum Call {
+    ///     set_name { name: BoundedVec<u8> },
+    ///     set_limit { limit: Option<u32> },
+    ///     transfer { dest: AccountId32, amount: u128 },
+    ///     set_config { config: Config },
+    /// }
+    /// ```
+    const METADATA: &[u8] = include_bytes!("../fixtures/dynamic-call-metadata.scale");
+
+    const ALICE: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    fn encode_call(call: &str, args: Json) -> Result<String> {
+        encode(METADATA, "Test", call, &args).map(hex::encode)
+    }
+
+    #[test]
+    fn single_field_structs_are_transparent() {
+        // BoundedVec<u8> as its bytes
+        let expected = "070008cafe";
+        assert_eq!(
+            encode_call("set_name", json!({ "name": "0xcafe" })).unwrap(),
+            expected
+        );
+        assert_eq!(
+            encode_call("set_name", json!({ "name": [202, 254] })).unwrap(),
+            expected
+        );
+        // A named single field, given as its value or as the struct
+        let expected = "0703000a000000";
+        let config = json!({ "config": { "mode": "Normal", "window": 10 } });
+        assert_eq!(encode_call("set_config", config).unwrap(), expected);
+        let config = json!({ "config": { "mode": "Normal", "window": { "blocks": 10 } } });
+        assert_eq!(encode_call("set_config", config).unwrap(), expected);
+        // A call with a single argument, given as its value
+        assert_eq!(
+            encode_call("set_name", json!("0xcafe")).unwrap(),
+            "070008cafe"
+        );
+    }
+
+    #[test]
+    fn options_are_null_or_the_value() {
+        assert_eq!(
+            encode_call("set_limit", json!({ "limit": null })).unwrap(),
+            "070100"
+        );
+        let expected = "07010105000000";
+        assert_eq!(
+            encode_call("set_limit", json!({ "limit": 5 })).unwrap(),
+            expected
+        );
+        let limit = json!({ "limit": { "Some": 5 } });
+        assert_eq!(encode_call("set_limit", limit).unwrap(), expected);
+        assert!(encode_call("set_limit", json!({ "limit": "5x" })).is_err());
+    }
+
+    #[test]
+    fn accounts_are_hex_or_ss58() {
+        let amount = "e8030000000000000000000000000000";
+        let expected = format!("0702{ALICE}{amount}");
+        let transfer = json!({ "dest": format!("0x{ALICE}"), "amount": 1000 });
+        assert_eq!(encode_call("transfer", transfer).unwrap(), expected);
+        let transfer = json!({
+            "dest": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+            "amount": "1000",
+        });
+        assert_eq!(encode_call("transfer", transfer).unwrap(), expected);
+        let transfer =
+            json!({ "dest": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ", "amount": 1 });
+        assert!(encode_call("transfer", transfer).is_err());
+        // Positional arguments
+        let transfer = json!([format!("0x{ALICE}"), 1000]);
+        assert_eq!(encode_call("transfer", transfer).unwrap(), expected);
+    }
+
+    #[test]
+    fn enums_are_names_or_objects() {
+        let config = json!({ "config": { "mode": { "Limited": 3 }, "window": 1 } });
+        assert_eq!(
+            encode_call("set_config", config).unwrap(),
+            "0703010301000000"
+        );
+        let config = json!({ "config": { "mode": { "Limited": 300 }, "window": 1 } });
+        assert!(encode_call("set_config", config).is_err());
+        let config = json!({ "config": { "mode": "Halted", "window": 1 } });
+        assert!(encode_call("set_config", config).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let config = json!({ "config": { "mode": "Normal", "window": 1, "extra": 0 } });
+        assert!(encode_call("set_config", config).is_err());
+        assert!(encode_call("set_limit", json!({})).is_err());
+        assert!(encode_call("missing", json!({})).is_err());
+        assert!(encode(METADATA, "Missing", "set_limit", &json!({})).is_err());
+    }
+}
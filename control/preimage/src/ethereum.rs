@@ -0,0 +1,162 @@
+//! Minimal Ethereum JSON-RPC client over websockets or HTTP(S), e.g. ws://127.0.0.1:8545 for
+//! anvil or an https:// endpoint of an RPC provider.
+
+use alloy_primitives::{Address, Bytes};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use subxt::ext::subxt_rpcs::client::{rpc_params, RpcClient, RpcParams};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct EthereumClient {
+    url: String,
+    transport: Transport,
+}
+
+enum Transport {
+    WebSocket(RpcClient),
+    Http(reqwest::Client),
+}
+
+/// A JSON-RPC 2.0 response to a single request.
+#[derive(Deserialize)]
+struct Response {
+    result: Option<Value>,
+    error: Option<Value>,
+}
+
+impl EthereumClient {
+    pub async fn connect(url: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let transport = match url.split_once("://").map(|(scheme, _)| scheme) {
+            Some("ws" | "wss") => Transport::WebSocket(
+                RpcClient::from_url(url)
+                    .await
+                    .map_err(|e| format!("Failed to connect to Ethereum at {url}: {e}"))?,
+            ),
+            Some("http" | "https") => Transport::Http(
+                reqwest::Client::builder()
+                    .timeout(HTTP_TIMEOUT)
+                    .build()
+                    .map_err(|e| format!("Failed to create the Ethereum client: {e}"))?,
+            ),
+            _ => {
+                return Err(format!(
+                    "Unsupported Ethereum endpoint {url}, expected a ws(s):// or http(s):// URL"
+                )
+                .into())
+            }
+        };
+        Ok(EthereumClient {
+            url: url.to_owned(),
+            transport,
+        })
+    }
+
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: RpcParams,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let result = match &self.transport {
+            Transport::WebSocket(client) => client
+                .request(method, params)
+                .await
+                .map_err(|e| e.to_string()),
+            Transport::Http(client) => self.http_request(client, method, params).await,
+        };
+        result.map_err(|e| format!("{method} failed on {}: {e}", self.url).into())
+    }
+
+    async fn http_request<T: DeserializeOwned>(
+        &self,
+        client: &reqwest::Client,
+        method: &str,
+        params: RpcParams,
+    ) -> Result<T, String> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params.build().map_or(Value::Array(vec![]), |params| {
+                serde_json::from_str(params.get()).expect("params are valid JSON")
+            }),
+        });
+        let response = client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        let text = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(format!("HTTP {status}: {text}"));
+        }
+        let response: Response =
+            serde_json::from_str(&text).map_err(|e| format!("invalid response '{text}': {e}"))?;
+        match response.error {
+            Some(error) => Err(error.to_string()),
+            None => serde_json::from_value(response.result.unwrap_or(Value::Null))
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// The code deployed at `address` in the latest block.
+    pub async fn get_code(&self, address: Address) -> Result<Bytes, Box<dyn std::error::Error>> {
+        let code: String = self
+            .request("eth_getCode", rpc_params![address.to_string(), "latest"])
+            .await?;
+        Ok(parse_bytes(&code)?)
+    }
+
+    /// Execute a read-only call of `data` against `to` in the latest block.
+    pub async fn call(
+        &self,
+        to: Address,
+        data: &[u8],
+    ) -> Result<Bytes, Box<dyn std::error::Error>> {
+        let call = serde_json::json!({
+            "to": to.to_string(),
+            "data": format!("0x{}", hex::encode(data)),
+        });
+        let result: String = self
+            .request("eth_call", rpc_params![call, "latest"])
+            .await?;
+        Ok(parse_bytes(&result)?)
+    }
+
+    /// Estimate the gas of calling `to` with `data` from `from` in the latest block.
+    pub async fn estimate_gas(
+        &self,
+        from: Address,
+        to: Address,
+        data: &[u8],
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let call = serde_json::json!({
+            "from": from.to_string(),
+            "to": to.to_string(),
+            "data": format!("0x{}", hex::encode(data)),
+        });
+        let gas: String = self
+            .request("eth_estimateGas", rpc_params![call, "latest"])
+            .await?;
+        u64::from_str_radix(gas.trim_start_matches("0x"), 16)
+            .map_err(|e| format!("Invalid gas '{gas}' in eth_estimateGas response: {e}").into())
+    }
+}
+
+fn parse_bytes(value: &str) -> Result<Bytes, String> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map(Bytes::from)
+        .map_err(|e| format!("Invalid hex data '{value}' in Ethereum response: {e}"))
+}
+
+/// The first four bytes of the keccak256 hash of a function signature such as
+/// `implementation()`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = sp_crypto_hashing::keccak_256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
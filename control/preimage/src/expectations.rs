@@ -0,0 +1,145 @@
+use super::asset_hub_runtime::runtime_types::{
+    frame_system, pallet_utility, pallet_xcm, snowbridge_pallet_system_frontend,
+    staging_xcm::v5::{
+        junction::Junction, junctions::Junctions, location::Location, Instruction, Xcm,
+    },
+    xcm::{VersionedLocation, VersionedXcm},
+};
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::bridge_hub_runtime::runtime_types::{
+    frame_system as bh_frame_system, snowbridge_pallet_ethereum_client,
+    snowbridge_pallet_inbound_queue, snowbridge_pallet_inbound_queue_v2,
+    snowbridge_pallet_outbound_queue, snowbridge_pallet_system,
+};
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::constants::BRIDGE_HUB_ID;
+use codec::{DecodeAll, Encode};
+use snowbridge_preimage_chopsticks::{Chain, StorageExpectation};
+use sp_crypto_hashing::twox_128;
+
+/// Storage values the final call is expected to leave behind on AssetHub and BridgeHub,
+/// checked by the chopsticks verification script.
+///
+/// Only writes whose resulting value is known up front are covered. Gateway operating
+/// modes are skipped as they take effect on Ethereum.
+pub fn storage_expectations(call: &AssetHubRuntimeCall) -> Vec<StorageExpectation> {
+    let mut expectations = vec![];
+    asset_hub_call(call, &mut expectations);
+
+    // The last write to a key wins
+    let mut deduped: Vec<StorageExpectation> = vec![];
+    for expectation in expectations.into_iter().rev() {
+        if !deduped
+            .iter()
+            .any(|e| e.chain() == expectation.chain() && e.key() == expectation.key())
+        {
+            deduped.push(expectation);
+        }
+    }
+    deduped.reverse();
+    deduped
+}
+
+fn storage_value_key(pallet: &str, storage: &str) -> Vec<u8> {
+    [twox_128(pallet.as_bytes()), twox_128(storage.as_bytes())].concat()
+}
+
+fn asset_hub_call(call: &AssetHubRuntimeCall, expectations: &mut Vec<StorageExpectation>) {
+    if let Some(call) = super::unwrap_sudo(call) {
+        return asset_hub_call(call, expectations);
+    }
+    if let Some((_, call)) = super::wrappers::unwrap(call) {
+        return asset_hub_call(call, expectations);
+    }
+    match call {
+        AssetHubRuntimeCall::Utility(
+            pallet_utility::pallet::Call::batch { calls }
+            | pallet_utility::pallet::Call::batch_all { calls }
+            | pallet_utility::pallet::Call::force_batch { calls },
+        ) => {
+            for call in calls {
+                asset_hub_call(call, expectations);
+            }
+        }
+        AssetHubRuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send { dest, message }) => {
+            let to_bridge_hub = matches!(
+                dest.as_ref(),
+                VersionedLocation::V5(Location {
+                    parents: 1,
+                    interior: Junctions::X1([Junction::Parachain(id)]),
+                }) if *id == BRIDGE_HUB_ID
+            );
+            let VersionedXcm::V5(Xcm(instructions)) = message.as_ref() else {
+                return;
+            };
+            if !to_bridge_hub {
+                return;
+            }
+            for instruction in instructions {
+                let Instruction::Transact { call, .. } = instruction else {
+                    continue;
+                };
+                if let Ok(call) = BridgeHubRuntimeCall::decode_all(&mut &call.encoded[..]) {
+                    bridge_hub_call(&call, expectations);
+                }
+            }
+        }
+        AssetHubRuntimeCall::System(frame_system::pallet::Call::set_storage { items }) => {
+            for (key, value) in items {
+                expectations.push(StorageExpectation::new(
+                    Chain::AssetHub,
+                    "System.set_storage",
+                    key,
+                    value,
+                ));
+            }
+        }
+        AssetHubRuntimeCall::SnowbridgeSystemFrontend(
+            snowbridge_pallet_system_frontend::pallet::Call::set_operating_mode { mode },
+        ) => expectations.push(StorageExpectation::new(
+            Chain::AssetHub,
+            "SnowbridgeSystemFrontend.ExportOperatingMode",
+            &storage_value_key("SnowbridgeSystemFrontend", "ExportOperatingMode"),
+            &mode.encode(),
+        )),
+        _ => {}
+    }
+}
+
+fn bridge_hub_call(call: &BridgeHubRuntimeCall, expectations: &mut Vec<StorageExpectation>) {
+    let (pallet, storage, value) = match call {
+        BridgeHubRuntimeCall::System(bh_frame_system::pallet::Call::set_storage { items }) => {
+            for (key, value) in items {
+                expectations.push(StorageExpectation::new(
+                    Chain::BridgeHub,
+                    "System.set_storage",
+                    key,
+                    value,
+                ));
+            }
+            return;
+        }
+        BridgeHubRuntimeCall::EthereumSystem(
+            snowbridge_pallet_system::pallet::Call::set_pricing_parameters { params },
+        ) => ("EthereumSystem", "PricingParameters", params.encode()),
+        BridgeHubRuntimeCall::EthereumInboundQueue(
+            snowbridge_pallet_inbound_queue::pallet::Call::set_operating_mode { mode },
+        ) => ("EthereumInboundQueue", "OperatingMode", mode.encode()),
+        BridgeHubRuntimeCall::EthereumInboundQueueV2(
+            snowbridge_pallet_inbound_queue_v2::pallet::Call::set_operating_mode { mode },
+        ) => ("EthereumInboundQueueV2", "OperatingMode", mode.encode()),
+        BridgeHubRuntimeCall::EthereumOutboundQueue(
+            snowbridge_pallet_outbound_queue::pallet::Call::set_operating_mode { mode },
+        ) => ("EthereumOutboundQueue", "OperatingMode", mode.encode()),
+        BridgeHubRuntimeCall::EthereumBeaconClient(
+            snowbridge_pallet_ethereum_client::pallet::Call::set_operating_mode { mode },
+        ) => ("EthereumBeaconClient", "OperatingMode", mode.encode()),
+        _ => return,
+    };
+    expectations.push(StorageExpectation::new(
+        Chain::BridgeHub,
+        format!("{pallet}.{storage}"),
+        &storage_value_key(pallet, storage),
+        &value,
+    ));
+}
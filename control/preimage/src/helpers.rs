@@ -2,12 +2,15 @@ use bridge_hub_runtime::ethereum_system::storage::types::pricing_parameters::Pri
 use codec::Encode;
 use subxt::{utils::H160, utils::H256, OnlineClient, PolkadotConfig};
 
-use crate::constants::BRIDGE_HUB_ID;
+use super::constants::{BRIDGE_HUB_ID, POLKADOT_DECIMALS};
+use crate::metadata_check::{call_name_by_index, event_name_by_index};
+use crate::report::TransactWeight;
+use crate::weights::WeightProvider;
 use crate::Context;
 
-use crate::bridge_hub_runtime::{self, RuntimeCall as BridgeHubRuntimeCall};
+use super::bridge_hub_runtime::{self, RuntimeCall as BridgeHubRuntimeCall};
 
-use crate::asset_hub_runtime::runtime_types::{
+use super::asset_hub_runtime::runtime_types::{
     pallet_xcm,
     sp_weights::weight_v2::Weight,
     staging_xcm::v5::{
@@ -22,7 +25,7 @@ use crate::asset_hub_runtime::runtime_types::{
     xcm::{VersionedLocation, VersionedXcm},
 };
 
-use crate::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
 
 use bridge_hub_runtime::runtime_types::snowbridge_outbound_queue_primitives::v1::message::{
     AgentExecuteCommand, Command, Fee,
@@ -47,17 +50,52 @@ pub fn increase_weight(ref_time: &mut u64, proof_size: &mut u64) {
     *proof_size = _proof_size.try_into().expect("overflow");
 }
 
+/// The `fallback_max_weight` for a `Transact` of `call` on BridgeHub.
+pub async fn transact_weight(
+    context: &Context,
+    call: &BridgeHubRuntimeCall,
+) -> Result<Option<(u64, u64)>, Box<dyn std::error::Error>> {
+    let (queried, weight) = match &context.weight_provider {
+        WeightProvider::Rpc(api) => {
+            let queried = query_weight_bridge_hub(api, call.clone()).await?;
+            let (mut ref_time, mut proof_size) = queried;
+            increase_weight(&mut ref_time, &mut proof_size);
+            (Some(queried), Some((ref_time, proof_size)))
+        }
+        WeightProvider::Table(table) => {
+            let weight = table.weight(&call_name(call))?;
+            (None, Some((weight.ref_time, weight.proof_size)))
+        }
+        WeightProvider::NoFallback => (None, None),
+        WeightProvider::Unavailable => {
+            return Err(format!(
+                "No Transact weight for {} offline: pass --weights or --no-fallback-weight",
+                call_name(call)
+            )
+            .into())
+        }
+    };
+    context
+        .report
+        .borrow_mut()
+        .transact_weights
+        .push(TransactWeight {
+            call: call_name(call),
+            queried: queried.map(Into::into),
+            fallback_max_weight: weight.map(Into::into),
+        });
+    Ok(weight)
+}
+
 pub async fn send_xcm_bridge_hub(
     context: &Context,
     calls: Vec<BridgeHubRuntimeCall>,
 ) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
-    let mut accum: Vec<(u64, u64, Vec<u8>)> = vec![];
+    let mut accum: Vec<(Option<(u64, u64)>, Vec<u8>)> = vec![];
 
     for call in calls.iter() {
-        let (mut ref_time, mut proof_size) =
-            query_weight_bridge_hub(&context.bridge_hub_api, call.clone()).await?;
-        increase_weight(&mut ref_time, &mut proof_size);
-        accum.push((ref_time, proof_size, call.encode()));
+        let weight = transact_weight(context, call).await?;
+        accum.push((weight, call.encode()));
     }
 
     let mut instructions: Vec<Instruction> = vec![UnpaidExecution {
@@ -65,13 +103,13 @@ pub async fn send_xcm_bridge_hub(
         check_origin: None,
     }];
 
-    for (ref_time, proof_size, encoded) in accum.into_iter() {
+    for (weight, encoded) in accum.into_iter() {
         instructions.append(&mut vec![
             Transact {
                 origin_kind: OriginKind::Superuser,
-                fallback_max_weight: Some(Weight {
-                    ref_time: ref_time,
-                    proof_size: proof_size,
+                fallback_max_weight: weight.map(|(ref_time, proof_size)| Weight {
+                    ref_time,
+                    proof_size,
                 }),
                 call: DoubleEncoded { encoded },
             },
@@ -101,6 +139,39 @@ pub async fn send_xcm_asset_hub(
     }
 }
 
+/// Dispatch already encoded relay chain calls as Root. The relay chain bindings can't
+/// decode calls, so their weight isn't queried and `Transact` is left to compute it.
+pub async fn send_xcm_relay(
+    _context: &Context,
+    calls: Vec<Vec<u8>>,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    let mut instructions: Vec<Instruction> = vec![UnpaidExecution {
+        weight_limit: WeightLimit::Unlimited,
+        check_origin: None,
+    }];
+
+    for encoded in calls.into_iter() {
+        instructions.append(&mut vec![
+            Transact {
+                origin_kind: OriginKind::Superuser,
+                fallback_max_weight: None,
+                call: DoubleEncoded { encoded },
+            },
+            ExpectTransactStatus(MaybeErrorCode::Success),
+        ]);
+    }
+
+    let call = AssetHubRuntimeCall::PolkadotXcm(pallet_xcm::pallet::Call::send {
+        dest: Box::new(VersionedLocation::V5(Location {
+            parents: 1,
+            interior: Junctions::Here,
+        })),
+        message: Box::new(VersionedXcm::V5(Xcm(instructions))),
+    });
+
+    Ok(call)
+}
+
 pub async fn query_weight_bridge_hub(
     api: &OnlineClient<PolkadotConfig>,
     call: BridgeHubRuntimeCall,
@@ -117,12 +188,11 @@ pub async fn query_weight_bridge_hub(
     Ok((call_info.weight.ref_time, call_info.weight.proof_size))
 }
 
-#[allow(dead_code)]
 pub async fn query_weight_asset_hub(
     api: &OnlineClient<PolkadotConfig>,
     call: AssetHubRuntimeCall,
 ) -> Result<(u64, u64), Box<dyn std::error::Error>> {
-    let runtime_api_call = crate::asset_hub_runtime::apis()
+    let runtime_api_call = super::asset_hub_runtime::apis()
         .transaction_payment_call_api()
         .query_call_info(call, 0);
     let call_info = api
@@ -138,7 +208,7 @@ pub async fn query_weight_asset_hub(
 /// fails. The default for governance batches that must commit atomically.
 pub fn utility_batch_all(calls: Vec<AssetHubRuntimeCall>) -> AssetHubRuntimeCall {
     AssetHubRuntimeCall::Utility(
-        crate::asset_hub_runtime::runtime_types::pallet_utility::pallet::Call::batch_all { calls },
+        super::asset_hub_runtime::runtime_types::pallet_utility::pallet::Call::batch_all { calls },
     )
 }
 
@@ -147,7 +217,7 @@ pub fn utility_batch_all(calls: Vec<AssetHubRuntimeCall>) -> AssetHubRuntimeCall
 #[allow(dead_code)]
 pub fn utility_batch(calls: Vec<AssetHubRuntimeCall>) -> AssetHubRuntimeCall {
     AssetHubRuntimeCall::Utility(
-        crate::asset_hub_runtime::runtime_types::pallet_utility::pallet::Call::batch { calls },
+        super::asset_hub_runtime::runtime_types::pallet_utility::pallet::Call::batch { calls },
     )
 }
 
@@ -157,32 +227,25 @@ pub fn utility_batch(calls: Vec<AssetHubRuntimeCall>) -> AssetHubRuntimeCall {
 /// (e.g. HRMP transport failure for the BH XCM) should not skip the rest.
 pub fn utility_force_batch(calls: Vec<AssetHubRuntimeCall>) -> AssetHubRuntimeCall {
     AssetHubRuntimeCall::Utility(
-        crate::asset_hub_runtime::runtime_types::pallet_utility::pallet::Call::force_batch {
+        super::asset_hub_runtime::runtime_types::pallet_utility::pallet::Call::force_batch {
             calls,
         },
     )
 }
 
-#[cfg(any(feature = "westend", feature = "paseo"))]
-pub fn sudo(call: Box<AssetHubRuntimeCall>) -> AssetHubRuntimeCall {
-    return AssetHubRuntimeCall::Sudo(
-        crate::asset_hub_runtime::runtime_types::pallet_sudo::pallet::Call::sudo { call },
-    );
-}
-
 pub fn force_xcm_version() -> AssetHubRuntimeCall {
-    use crate::asset_hub_runtime::runtime_types::staging_xcm::v5::{
+    use super::asset_hub_runtime::runtime_types::staging_xcm::v5::{
         junction::Junction::GlobalConsensus, junction::NetworkId, junctions::Junctions::X1,
         location::Location,
     };
-    let chain_id = crate::bridge_hub_runtime::CHAIN_ID;
+    let chain_id = super::bridge_hub_runtime::CHAIN_ID;
     let location = Box::new(Location {
         parents: 2,
         interior: X1([GlobalConsensus(NetworkId::Ethereum { chain_id })]),
     });
 
     AssetHubRuntimeCall::PolkadotXcm(
-        crate::asset_hub_runtime::runtime_types::pallet_xcm::pallet::Call::force_xcm_version {
+        super::asset_hub_runtime::runtime_types::pallet_xcm::pallet::Call::force_xcm_version {
             location,
             version: 4,
         },
@@ -201,6 +264,15 @@ pub async fn calculate_delivery_fee(
             amount: 0,
         },
     };
+    calculate_fee(api, command, params).await
+}
+
+/// Fee charged by the V1 outbound queue for delivering `command` with the pricing `params`.
+pub async fn calculate_fee(
+    api: &OnlineClient<PolkadotConfig>,
+    command: Command,
+    params: &PricingParameters,
+) -> Result<Fee<u128>, Box<dyn std::error::Error>> {
     let runtime_api_call = bridge_hub_runtime::apis()
         .outbound_queue_api()
         .calculate_fee(command, Some(params.clone()));
@@ -213,3 +285,105 @@ pub async fn calculate_delivery_fee(
 
     Ok(fee)
 }
+
+/// Delivery fees with the pricing `params` of the V1 commands sent for user messages.
+///
+/// The fee only depends on the kind of command, so all fields are left zero or empty.
+pub async fn command_fees(
+    api: &OnlineClient<PolkadotConfig>,
+    params: &PricingParameters,
+) -> Result<Vec<(&'static str, Fee<u128>)>, Box<dyn std::error::Error>> {
+    let commands = [
+        (
+            "UnlockNativeToken",
+            Command::UnlockNativeToken {
+                agent_id: H256::zero(),
+                token: H160::zero(),
+                recipient: H160::zero(),
+                amount: 0,
+            },
+        ),
+        (
+            "RegisterForeignToken",
+            Command::RegisterForeignToken {
+                token_id: H256::zero(),
+                name: vec![],
+                symbol: vec![],
+                decimals: 0,
+            },
+        ),
+        (
+            "MintForeignToken",
+            Command::MintForeignToken {
+                token_id: H256::zero(),
+                recipient: H160::zero(),
+                amount: 0,
+            },
+        ),
+        (
+            "AgentExecute",
+            Command::AgentExecute {
+                agent_id: H256::zero(),
+                command: AgentExecuteCommand::TransferToken {
+                    token: H160::zero(),
+                    recipient: H160::zero(),
+                    amount: 0,
+                },
+            },
+        ),
+    ];
+    let mut fees = vec![];
+    for (name, command) in commands {
+        fees.push((name, calculate_fee(api, command, params).await?));
+    }
+    Ok(fees)
+}
+
+/// A runtime call or event of AssetHub or BridgeHub, named from the bundled metadata the
+/// bindings were generated from.
+pub trait RuntimeVariant: Encode {
+    const METADATA: &'static [u8];
+    const EVENT: bool;
+}
+
+impl RuntimeVariant for AssetHubRuntimeCall {
+    const METADATA: &'static [u8] = super::asset_hub_runtime::METADATA;
+    const EVENT: bool = false;
+}
+
+impl RuntimeVariant for super::asset_hub_runtime::RuntimeEvent {
+    const METADATA: &'static [u8] = super::asset_hub_runtime::METADATA;
+    const EVENT: bool = true;
+}
+
+impl RuntimeVariant for BridgeHubRuntimeCall {
+    const METADATA: &'static [u8] = bridge_hub_runtime::METADATA;
+    const EVENT: bool = false;
+}
+
+impl RuntimeVariant for bridge_hub_runtime::RuntimeEvent {
+    const METADATA: &'static [u8] = bridge_hub_runtime::METADATA;
+    const EVENT: bool = true;
+}
+
+/// The `Pallet.call_name` (or `Pallet.EventName`) of a runtime call or event, looked up by
+/// its encoded pallet and variant indices, e.g. `EthereumSystem.set_operating_mode`.
+pub fn call_name<T: RuntimeVariant>(call: &T) -> String {
+    let encoded = call.encode();
+    let name = if T::EVENT {
+        event_name_by_index(T::METADATA, &encoded)
+    } else {
+        call_name_by_index(T::METADATA, &encoded)
+    };
+    name.unwrap_or_else(|| {
+        format!(
+            "Unknown 0x{}",
+            hex::encode(&encoded[..encoded.len().min(2)])
+        )
+    })
+}
+
+/// Convert an amount in the relay chain's native token (e.g. `0.01`) to planck.
+pub fn parse_units_polkadot(v: &str) -> Result<u128, String> {
+    crate::parse_units_relay(v, POLKADOT_DECIMALS)
+}
@@ -0,0 +1,545 @@
+//! Gateway upgrade initializers: encoding the `initialize(bytes)` payload from the ABI of the
+//! new logic contract, and estimating the gas the upgrade needs.
+
+use crate::ethereum::{selector, EthereumClient};
+use alloy_dyn_abi::{DynSolType, DynSolValue, ResolveSolType};
+use alloy_json_abi::{JsonAbi, Param};
+use alloy_primitives::{Address, FixedBytes, U256};
+use serde_json::Value;
+use std::path::Path;
+use subxt::ext::subxt_rpcs::client::rpc_params;
+
+/// Intrinsic gas of a transaction, which is not spent by the upgrade itself.
+const TRANSACTION_GAS: u64 = 21_000;
+
+/// Calldata gas per zero and per non-zero byte (EIP-2028).
+const ZERO_BYTE_GAS: u64 = 4;
+const NON_ZERO_BYTE_GAS: u64 = 16;
+
+/// `CommandKind.Upgrade` of the V2 Gateway.
+const COMMAND_KIND_UPGRADE: u8 = 0;
+
+/// Percentage added to an estimated `maximum_required_gas` by default.
+pub const DEFAULT_GAS_MARGIN_PERCENT: u64 = 25;
+
+/// The `initialize(bytes)` payload, and a line per leaf value for review.
+pub struct EncodedInitializer {
+    pub params: Vec<u8>,
+    pub decoded: Vec<String>,
+}
+
+/// ABI-encode `args`, a JSON object keyed by input name, as the inputs of the function or
+/// error `name` in `abi_path` (a Foundry artifact or a bare ABI).
+///
+/// Logic contracts take the payload as opaque `bytes` and `abi.decode` it themselves, so
+/// `name` should be an item whose inputs match that decoding, such as a function taking the
+/// initializer's `Config` struct.
+pub fn encode(
+    abi_path: &Path,
+    name: &str,
+    args: &Value,
+) -> Result<EncodedInitializer, Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(abi_path)
+        .map_err(|e| format!("Failed to read ABI {}: {e}", abi_path.display()))?;
+    let mut abi: Value = serde_json::from_str(&data)
+        .map_err(|e| format!("Invalid ABI {}: {e}", abi_path.display()))?;
+    // Foundry artifacts hold the ABI under `abi`
+    if let Some(inner) = abi.get_mut("abi") {
+        abi = inner.take();
+    }
+    // Parsed from a string, as ABI items borrow their names while deserializing
+    let abi: JsonAbi = serde_json::from_str(&abi.to_string())
+        .map_err(|e| format!("Invalid ABI {}: {e}", abi_path.display()))?;
+    let functions = abi.function(name).into_iter().flatten();
+    let errors = abi.error(name).into_iter().flatten();
+    let mut items = functions
+        .map(|function| &function.inputs)
+        .chain(errors.map(|error| &error.inputs));
+    let inputs = match (items.next(), items.next()) {
+        (Some(inputs), None) => inputs,
+        (None, _) => return Err(format!("No '{name}' in ABI {}", abi_path.display()).into()),
+        (Some(_), Some(_)) => {
+            return Err(format!("'{name}' is overloaded in ABI {}", abi_path.display()).into())
+        }
+    };
+    if matches!(&inputs[..], [input] if input.ty == "bytes") {
+        return Err(format!(
+            "'{name}' takes opaque bytes. Pass --initializer-type naming an ABI item whose \
+             inputs describe the initializer params"
+        )
+        .into());
+    }
+
+    let mut decoded = vec![];
+    let values = match args {
+        Value::Object(_) | Value::Array(_) => components(inputs, args, "", &mut decoded)?,
+        Value::Null if inputs.is_empty() => vec![],
+        _ => return Err("--initializer-args must be a JSON object keyed by input name".into()),
+    };
+    Ok(EncodedInitializer {
+        params: DynSolValue::Tuple(values).abi_encode_params(),
+        decoded,
+    })
+}
+
+/// Convert the fields of a tuple from a JSON object keyed by name, or a positional array.
+fn components(
+    params: &[Param],
+    value: &Value,
+    path: &str,
+    decoded: &mut Vec<String>,
+) -> Result<Vec<DynSolValue>, String> {
+    let field_path = |name: &str, index: usize| {
+        let name = match name {
+            "" => index.to_string(),
+            name => name.to_string(),
+        };
+        match path {
+            "" => name,
+            path => format!("{path}.{name}"),
+        }
+    };
+    let resolve = |param: &Param| {
+        param
+            .resolve()
+            .map_err(|e| format!("Unsupported ABI type '{}': {e}", param.ty))
+    };
+    match value {
+        Value::Object(fields) => {
+            if let Some(unknown) = fields
+                .keys()
+                .find(|key| !params.iter().any(|param| param.name == **key))
+            {
+                return Err(format!(
+                    "Unknown initializer argument '{}'",
+                    field_path(unknown, 0)
+                ));
+            }
+            params
+                .iter()
+                .enumerate()
+                .map(|(index, param)| {
+                    let path = field_path(&param.name, index);
+                    let ty = resolve(param)?;
+                    let value = fields.get(&param.name).ok_or_else(|| {
+                        format!(
+                            "Missing initializer argument '{path}' ({})",
+                            ty.sol_type_name()
+                        )
+                    })?;
+                    to_value(&ty, &param.components, value, &path, decoded)
+                })
+                .collect()
+        }
+        Value::Array(values) if values.len() == params.len() => params
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(index, (param, value))| {
+                let path = field_path(&param.name, index);
+                to_value(&resolve(param)?, &param.components, value, &path, decoded)
+            })
+            .collect(),
+        _ => Err(format!(
+            "Expected an object with {} fields for '{path}'",
+            params.len()
+        )),
+    }
+}
+
+/// Convert `value` to `ty`. `fields` names the fields of a tuple, or of the tuples in an
+/// array. Leaf values are JSON strings, or numbers and booleans, coerced as by `cast`, such
+/// as `"0x123"`, `"-1"` or `"1 gwei"`.
+fn to_value(
+    ty: &DynSolType,
+    fields: &[Param],
+    value: &Value,
+    path: &str,
+    decoded: &mut Vec<String>,
+) -> Result<DynSolValue, String> {
+    let name = ty.sol_type_name();
+    let invalid = || format!("Invalid {name} for '{path}': {value}");
+    match ty {
+        DynSolType::Tuple(_) => Ok(DynSolValue::Tuple(components(
+            fields, value, path, decoded,
+        )?)),
+        DynSolType::Array(element) | DynSolType::FixedArray(element, _) => {
+            let values = value.as_array().ok_or_else(invalid)?;
+            if let DynSolType::FixedArray(_, length) = ty {
+                if values.len() != *length {
+                    return Err(invalid());
+                }
+            }
+            let elements = values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let path = format!("{path}[{index}]");
+                    to_value(element, fields, value, &path, decoded)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(match ty {
+                DynSolType::Array(_) => DynSolValue::Array(elements),
+                _ => DynSolValue::FixedArray(elements),
+            })
+        }
+        _ => {
+            let string = match value {
+                Value::String(string) => string.clone(),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => return Err(invalid()),
+            };
+            let leaf = ty
+                .coerce_str(&string)
+                .map_err(|e| format!("{}: {e}", invalid()))?;
+            let display = match &leaf {
+                // The parser only bounds the magnitude of negative integers
+                DynSolValue::Int(number, bits)
+                    if number.unsigned_abs() > U256::from(1) << (bits - 1) =>
+                {
+                    return Err(invalid())
+                }
+                DynSolValue::Int(number, _) => number.to_string(),
+                DynSolValue::Uint(number, _) => number.to_string(),
+                DynSolValue::Address(address) => address.to_string(),
+                DynSolValue::Bool(flag) => flag.to_string(),
+                DynSolValue::FixedBytes(word, size) => format!("0x{}", hex::encode(&word[..*size])),
+                DynSolValue::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+                DynSolValue::String(string) => format!("{string:?}"),
+                _ => return Err(format!("Unsupported ABI type '{name}' for '{path}'")),
+            };
+            decoded.push(format!("{path} ({name}): {display}"));
+            Ok(leaf)
+        }
+    }
+}
+
+/// Estimate the gas used by the Gateway to handle the upgrade: the `upgrade` handler that
+/// the Gateway dispatches to itself, which checks the code hash, sets the implementation and
+/// `delegatecall`s `initialize(params)` on the new logic contract.
+///
+/// The handler only accepts calls from the Gateway itself, so this needs an anvil node,
+/// typically forking Ethereum, on which the Gateway can be impersonated.
+pub async fn estimate_gas(
+    ethereum: &EthereumClient,
+    gateway: Address,
+    logic_address: Address,
+    logic_code_hash: FixedBytes<32>,
+    params: &[u8],
+    v2: bool,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let data = upgrade_calldata(logic_address, logic_code_hash, params, v2);
+    let gateway_param = gateway.to_string();
+    let _: Value = ethereum
+        .request("anvil_impersonateAccount", rpc_params![&gateway_param])
+        .await
+        .map_err(|e| {
+            format!(
+                "{e}. Estimating the upgrade gas needs a forked anvil, or pass --initializer-gas"
+            )
+        })?;
+    let gas = ethereum.estimate_gas(gateway, gateway, &data).await;
+    let _: Value = ethereum
+        .request(
+            "anvil_stopImpersonatingAccount",
+            rpc_params![&gateway_param],
+        )
+        .await?;
+    Ok(gas?.saturating_sub(intrinsic_gas(&data)))
+}
+
+/// Calldata of the call through which the Gateway dispatches an upgrade to itself:
+/// `v1_handleUpgrade(bytes)`, or `v2_dispatchCommand(Command, bytes32)` for V2, with the
+/// ABI-encoded `UpgradeParams` as the payload.
+fn upgrade_calldata(
+    logic_address: Address,
+    logic_code_hash: FixedBytes<32>,
+    params: &[u8],
+    v2: bool,
+) -> Vec<u8> {
+    let upgrade_params = DynSolValue::Tuple(vec![
+        DynSolValue::Address(logic_address),
+        DynSolValue::FixedBytes(logic_code_hash, 32),
+        DynSolValue::Bytes(params.to_vec()),
+    ])
+    .abi_encode();
+    let (signature, args) = match v2 {
+        false => (
+            "v1_handleUpgrade(bytes)",
+            vec![DynSolValue::Bytes(upgrade_params)],
+        ),
+        true => (
+            "v2_dispatchCommand((uint8,uint64,bytes),bytes32)",
+            vec![
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Uint(U256::from(COMMAND_KIND_UPGRADE), 8),
+                    // The gas limit is applied by `v2_dispatch`, not read by the handler
+                    DynSolValue::Uint(U256::ZERO, 64),
+                    DynSolValue::Bytes(upgrade_params),
+                ]),
+                DynSolValue::FixedBytes(FixedBytes::ZERO, 32),
+            ],
+        ),
+    };
+    let mut data = selector(signature).to_vec();
+    data.extend(DynSolValue::Tuple(args).abi_encode_params());
+    data
+}
+
+/// Gas charged for a transaction with `data` before any code runs.
+fn intrinsic_gas(data: &[u8]) -> u64 {
+    data.iter().fold(TRANSACTION_GAS, |gas, byte| match byte {
+        0 => gas + ZERO_BYTE_GAS,
+        _ => gas + NON_ZERO_BYTE_GAS,
+    })
+}
+
+/// `gas` plus `percent`%.
+pub fn with_margin(gas: u64, percent: u64) -> u64 {
+    gas.saturating_add(gas.saturating_mul(percent) / 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// ABI-encode `args` as the inputs described by the ABI fragment `inputs`.
+    fn encode_args(inputs: Value, args: Value) -> Result<String, String> {
+        let inputs: Vec<Param> = serde_json::from_str(&inputs.to_string()).unwrap();
+        let values = components(&inputs, &args, "", &mut vec![])?;
+        Ok(hex::encode(DynSolValue::Tuple(values).abi_encode_params()))
+    }
+
+    fn words(words: &[&str]) -> String {
+        words.iter().map(|word| format!("{word:0>64}")).collect()
+    }
+
+    fn padded(bytes: &[u8]) -> String {
+        format!("{:0<64}", hex::encode(bytes))
+    }
+
+    // Examples from the Solidity ABI specification
+    #[test]
+    fn encodes_static_arguments() {
+        let encoded = encode_args(
+            json!([{ "name": "x", "type": "uint32" }, { "name": "y", "type": "bool" }]),
+            json!({ "x": 69, "y": true }),
+        );
+        assert_eq!(encoded.unwrap(), words(&["45", "1"]));
+    }
+
+    #[test]
+    fn encodes_dynamic_arguments() {
+        let encoded = encode_args(
+            json!([
+                { "name": "a", "type": "bytes" },
+                { "name": "b", "type": "bool" },
+                { "name": "c", "type": "uint256[]" },
+            ]),
+            json!({ "a": "0x64617665", "b": true, "c": [1, 2, 3] }),
+        );
+        let expected = [
+            words(&["60", "1", "a0", "4"]),
+            padded(b"dave"),
+            words(&["3", "1", "2", "3"]),
+        ]
+        .concat();
+        assert_eq!(encoded.unwrap(), expected);
+    }
+
+    #[test]
+    fn encodes_mixed_arguments() {
+        let encoded = encode_args(
+            json!([
+                { "name": "a", "type": "uint256" },
+                { "name": "b", "type": "uint32[]" },
+                { "name": "c", "type": "bytes10" },
+                { "name": "d", "type": "bytes" },
+            ]),
+            json!({
+                "a": "0x123",
+                "b": [1110, 1929],
+                "c": "0x31323334353637383930",
+                "d": "0x48656c6c6f2c20776f726c6421",
+            }),
+        );
+        let expected = [
+            words(&["123", "80"]),
+            padded(b"1234567890"),
+            words(&["e0", "2", "456", "789", "d"]),
+            padded(b"Hello, world!"),
+        ]
+        .concat();
+        assert_eq!(encoded.unwrap(), expected);
+    }
+
+    #[test]
+    fn encodes_nested_dynamic_arrays() {
+        let encoded = encode_args(
+            json!([
+                { "name": "a", "type": "uint256[][]" },
+                { "name": "b", "type": "string[]" },
+            ]),
+            json!({ "a": [[1, 2], [3]], "b": ["one", "two", "three"] }),
+        );
+        let expected = [
+            words(&["40", "140"]),
+            words(&["2", "40", "a0", "2", "1", "2", "1", "3"]),
+            words(&["3", "60", "a0", "e0"]),
+            words(&["3"]),
+            padded(b"one"),
+            words(&["3"]),
+            padded(b"two"),
+            words(&["5"]),
+            padded(b"three"),
+        ]
+        .concat();
+        assert_eq!(encoded.unwrap(), expected);
+    }
+
+    #[test]
+    fn encodes_tuples_and_fixed_arrays() {
+        // abi.encode(Config({mode: 1, name: "x"}), [uint8(2), 3])
+        let encoded = encode_args(
+            json!([
+                {
+                    "name": "config",
+                    "type": "tuple",
+                    "components": [
+                        { "name": "mode", "type": "uint8" },
+                        { "name": "name", "type": "string" },
+                    ],
+                },
+                { "name": "pair", "type": "uint8[2]" },
+            ]),
+            json!({ "config": { "mode": 1, "name": "x" }, "pair": [2, 3] }),
+        );
+        let expected = [words(&["60", "2", "3", "1", "40", "1"]), padded(b"x")].concat();
+        assert_eq!(encoded.unwrap(), expected);
+    }
+
+    #[test]
+    fn encodes_signed_integers() {
+        let inputs = json!([{ "name": "x", "type": "int8" }, { "name": "y", "type": "int256" }]);
+        let encoded = encode_args(inputs.clone(), json!({ "x": -128, "y": "-1" }));
+        let expected = [format!("{:f>64}", "80"), "f".repeat(64)].concat();
+        assert_eq!(encoded.unwrap(), expected);
+        assert!(encode_args(inputs.clone(), json!({ "x": 128, "y": 0 })).is_err());
+        assert!(encode_args(inputs, json!({ "x": "-129", "y": 0 })).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let inputs = json!([{ "name": "x", "type": "uint8" }]);
+        assert_eq!(
+            encode_args(inputs.clone(), json!({ "x": 255 })).unwrap(),
+            words(&["ff"])
+        );
+        assert!(encode_args(inputs.clone(), json!({ "x": 256 })).is_err());
+        assert!(encode_args(inputs.clone(), json!({})).is_err());
+        assert!(encode_args(inputs, json!({ "x": 1, "y": 2 })).is_err());
+        let inputs = json!([{ "name": "x", "type": "bytes4" }]);
+        assert!(encode_args(inputs, json!({ "x": "0x010203" })).is_err());
+    }
+
+    #[test]
+    fn encodes_from_foundry_artifact() {
+        let artifact = json!({
+            "abi": [
+                {
+                    "type": "function",
+                    "name": "initialize",
+                    "inputs": [{ "name": "data", "type": "bytes", "internalType": "bytes" }],
+                    "outputs": [],
+                    "stateMutability": "nonpayable",
+                },
+                {
+                    "type": "function",
+                    "name": "initializeConfig",
+                    "inputs": [{ "name": "mode", "type": "uint8", "internalType": "uint8" }],
+                    "outputs": [],
+                    "stateMutability": "pure",
+                },
+            ],
+            "bytecode": { "object": "0x__$b1e4e3d0f5a2c7d8e9f0a1b2c3d4e5f6a7$__" },
+        });
+        let path = std::env::temp_dir().join("snowbridge-preimage-initializer-artifact.json");
+        std::fs::write(&path, artifact.to_string()).unwrap();
+        let encoded = encode(&path, "initializeConfig", &json!({ "mode": 1 })).unwrap();
+        assert_eq!(hex::encode(encoded.params), words(&["1"]));
+        assert_eq!(encoded.decoded, ["mode (uint8): 1"]);
+        assert!(encode(&path, "initialize", &json!({ "data": "0x" })).is_err());
+        assert!(encode(&path, "missing", &json!({})).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn lists_decoded_values() {
+        let inputs = json!([
+            {
+                "name": "config",
+                "type": "tuple",
+                "components": [
+                    { "name": "owner", "type": "address" },
+                    { "name": "fees", "type": "uint128[]" },
+                ],
+            },
+        ]);
+        let inputs: Vec<Param> = serde_json::from_str(&inputs.to_string()).unwrap();
+        let args = json!({
+            "config": {
+                "owner": "0x0000000000000000000000000000000000000001",
+                "fees": ["1 gwei", 2],
+            },
+        });
+        let mut decoded = vec![];
+        components(&inputs, &args, "", &mut decoded).unwrap();
+        assert_eq!(
+            decoded,
+            [
+                "config.owner (address): 0x0000000000000000000000000000000000000001",
+                "config.fees[0] (uint128): 1000000000",
+                "config.fees[1] (uint128): 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_upgrade_calldata() {
+        let logic = Address::repeat_byte(0x11);
+        let hash = FixedBytes::repeat_byte(0x22);
+        let payload = [
+            words(&["20", &"11".repeat(20), &"22".repeat(32), "60", "1"]),
+            padded(&[0xab]),
+        ]
+        .concat();
+
+        let data = hex::encode(upgrade_calldata(logic, hash, &[0xab], false));
+        let v1 = [
+            hex::encode(selector("v1_handleUpgrade(bytes)")),
+            words(&["20", "c0"]),
+        ];
+        assert_eq!(data, [v1.concat(), payload.clone()].concat());
+
+        let data = hex::encode(upgrade_calldata(logic, hash, &[0xab], true));
+        let v2 = [
+            hex::encode(selector("v2_dispatchCommand((uint8,uint64,bytes),bytes32)")),
+            words(&["40", "0", "0", "0", "60", "c0"]),
+        ];
+        assert_eq!(data, [v2.concat(), payload].concat());
+    }
+
+    #[test]
+    fn subtracts_intrinsic_gas() {
+        assert_eq!(intrinsic_gas(&[]), 21_000);
+        assert_eq!(intrinsic_gas(&[0, 1, 0, 2]), 21_000 + 2 * 4 + 2 * 16);
+    }
+
+    #[test]
+    fn adds_gas_margin() {
+        assert_eq!(with_margin(100_000, 25), 125_000);
+        assert_eq!(with_margin(100_000, 0), 100_000);
+        assert_eq!(with_margin(u64::MAX, 25), u64::MAX);
+    }
+}
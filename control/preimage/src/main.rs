@@ -1,45 +1,108 @@
-mod asset_hub_runtime;
-mod bridge_hub_runtime;
-mod commands;
+mod call_tree;
+mod checkpoint;
 mod constants;
-mod helpers;
-#[allow(unused)]
-mod relay_runtime;
-mod treasury_commands;
-
-use alloy_primitives::{address, utils::parse_units, Address, Bytes, FixedBytes, U128, U256};
-use clap::{Args, Parser, Subcommand, ValueEnum};
-use codec::Encode;
-use constants::{ASSET_HUB_API, BRIDGE_HUB_API, POLKADOT_DECIMALS, POLKADOT_SYMBOL, RELAY_API};
-use helpers::{
-    force_xcm_version, send_xcm_asset_hub, send_xcm_bridge_hub, utility_batch_all,
-    utility_force_batch,
-};
-use snowbridge_preimage_chopsticks::generate_chopsticks_script;
-use sp_crypto_hashing::blake2_256;
-use std::{io::Write, path::PathBuf};
-use subxt::{OnlineClient, PolkadotConfig};
+mod dynamic_call;
+mod ethereum;
+mod initializer;
+mod metadata_check;
+mod paseo;
+mod polkadot;
+mod report;
+mod snapshot;
+mod suggest_pricing;
+mod time;
+mod treasury_schedule;
+mod weights;
+mod westend;
 
-#[cfg(any(feature = "westend", feature = "paseo"))]
-use crate::helpers::sudo;
+use alloy_primitives::{utils::parse_units, Address, Bytes, FixedBytes, U256};
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use constants::Network;
+use ethereum::EthereumClient;
+use metadata_check::ChainCall;
+use report::Report;
+use serde::Deserialize;
+use std::{cell::RefCell, path::PathBuf, process::ExitCode};
+use subxt::{OnlineClient, PolkadotConfig};
+use weights::{WeightProvider, WeightTable};
 
 #[derive(Debug, Parser)]
 #[command(name = "snowbridge-preimage", version, about, long_about = None)]
 struct Cli {
+    /// Network to generate the preimage for
+    #[arg(long, value_enum)]
+    network: Network,
+
     /// Output format of preimage
     #[arg(long, value_enum, default_value_t=Format::Hex)]
     format: Format,
 
-    /// Wrap preimage in a sudo call
-    #[cfg(any(feature = "westend", feature = "paseo"))]
+    /// Wrap preimage in a sudo call (Westend and Paseo only)
     #[arg(long, default_value_t = false)]
     sudo: bool,
 
+    /// Do not connect to any chain. Commands which need chain data will fail.
+    ///
+    /// Commands sending a `Transact` to BridgeHub also need `--weights` or
+    /// `--no-fallback-weight`.
+    #[arg(long, default_value_t = false)]
+    offline: bool,
+
+    /// Use fixed `Transact` weights from a TOML or JSON file instead of querying BridgeHub
+    #[arg(long, value_name = "FILE", conflicts_with = "no_fallback_weight")]
+    weights: Option<PathBuf>,
+
+    /// Omit the `Transact` fallback weight (XCM v5), letting BridgeHub weigh each call
+    #[arg(long, default_value_t = false)]
+    no_fallback_weight: bool,
+
+    /// Emit the preimage even if calls in it changed in the live runtimes since the bundled
+    /// metadata was generated
+    #[arg(long, default_value_t = false)]
+    allow_metadata_mismatch: bool,
+
+    /// Dry-run the preimage through AssetHub and BridgeHub `DryRunApi` before emitting it
+    #[arg(long, default_value_t = false, conflicts_with = "offline")]
+    dry_run: bool,
+
+    /// Also write chopsticks xcm-mode configs (relay, AssetHub, BridgeHub) and a
+    /// `verify-preimage.mjs` script asserting the outcome of the preimage to this directory
+    #[arg(long, value_name = "DIR")]
+    chopsticks_xcm: Option<PathBuf>,
+
+    /// Governance path simulated by the chopsticks script. Defaults to `sudo` with `--sudo`,
+    /// otherwise to the `--submission` path, or `root`.
+    #[arg(long, value_enum)]
+    chopsticks_enactment: Option<ChopsticksEnactment>,
+
+    /// Schedule the call on AssetHub for an RFC 3339 time, e.g. 2026-03-01T12:00:00Z
+    #[arg(long, value_name = "TIME", value_parser = time::parse_rfc3339, group = "schedule")]
+    schedule_at: Option<i64>,
+
+    /// Schedule the call on AssetHub after a duration, e.g. 36h or 7d
+    #[arg(long, value_name = "DURATION", value_parser = time::parse_duration, group = "schedule")]
+    schedule_after: Option<u64>,
+
+    /// Use `schedule_named` with the blake2_256 hash of NAME as id, so the call can be cancelled
+    #[arg(long, value_name = "NAME", requires = "schedule")]
+    schedule_name: Option<String>,
+
+    #[command(flatten)]
+    origin: OriginArgs,
+
+    #[command(flatten)]
+    submission: SubmissionArgs,
+
     #[command(flatten)]
     api_endpoints: ApiEndpoints,
 
     #[command(subcommand)]
     command: Command,
+
+    /// Set by `verify`: check that the preimage noted on AssetHub under this hash is the
+    /// same, and show a diff of the decoded calls if it is not
+    #[arg(skip)]
+    verify_hash: Option<FixedBytes<32>>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -50,6 +113,10 @@ pub enum Command {
     UpdateAsset(UpdateAssetArgs),
     /// Upgrade the Gateway contract
     Upgrade(UpgradeArgs),
+    /// Set the fees the Gateway charges for registering and sending tokens
+    TokenTransferFees(TokenTransferFeesArgs),
+    /// Register a Polkadot native asset for export to Ethereum
+    RegisterToken(RegisterTokenArgs),
     /// Change the gateway operating mode
     GatewayOperatingMode(GatewayOperatingModeArgs),
     /// Set pricing parameters
@@ -58,16 +125,26 @@ pub enum Command {
     ForceCheckpoint(ForceCheckpointArgs),
     /// Set the checkpoint for the beacon light client
     HaltBridge(HaltBridgeArgs),
+    /// Resume parts of the bridge halted by `halt-bridge`
+    ResumeBridge(ResumeBridgeArgs),
     /// Register Ether
     RegisterEther(RegisterEtherArgs),
     /// Treasury proposal
     TreasuryProposal2024(TreasuryProposal2024Args),
+    /// Treasury proposal from a spend schedule file
+    TreasuryProposal(TreasuryProposalArgs),
     /// Governance update 202501
     GovUpdate202501(GovUpdate202501Args),
     /// Register PNA
     RegisterPnaBatch202503,
     /// Register all ERC20 tokens metadata
     RegisterErc20TokenMetadata,
+    /// Register the PNAs and set the ERC20 metadata of a token list
+    RegisterTokens(RegisterTokensArgs),
+    /// Freeze a bridged asset on AssetHub, or single accounts holding it
+    FreezeAsset(FreezeAssetArgs),
+    /// Thaw an asset or accounts frozen by `freeze-asset`
+    ThawAsset(FreezeTargetArgs),
     /// Upgrade to V2
     UpgradeV2,
     /// Replay failed XCM messages from September 2025
@@ -79,6 +156,61 @@ pub enum Command {
     /// Upgrade to FiatShamir on Polkadot
     #[command(alias = "upgrade-202603")]
     Upgrade202603,
+    /// Dispatch any call as Root, built from JSON arguments against the bundled metadata
+    Call(CallArgs),
+    /// Build a proposal from a declarative TOML or JSON manifest
+    FromManifest(FromManifestArgs),
+    /// Decode a preimage into a call tree
+    Decode(DecodeArgs),
+    /// Suggest `pricing-parameters` arguments from ETH and DOT prices and Ethereum gas fees
+    SuggestPricing(SuggestPricingArgs),
+    /// Check that the preimage noted on AssetHub under `--hash` is the one a command generates
+    Verify(VerifyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Hash of the preimage noted on AssetHub
+    #[arg(long, value_name = "HASH", value_parser = parse_hex_bytes32)]
+    hash: FixedBytes<32>,
+    /// The command, with its arguments, whose preimage is regenerated and compared instead
+    /// of being emitted
+    #[arg(
+        value_name = "COMMAND",
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        required = true
+    )]
+    command: Vec<String>,
+}
+
+/// The command given to `verify`, which clap cannot nest in `Command` itself.
+#[derive(Debug, Parser)]
+#[command(name = "verify")]
+struct VerifiedCommand {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Replace `verify` with the command it verifies, and set `verify_hash`.
+fn unwrap_verify(mut cli: Cli) -> Result<Cli, clap::Error> {
+    let Command::Verify(params) = &cli.command else {
+        return Ok(cli);
+    };
+    let args = std::iter::once("verify".to_owned()).chain(params.command.iter().cloned());
+    let verified = VerifiedCommand::try_parse_from(args)?;
+    if matches!(
+        verified.command,
+        Command::Verify(_) | Command::Decode(_) | Command::SuggestPricing(_)
+    ) {
+        return Err(VerifiedCommand::command().error(
+            ErrorKind::InvalidSubcommand,
+            "verify takes a command that generates a preimage",
+        ));
+    }
+    cli.verify_hash = Some(params.hash);
+    cli.command = verified.command;
+    Ok(cli)
 }
 
 #[derive(Debug, Args)]
@@ -131,12 +263,92 @@ pub struct UpgradeArgs {
     logic_code_hash: FixedBytes<32>,
 
     /// ABI-encoded params to pass to initializer
-    #[arg(long, value_name = "BYTES", value_parser=parse_hex_bytes)]
-    initializer_params: Bytes,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        value_parser=parse_hex_bytes,
+        required_unless_present = "initializer_abi",
+        conflicts_with = "initializer_abi"
+    )]
+    initializer_params: Option<Bytes>,
+
+    /// Foundry artifact or ABI of the logic contract, to encode the initializer params from
+    /// `--initializer-args`
+    #[arg(long, value_name = "FILE", requires = "initializer_type")]
+    initializer_abi: Option<PathBuf>,
+
+    /// ABI item whose inputs describe the initializer params, as decoded by `initialize`
+    #[arg(long, value_name = "NAME", requires = "initializer_abi")]
+    initializer_type: Option<String>,
+
+    /// Initializer params as a JSON object keyed by input name, e.g. '{"config": {"mode": 0}}'
+    #[arg(long, value_name = "JSON", value_parser=parse_json, requires = "initializer_abi")]
+    initializer_args: Option<serde_json::Value>,
 
-    /// Maximum gas required by the initializer
+    /// Maximum gas required by the initializer. Estimated on a forked anvil if omitted
     #[arg(long, value_name = "GAS")]
-    initializer_gas: u64,
+    initializer_gas: Option<u64>,
+
+    /// Percentage added to the initializer gas estimated for the Gateway's `upgrade` handler
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = initializer::DEFAULT_GAS_MARGIN_PERCENT,
+        conflicts_with = "initializer_gas"
+    )]
+    initializer_gas_margin: u64,
+
+    /// Send the upgrade through `EthereumSystemV2` and the V2 outbound queue
+    #[arg(long)]
+    v2: bool,
+
+    /// Emit the preimage without checking the code at the logic address against
+    /// `--logic-code-hash` on Ethereum
+    #[arg(long)]
+    skip_code_check: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TokenTransferFeesArgs {
+    /// XCM fee for creating the asset on AssetHub when a token is registered, in the relay
+    /// chain token (DOT, WND or PAS)
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_relay_amount)]
+    create_asset_xcm: String,
+    /// XCM fee for a reserve transfer to AssetHub, in the relay chain token
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_relay_amount)]
+    transfer_asset_xcm: String,
+    /// Ether fee for registering a token on the Gateway
+    #[arg(long, value_name = "ETHER", value_parser = parse_units_eth)]
+    register_token: U256,
+}
+
+#[derive(Debug, Args)]
+pub struct RegisterTokenArgs {
+    /// Location of the asset relative to AssetHub or BridgeHub, in token list JSON, e.g.
+    /// '{"parents": 1, "interior": [{"Parachain": 2034}, {"GeneralIndex": 0}]}'
+    #[arg(long, value_name = "JSON", value_parser = parse_token_location)]
+    location: snowbridge_token_list::Location,
+    /// Name of the ERC20 token created on Ethereum
+    #[arg(long)]
+    name: String,
+    /// Symbol of the ERC20 token created on Ethereum
+    #[arg(long)]
+    symbol: String,
+    /// Decimals of the ERC20 token created on Ethereum
+    #[arg(long)]
+    decimals: u8,
+    /// Register through `SnowbridgeSystemFrontend` on AssetHub, which uses `EthereumSystemV2`
+    #[arg(long)]
+    v2: bool,
+    /// Ether paid to register through the V2 frontend
+    #[arg(
+        long,
+        value_name = "ETHER",
+        value_parser = parse_units_eth,
+        required_if_eq("v2", "true"),
+        requires = "v2"
+    )]
+    fee: Option<U256>,
 }
 
 #[derive(Debug, Args)]
@@ -144,15 +356,20 @@ pub struct GatewayOperatingModeArgs {
     /// Operating mode
     #[arg(long, value_enum)]
     gateway_operating_mode: GatewayOperatingModeEnum,
+    /// Set the mode through `EthereumSystemV2` and the V2 outbound queue
+    #[arg(long)]
+    v2: bool,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum GatewayOperatingModeEnum {
     Normal,
     RejectingOutboundMessages,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OperatingModeEnum {
     Normal,
     Halted,
@@ -170,6 +387,11 @@ pub struct ForceCheckpointArgs {
     /// Path to JSON file containing checkpoint
     #[arg(long, value_name = "FILE")]
     pub checkpoint: PathBuf,
+
+    /// Beacon node REST API, e.g. http://127.0.0.1:5052, to check that the checkpoint header
+    /// is finalized
+    #[arg(long, value_name = "URL")]
+    pub beacon_api: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -197,12 +419,20 @@ pub struct PricingParametersArgs {
     /// Ether fee per unit of gas
     #[arg(long, value_name = "GWEI", value_parser = parse_units_gwei)]
     pub fee_per_gas: U256,
-    /// Relayer reward for delivering messages to Polkadot
-    #[arg(long, value_name = POLKADOT_SYMBOL, value_parser = parse_units_polkadot)]
-    pub local_reward: U128,
+    /// Relayer reward for delivering messages to Polkadot, in the relay chain token (DOT, WND or PAS)
+    ///
+    /// Converted to planck using the decimals of the selected network.
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_relay_amount)]
+    pub local_reward: String,
     /// Relayer reward for delivering messages to Ethereum
     #[arg(long, value_name = "ETHER", value_parser = parse_units_eth)]
     pub remote_reward: U256,
+    /// Compare against the pricing parameters at this BridgeHub block instead of the latest
+    #[arg(long, value_name = "BLOCK_HASH", value_parser=parse_hex_bytes32)]
+    pub bridge_hub_at: Option<FixedBytes<32>>,
+    /// Compare against the `BridgeHubEthereumBaseFee` at this AssetHub block instead of the latest
+    #[arg(long, value_name = "BLOCK_HASH", value_parser=parse_hex_bytes32)]
+    pub asset_hub_at: Option<FixedBytes<32>>,
 }
 
 #[derive(Debug, Args)]
@@ -266,6 +496,59 @@ pub struct HaltBridgeArgs {
     /// Halt all parts of the bridge (equivalent to passing every other flag).
     #[arg(long, value_name = "HALT_SNOWBRIDGE")]
     all: bool,
+    /// Save the AssetHub outbound fees about to be overwritten to FILE (default
+    /// `halt-bridge-snapshot.json`) for `resume-bridge`, once the preimage is emitted
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = snapshot::DEFAULT_PATH)]
+    snapshot_out: Option<PathBuf>,
+    /// Overwrite an existing `--snapshot-out` file
+    #[arg(long, requires = "snapshot_out")]
+    force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ResumeBridgeArgs {
+    /// Resume the Ethereum Gateway contract (both V1 and V2 paths) by sending
+    /// `Command::SetOperatingMode(Normal)` via both V1 and V2 system pallets.
+    #[arg(long)]
+    gateway: bool,
+    /// Resume the Ethereum Gateway via the V2 system pallet only.
+    #[arg(long)]
+    gateway_v2: bool,
+    /// Resume both V1 and V2 inbound-queue pallets on BridgeHub.
+    #[arg(long)]
+    inbound_queue: bool,
+    /// Resume only the V1 inbound-queue pallet on BridgeHub.
+    #[arg(long)]
+    inbound_queue_v1: bool,
+    /// Resume only the V2 inbound-queue pallet on BridgeHub.
+    #[arg(long)]
+    inbound_queue_v2: bool,
+    /// Resume the V1 outbound-queue pallet on BridgeHub and the system-frontend pallet
+    /// on AssetHub.
+    #[arg(long)]
+    outbound_queue: bool,
+    /// Resume only the AssetHub system-frontend pallet.
+    #[arg(long)]
+    system_frontend: bool,
+    /// Resume the Ethereum beacon light client.
+    #[arg(long)]
+    ethereum_client: bool,
+    /// Restore both V1 (`BridgeHubEthereumBaseFee`) and V2 (`BridgeHubEthereumBaseFeeV2`)
+    /// AssetHub outbound fees from `--snapshot` or `--fees-at`.
+    #[arg(long)]
+    assethub_max_fee: bool,
+    /// Restore only the V2 AssetHub outbound fee from `--snapshot` or `--fees-at`.
+    #[arg(long)]
+    assethub_max_fee_v2: bool,
+    /// Resume all parts of the bridge (equivalent to passing every other flag).
+    #[arg(long)]
+    all: bool,
+    /// Restore the fees stored on AssetHub at this block, i.e. before the halt
+    #[arg(long, value_name = "BLOCK_HASH", value_parser=parse_hex_bytes32, conflicts_with = "snapshot")]
+    fees_at: Option<FixedBytes<32>>,
+    /// Restore the fees saved by `halt-bridge --snapshot-out`, unless `--fees-at` is given
+    #[arg(long, value_name = "FILE", default_value = snapshot::DEFAULT_PATH)]
+    snapshot: PathBuf,
 }
 
 #[derive(Debug, Args)]
@@ -275,6 +558,62 @@ pub struct TreasuryProposal2024Args {
     beneficiary: FixedBytes<32>,
 }
 
+#[derive(Debug, Args)]
+pub struct TreasuryProposalArgs {
+    /// Spend schedule (.toml or .csv)
+    #[arg(value_name = "FILE")]
+    schedule: PathBuf,
+
+    /// Beneficiary address
+    #[arg(long, value_name = "ADDRESS", value_parser=parse_hex_bytes32)]
+    beneficiary: FixedBytes<32>,
+
+    /// Relay chain block that spend delays are relative to
+    #[arg(long, value_name = "BLOCK")]
+    start_block: u32,
+
+    /// Date of `--start-block` (YYYY-MM-DD), required for spends with a date
+    #[arg(long, value_name = "DATE")]
+    start_date: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RegisterTokensArgs {
+    /// Token list (Uniswap token list JSON, with PNA locations in `extensions.location`)
+    #[arg(value_name = "FILE")]
+    token_list: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct FreezeAssetArgs {
+    #[command(flatten)]
+    target: FreezeTargetArgs,
+    /// Also write the preimage thawing the asset again to FILE, as hex and wrapped like this
+    /// one except for the schedule. It is built whether or not the asset is frozen now.
+    #[arg(long, value_name = "FILE")]
+    thaw_out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct FreezeTargetArgs {
+    /// Address of the bridged ERC20 token
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        value_parser = parse_eth_address_without_validation,
+        required_unless_present = "location",
+        conflicts_with = "location"
+    )]
+    token: Option<Address>,
+    /// Location of the asset in `ForeignAssets` relative to AssetHub, e.g.
+    /// `../Parachain(2034)/GeneralIndex(0)` or `../../GlobalConsensus(Ethereum(1))` for Ether
+    #[arg(long, value_name = "LOCATION")]
+    location: Option<snowbridge_token_list::Location>,
+    /// Freeze or thaw only these accounts instead of the whole asset
+    #[arg(long, value_name = "ACCOUNT", value_delimiter = ',', value_parser = parse_account_id)]
+    accounts: Vec<[u8; 32]>,
+}
+
 #[derive(Debug, Args)]
 pub struct GovUpdate202501Args {
     #[command(flatten)]
@@ -299,6 +638,164 @@ pub struct RegisterEtherArgs {
     ether_decimals: u8,
 }
 
+#[derive(Debug, Args)]
+pub struct FromManifestArgs {
+    /// Path to a TOML or JSON manifest describing the proposal
+    #[arg(value_name = "FILE")]
+    manifest: PathBuf,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+pub enum Chain {
+    BridgeHub,
+    AssetHub,
+    Relay,
+}
+
+#[derive(Debug, Args)]
+pub struct CallArgs {
+    /// Chain the call is dispatched on
+    #[arg(value_enum)]
+    chain: Chain,
+    /// Pallet name, as in the metadata (e.g. `EthereumSystem`)
+    pallet: String,
+    /// Call name, as in the metadata (e.g. `set_operating_mode`)
+    call: String,
+    /// Call arguments, as a JSON object keyed by argument name or an array
+    #[arg(long, value_name = "JSON", value_parser = parse_json, default_value = "{}")]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Args)]
+pub struct DecodeArgs {
+    /// Hex-encoded preimage (0x-prefixed), or path to a hex or binary preimage file
+    #[arg(value_name = "PREIMAGE")]
+    preimage: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SuggestPricingArgs {
+    /// ETH price in USD
+    #[arg(long, value_name = "USD", required_unless_present = "price_file")]
+    eth_usd: Option<String>,
+    /// DOT price in USD
+    #[arg(long, value_name = "USD", required_unless_present = "price_file")]
+    dot_usd: Option<String>,
+    /// JSON file with the prices, e.g. `{ "eth_usd": "3215.40", "dot_usd": "4.12" }`
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["eth_usd", "dot_usd"])]
+    price_file: Option<PathBuf>,
+    /// Number of recent blocks to query with `eth_feeHistory`
+    #[arg(long, value_name = "BLOCKS", default_value_t = 300u64)]
+    blocks: u64,
+    /// Percentile of the base fees of those blocks to use
+    #[arg(long, value_name = "PERCENTILE", default_value_t = 75.0, value_parser = parse_percentile)]
+    base_fee_percentile: f64,
+    /// Percentile of the priority fees within each block. The median over all blocks is used.
+    #[arg(long, value_name = "PERCENTILE", default_value_t = 50.0, value_parser = parse_percentile)]
+    priority_fee_percentile: f64,
+    /// Numerator for Multiplier, passed through
+    #[arg(long, value_name = "UINT", default_value_t = 1u64)]
+    multiplier_numerator: u64,
+    /// Denominator for Multiplier, passed through
+    #[arg(long, value_name = "UINT", default_value_t = 1u64)]
+    multiplier_denominator: u64,
+    /// Relayer reward for delivering messages to Polkadot, passed through
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_relay_amount)]
+    local_reward: String,
+    /// Relayer reward for delivering messages to Ethereum, passed through
+    #[arg(long, value_name = "ETHER", value_parser = parse_decimal)]
+    remote_reward: String,
+}
+
+/// Signed origins for test environments run by a multisig or through a proxy.
+#[derive(Debug, Args)]
+pub struct OriginArgs {
+    /// Wrap the `Sudo.sudo` call in `Multisig.as_multi`, as the first approval of a multisig
+    /// holding the sudo key
+    #[arg(long, requires_all = ["sudo", "threshold", "signatories"])]
+    as_multi: bool,
+
+    /// Number of approvals required by the multisig
+    #[arg(long, value_name = "N", requires = "as_multi")]
+    threshold: Option<u16>,
+
+    /// The other signatories of the multisig, excluding the account submitting `as_multi`
+    #[arg(
+        long,
+        value_name = "ACCOUNT",
+        value_delimiter = ',',
+        value_parser = parse_account_id,
+        requires = "as_multi"
+    )]
+    signatories: Vec<[u8; 32]>,
+
+    /// Wrap the `Sudo.sudo` call in `Proxy.proxy`, dispatching it as ACCOUNT, the sudo key
+    /// (applied before --as-multi)
+    #[arg(long, value_name = "ACCOUNT", value_parser = parse_account_id, requires = "sudo")]
+    via_proxy: Option<[u8; 32]>,
+
+    /// Proxy type the proxy must have for ACCOUNT
+    #[arg(long, value_enum, requires = "via_proxy")]
+    proxy_type: Option<ProxyType>,
+}
+
+/// Proxy types common to the AssetHub runtimes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+pub enum ProxyType {
+    Any,
+    NonTransfer,
+    CancelProxy,
+    Assets,
+    AssetOwner,
+    AssetManager,
+    Collator,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmissionArgs {
+    /// Emit every call needed to submit the preimage via the given governance path
+    #[arg(long, value_enum, conflicts_with = "sudo")]
+    submission: Option<Submission>,
+
+    /// Number of blocks after approval at which the referendum is enacted
+    #[arg(long, value_name = "BLOCKS", default_value_t = 100u32)]
+    enactment_after: u32,
+
+    /// Index of the referendum to place the decision deposit for. Defaults to the next
+    /// index on AssetHub (`Referenda.ReferendumCount`).
+    #[arg(long, value_name = "INDEX", requires = "submission")]
+    referendum_index: Option<u32>,
+
+    /// Secret URI of an account to sign and submit the bundle with, e.g. //Alice
+    #[arg(long, value_name = "SURI", requires = "submission")]
+    signer: Option<String>,
+
+    /// AssetHub endpoint to submit the bundle to. Defaults to the AssetHub API.
+    #[arg(long, value_name = "URL", requires = "signer")]
+    submit_api: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+pub enum Submission {
+    /// Public referendum on the Root track
+    Root,
+    /// Public referendum on the WhitelistedCaller track, dispatching the call once the
+    /// Fellowship has whitelisted it
+    WhitelistedCaller,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+pub enum ChopsticksEnactment {
+    /// Scheduled with a Root origin
+    Root,
+    /// Dispatched by a WhitelistedCaller referendum, with the call already whitelisted
+    WhitelistedCaller,
+    /// Whitelisted by the Fellowship over XCM, then dispatched by a WhitelistedCaller referendum
+    FellowshipWhitelist,
+    /// Signed by the sudo key (requires --sudo)
+    Sudo,
+}
+
 #[derive(Debug, Args)]
 pub struct ApiEndpoints {
     #[arg(long, value_name = "URL")]
@@ -309,13 +806,17 @@ pub struct ApiEndpoints {
 
     #[arg(long, value_name = "URL")]
     relay_api: Option<String>,
+
+    /// Ethereum JSON-RPC endpoint over ws(s) or http(s), e.g. ws://127.0.0.1:8545 for a local
+    /// anvil node
+    #[arg(long, value_name = "URL")]
+    ethereum_api: Option<String>,
 }
 
 fn parse_eth_address(v: &str) -> Result<Address, String> {
     Address::parse_checksummed(v, None).map_err(|_| "invalid ethereum address".to_owned())
 }
 
-use hex_literal::hex;
 use std::str::FromStr;
 
 fn parse_eth_address_without_validation(v: &str) -> Result<Address, String> {
@@ -327,16 +828,102 @@ fn parse_hex_bytes32(v: &str) -> Result<FixedBytes<32>, String> {
         .map_err(|_| "invalid 32-byte hex value".to_owned())
 }
 
+/// An account given as 32-byte hex or SS58 address.
+fn parse_account_id(v: &str) -> Result<[u8; 32], String> {
+    if v.starts_with("0x") {
+        return parse_hex_bytes32(v).map(|bytes| bytes.0);
+    }
+    subxt::utils::AccountId32::from_str(v)
+        .map(|account| account.0)
+        .map_err(|_| "invalid account, expected 32-byte hex or an SS58 address".to_owned())
+}
+
 fn parse_hex_bytes(v: &str) -> Result<Bytes, String> {
     v.parse::<Bytes>()
         .map_err(|_| "invalid hex value".to_owned())
 }
 
-fn parse_units_polkadot(v: &str) -> Result<U128, String> {
-    let amount = parse_units(v, POLKADOT_DECIMALS).map_err(|e| format!("{e}"))?;
+fn parse_token_location(v: &str) -> Result<snowbridge_token_list::Location, String> {
+    serde_json::from_str(v).map_err(|e| format!("invalid location: {e}"))
+}
+
+fn parse_json(v: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(v).map_err(|e| format!("invalid JSON: {e}"))
+}
+
+fn parse_decimal(v: &str) -> Result<String, String> {
+    parse_units(v, "ether").map_err(|e| format!("{e}"))?;
+    Ok(v.to_owned())
+}
+
+fn parse_relay_amount(v: &str) -> Result<String, String> {
+    // Validated against the decimals of every network here, and converted (and checked
+    // again by `check_relay_amounts`) once the network is known
+    let decimals = Network::value_variants()
+        .iter()
+        .map(Network::decimals)
+        .max()
+        .unwrap();
+    parse_units_relay(v, decimals)?;
+    Ok(v.to_owned())
+}
+
+/// Convert an amount in a relay chain token with `decimals` (e.g. `0.01`) to planck.
+fn parse_units_relay(v: &str, decimals: u8) -> Result<u128, String> {
+    // `parse_units` silently truncates digits beyond `decimals`
+    if v.split_once('.')
+        .is_some_and(|(_, fraction)| fraction.trim_end_matches('0').len() > decimals.into())
+    {
+        return Err(format!("Amount {v} has more than {decimals} decimals"));
+    }
+    let amount = parse_units(v, decimals).map_err(|e| format!("{e}"))?;
     let amount: U256 = amount.into();
-    let amount: U128 = amount.to::<U128>();
-    Ok(amount)
+    amount
+        .try_into()
+        .map_err(|_| format!("Amount {v} does not fit in a u128 balance"))
+}
+
+/// Arguments in the relay chain token, whose decimals depend on `--network`.
+fn relay_amounts(command: &Command) -> Vec<(&'static str, &str)> {
+    match command {
+        Command::PricingParameters(params) => vec![("--local-reward", &params.local_reward)],
+        Command::Initialize(InitializeArgs {
+            pricing_parameters, ..
+        })
+        | Command::GovUpdate202501(GovUpdate202501Args {
+            pricing_parameters, ..
+        }) => vec![("--local-reward", &pricing_parameters.local_reward)],
+        Command::TokenTransferFees(params) => vec![
+            ("--create-asset-xcm", &params.create_asset_xcm),
+            ("--transfer-asset-xcm", &params.transfer_asset_xcm),
+        ],
+        Command::SuggestPricing(params) => vec![("--local-reward", &params.local_reward)],
+        _ => vec![],
+    }
+}
+
+/// Reject relay chain amounts with more decimals than the token of `--network` has, as a
+/// usage error before connecting to any chain.
+fn check_relay_amounts(cli: &Cli) -> Result<(), clap::Error> {
+    for (arg, amount) in relay_amounts(&cli.command) {
+        if let Err(e) = parse_units_relay(amount, cli.network.decimals()) {
+            return Err(Cli::command().error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "invalid value '{amount}' for '{arg} <AMOUNT>' on {}: {e}",
+                    cli.network
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn parse_percentile(v: &str) -> Result<f64, String> {
+    v.parse::<f64>()
+        .ok()
+        .filter(|percentile| (0.0..=100.0).contains(percentile))
+        .ok_or_else(|| "percentile must be between 0 and 100".to_owned())
 }
 
 fn parse_units_gwei(v: &str) -> Result<U256, String> {
@@ -353,344 +940,248 @@ fn parse_units_eth(v: &str) -> Result<U256, String> {
 pub enum Format {
     Hex,
     Binary,
+    /// A JSON object with the preimage, its hash, the decoded call tree and computed values
+    Json,
 }
 
 struct Context {
-    bridge_hub_api: Box<OnlineClient<PolkadotConfig>>,
-    _asset_hub_api: Box<OnlineClient<PolkadotConfig>>,
-    _relay_api: Box<OnlineClient<PolkadotConfig>>,
+    bridge_hub_api: Option<Box<OnlineClient<PolkadotConfig>>>,
+    asset_hub_api: Option<Box<OnlineClient<PolkadotConfig>>>,
+    /// Connected only to check the metadata of preimages that use relay chain calls
+    relay_api_url: Option<String>,
+    ethereum_api: Option<Box<EthereumClient>>,
+    weight_provider: WeightProvider,
+    /// Calls that differ between the bundled and the live BridgeHub and AssetHub metadata
+    changed_calls: Vec<ChainCall>,
+    /// Values collected while building the proposal, for `--format json`
+    report: RefCell<Report>,
+}
+
+impl Context {
+    fn bridge_hub_api(&self) -> Result<&OnlineClient<PolkadotConfig>, Box<dyn std::error::Error>> {
+        self.bridge_hub_api
+            .as_deref()
+            .ok_or_else(|| "This command requires a BridgeHub connection (remove --offline)".into())
+    }
+
+    fn asset_hub_api(&self) -> Result<&OnlineClient<PolkadotConfig>, Box<dyn std::error::Error>> {
+        self.asset_hub_api
+            .as_deref()
+            .ok_or_else(|| "This command requires an AssetHub connection (remove --offline)".into())
+    }
+
+    fn ethereum_api(&self) -> Result<&EthereumClient, Box<dyn std::error::Error>> {
+        self.ethereum_api
+            .as_deref()
+            .ok_or_else(|| "This command requires an Ethereum connection (--ethereum-api)".into())
+    }
+
+    /// The calls that differ between the bundled and the live metadata. The relay chain is
+    /// only connected, and its metadata compared, if `used` includes a relay chain call.
+    async fn changed_calls(
+        &self,
+        network: Network,
+        used: &[ChainCall],
+    ) -> Result<Vec<ChainCall>, Box<dyn std::error::Error>> {
+        let mut changed = self.changed_calls.clone();
+        let Some(url) = &self.relay_api_url else {
+            return Ok(changed);
+        };
+        if used.iter().any(|call| call.chain == Chain::Relay) {
+            let relay_api: OnlineClient<PolkadotConfig> = OnlineClient::from_url(url).await?;
+            changed.extend(metadata_check::compare(
+                network,
+                &[(Chain::Relay, &relay_api)],
+            )?);
+        }
+        Ok(changed)
+    }
 }
 
 #[tokio::main]
-async fn main() {
-    if let Err(err) = run().await {
-        eprintln!("{err}");
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
     }
 }
 
 async fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-
-    let bridge_hub_api: OnlineClient<PolkadotConfig> = OnlineClient::from_url(
-        cli.api_endpoints
-            .bridge_hub_api
-            .unwrap_or(BRIDGE_HUB_API.to_owned()),
-    )
-    .await?;
-
-    let asset_hub_api: OnlineClient<PolkadotConfig> = OnlineClient::from_url(
-        cli.api_endpoints
-            .asset_hub_api
-            .unwrap_or(ASSET_HUB_API.to_owned()),
-    )
-    .await?;
-
-    let relay_api: OnlineClient<PolkadotConfig> =
-        OnlineClient::from_url(cli.api_endpoints.relay_api.unwrap_or(RELAY_API.to_owned())).await?;
-
-    let context = Context {
-        bridge_hub_api: Box::new(bridge_hub_api),
-        _asset_hub_api: Box::new(asset_hub_api),
-        _relay_api: Box::new(relay_api),
+    let cli = unwrap_verify(Cli::parse()).unwrap_or_else(|e| e.exit());
+    if let Err(e) = check_relay_amounts(&cli) {
+        e.exit();
+    }
+
+    if cli.verify_hash.is_some() && cli.offline {
+        return Err("verify reads the noted preimage from AssetHub, remove --offline".into());
+    }
+
+    // Decoding is offline and does not need any chain connections
+    if let Command::Decode(params) = &cli.command {
+        return match cli.network {
+            Network::Polkadot => polkadot::decode::decode(params),
+            Network::Westend => westend::decode::decode(params),
+            Network::Paseo => paseo::decode::decode(params),
+        };
+    }
+
+    // Only needs prices and an Ethereum endpoint
+    if let Command::SuggestPricing(params) = &cli.command {
+        let ethereum_api = cli
+            .api_endpoints
+            .ethereum_api
+            .as_deref()
+            .ok_or("suggest-pricing requires --ethereum-api")?;
+        return suggest_pricing::suggest_pricing(params, ethereum_api, cli.format).await;
+    }
+
+    let weight_table = match &cli.weights {
+        Some(path) => Some(WeightTable::load(path)?),
+        None => None,
     };
 
-    let call = match &cli.command {
-        Command::ForceCheckpoint(params) => {
-            let call = commands::force_checkpoint(params);
-            send_xcm_bridge_hub(&context, vec![call]).await?
-        }
-        Command::Initialize(params) => {
-            let (set_pricing_parameters, set_ethereum_fee) =
-                commands::pricing_parameters(&context, &params.pricing_parameters).await?;
-            let bridge_hub_call = send_xcm_bridge_hub(
-                &context,
-                vec![
-                    commands::set_gateway_address(&params.gateway_address),
-                    set_pricing_parameters,
-                    commands::gateway_operating_mode(
-                        &params.gateway_operating_mode.gateway_operating_mode,
-                    ),
-                    commands::force_checkpoint(&params.force_checkpoint),
-                ],
-            )
-            .await?;
-            let (register_ether_call, set_ether_metadata_call) =
-                commands::register_ether(&params.register_ether);
-            let asset_hub_call = send_xcm_asset_hub(
-                &context,
-                vec![
-                    register_ether_call,
-                    set_ether_metadata_call,
-                    force_xcm_version(),
-                    set_ethereum_fee,
-                ],
-            )
-            .await?;
-            utility_batch_all(vec![bridge_hub_call, asset_hub_call])
-        }
-        Command::UpdateAsset(params) => {
-            send_xcm_asset_hub(
-                &context,
-                vec![
-                    commands::make_asset_sufficient(params),
-                    commands::force_set_metadata(params),
-                ],
-            )
-            .await?
-        }
-        Command::GatewayOperatingMode(params) => {
-            let call = commands::gateway_operating_mode(&params.gateway_operating_mode);
-            send_xcm_bridge_hub(&context, vec![call]).await?
-        }
-        Command::Upgrade(params) => {
-            let call = commands::upgrade(params);
-            send_xcm_bridge_hub(&context, vec![call]).await?
-        }
-        Command::PricingParameters(params) => {
-            let (set_pricing_parameters, set_ethereum_fee) =
-                commands::pricing_parameters(&context, params).await?;
-            let bridge_hub_call =
-                send_xcm_bridge_hub(&context, vec![set_pricing_parameters]).await?;
-            let asset_hub_call = send_xcm_asset_hub(&context, vec![set_ethereum_fee]).await?;
-            utility_batch_all(vec![bridge_hub_call, asset_hub_call])
-        }
-        Command::HaltBridge(params) => {
-            let mut bh_calls = vec![];
-            let mut ah_calls = vec![];
-            let mut halt_all = params.all;
-            // if no individual option specified, assume halt the whole bridge.
-            if !params.gateway
-                && !params.gateway_v2
-                && !params.inbound_queue
-                && !params.inbound_queue_v1
-                && !params.inbound_queue_v2
-                && !params.outbound_queue
-                && !params.system_frontend
-                && !params.ethereum_client
-                && !params.assethub_max_fee
-                && !params.assethub_max_fee_v2
-            {
-                halt_all = true;
-            }
-            // Gateway halt commands must be enqueued BEFORE any local outbound-queue
-            // halt takes effect, otherwise the SetOperatingMode command cannot be
-            // committed for delivery to Ethereum. Push both V1 and V2 variants so the
-            // halt is delivered via whichever outbound queue is operational.
-            if params.gateway || halt_all {
-                bh_calls.push(commands::gateway_operating_mode(
-                    &GatewayOperatingModeEnum::RejectingOutboundMessages,
-                ));
-                bh_calls.push(commands::gateway_operating_mode_v2(
-                    &GatewayOperatingModeEnum::RejectingOutboundMessages,
-                ));
-            } else if params.gateway_v2 {
-                // V2-only: leave V1 SetOperatingMode unsent.
-                bh_calls.push(commands::gateway_operating_mode_v2(
-                    &GatewayOperatingModeEnum::RejectingOutboundMessages,
-                ));
-            }
-            if params.inbound_queue || params.inbound_queue_v1 || halt_all {
-                bh_calls.push(commands::inbound_queue_operating_mode(
-                    &OperatingModeEnum::Halted,
-                ));
-            }
-            if params.inbound_queue || params.inbound_queue_v2 || halt_all {
-                bh_calls.push(commands::inbound_queue_v2_operating_mode(
-                    &OperatingModeEnum::Halted,
-                ));
-            }
-            if params.outbound_queue || halt_all {
-                // V1 local halt on BridgeHub. V2's outbound-queue-v2 has no local halt;
-                // the system-frontend halt below is the effective V2 outbound lever.
-                bh_calls.push(commands::outbound_queue_operating_mode(
-                    &OperatingModeEnum::Halted,
-                ));
-                // system-frontend halt on AssetHub: short-circuits the PausableExporter
-                // wrapping the AH->Ethereum router, blocking both V1 and V2 exports at
-                // the source regardless of user or parachain origin.
-                ah_calls.push(commands::system_frontend_operating_mode(
-                    &OperatingModeEnum::Halted,
-                ));
-            } else if params.system_frontend {
-                // Router-layer halt: AH frontend, blocks both V1 and V2 P->E at the
-                // PausableExporter. V1 BH outbound-queue left running so in-flight V1
-                // messages continue to drain.
-                ah_calls.push(commands::system_frontend_operating_mode(
-                    &OperatingModeEnum::Halted,
-                ));
-            }
-            if params.ethereum_client || halt_all {
-                bh_calls.push(commands::ethereum_client_operating_mode(
-                    &OperatingModeEnum::Halted,
-                ));
-            }
-            if params.assethub_max_fee || halt_all {
-                // Set both V1 and V2 AssetHub outbound fee storage items to u128::MAX.
-                ah_calls.push(commands::set_assethub_fee(u128::MAX));
-                ah_calls.push(commands::set_assethub_fee_v2(u128::MAX));
-            } else if params.assethub_max_fee_v2 {
-                // V2-only: leave V1 fee untouched.
-                ah_calls.push(commands::set_assethub_fee_v2(u128::MAX));
-            }
-            // Use `force_batch` (not `batch_all` or `batch`) so every lever fires
-            // independently — a single failure (e.g. HRMP transport hiccup blocking
-            // pallet_xcm::send to BridgeHub) must not skip the AH-side halts, and
-            // vice versa. Per-call failures are reported via `ItemFailed` events.
-            let ah_call = if ah_calls.len() == 1 {
-                Some(ah_calls.into_iter().next().unwrap())
-            } else if ah_calls.len() > 1 {
-                Some(utility_force_batch(ah_calls))
-            } else {
-                None
-            };
-            if bh_calls.len() > 0 && ah_call.is_none() {
-                send_xcm_bridge_hub(&context, bh_calls).await?
-            } else if ah_call.is_some() && bh_calls.len() == 0 {
-                ah_call.unwrap()
-            } else {
-                let bh_xcm_send = send_xcm_bridge_hub(&context, bh_calls).await?;
-                // BH XCM-send is the first call so the V2 Gateway halt (the first
-                // Transact inside the XCM) is processed before any AH-side halt.
-                utility_force_batch(vec![bh_xcm_send, ah_call.unwrap()])
-            }
-        }
-        Command::RegisterEther(params) => {
-            let (register_ether_call, set_ether_metadata_call) = commands::register_ether(&params);
-            send_xcm_asset_hub(&context, vec![register_ether_call, set_ether_metadata_call]).await?
-        }
-        Command::TreasuryProposal2024(params) => treasury_commands::treasury_proposal(&params),
-        Command::GovUpdate202501(GovUpdate202501Args {
-            pricing_parameters,
-            register_ether,
-        }) => {
-            let (set_pricing_parameters, set_ethereum_fee) =
-                commands::pricing_parameters(&context, pricing_parameters).await?;
-
-            let bh_set_pricing_call =
-                send_xcm_bridge_hub(&context, vec![set_pricing_parameters]).await?;
-
-            let ah_set_pricing_call = send_xcm_asset_hub(&context, vec![set_ethereum_fee]).await?;
-
-            let (register_ether_call, set_ether_metadata_call) =
-                commands::register_ether(&register_ether);
-            let ah_register_ether_call =
-                send_xcm_asset_hub(&context, vec![register_ether_call, set_ether_metadata_call])
-                    .await?;
-
-            utility_batch_all(vec![
-                bh_set_pricing_call,
-                ah_set_pricing_call,
-                ah_register_ether_call,
-            ])
-        }
-        Command::RegisterPnaBatch202503 => {
-            #[cfg(not(feature = "polkadot"))]
-            panic!("RegisterPnaBatch202503 only for polkadot runtime.");
-
-            #[cfg(feature = "polkadot")]
-            {
-                let reg_call =
-                    send_xcm_bridge_hub(&context, commands::token_registrations()).await?;
-                reg_call
-            }
-        }
-        Command::RegisterErc20TokenMetadata => {
-            #[cfg(not(feature = "polkadot"))]
-            panic!("RegisterErc20TokenMetadata only for polkadot runtime.");
-
-            #[cfg(feature = "polkadot")]
-            {
-                let metadata_calls = commands::register_erc20_token_metadata();
-                let reg_call = commands::frequency_token_registrations();
-                utility_batch_all(vec![
-                    send_xcm_asset_hub(&context, metadata_calls).await?,
-                    send_xcm_bridge_hub(&context, reg_call).await?,
-                ])
-            }
-        }
-        Command::UpgradeV2 => {
-            #[cfg(not(feature = "polkadot"))]
-            panic!("UpgradeV2 only for polkadot runtime.");
-
-            #[cfg(feature = "polkadot")]
-            {
-                // Upgrade logic gateway on BH
-                let upgrade_call = commands::upgrade(&UpgradeArgs {
-                    logic_address: address!("8a887783E945233d51881e06835Ec78A8b575eCe"),
-                    logic_code_hash: FixedBytes::from_slice(&hex!(
-                        "cbabd7683b33e7d8f4b143def2d712999961b306e1f98782016439293d673849"
-                    )),
-                    initializer_params: Default::default(),
-                    initializer_gas: 100000,
-                });
-                let bh_xcm_call = send_xcm_bridge_hub(&context, vec![upgrade_call]).await?;
-
-                // Set bound fee to 0.1 DOT on AH
-                let outbound_fee_call = commands::set_assethub_fee_v2(1_000_000_000);
-                let ah_xcm_call = send_xcm_asset_hub(&context, vec![outbound_fee_call]).await?;
-
-                utility_batch_all(vec![bh_xcm_call, ah_xcm_call])
-            }
-        }
-        Command::ReplaySep2025 => {
-            let asset_hub_call = commands::replay_sep_2025_xcm();
-            send_xcm_asset_hub(&context, vec![asset_hub_call]).await?
-        }
-        Command::MintFeb2026 => {
-            let bridge_hub_call = commands::mint_feb_2026_xcm();
-            send_xcm_bridge_hub(&context, vec![bridge_hub_call]).await?
-        }
-        Command::SetPaseoFeeV2 => {
-            #[cfg(not(feature = "paseo"))]
-            panic!("SetPaseoFeeV2 only for paseo runtime.");
-
-            #[cfg(feature = "paseo")]
-            {
-                // Set bound fee to 0.1 DOT (same as Polkadot V2) on AH
-                let outbound_fee_call = commands::set_assethub_fee_v2(1_000_000_000);
-                send_xcm_asset_hub(&context, vec![outbound_fee_call]).await?
-            }
+    // Ethereum is also used offline, as it only checks the upgrade against Ethereum
+    let ethereum_api = match &cli.api_endpoints.ethereum_api {
+        Some(url) => Some(Box::new(EthereumClient::connect(url).await?)),
+        None => None,
+    };
+
+    let context = if cli.offline {
+        let weight_provider = match weight_table {
+            Some(table) => WeightProvider::Table(table),
+            None if cli.no_fallback_weight => WeightProvider::NoFallback,
+            None => WeightProvider::Unavailable,
+        };
+        Context {
+            bridge_hub_api: None,
+            asset_hub_api: None,
+            relay_api_url: None,
+            ethereum_api,
+            weight_provider,
+            changed_calls: vec![],
+            report: Default::default(),
         }
-        Command::Upgrade202603 => {
-            #[cfg(not(feature = "polkadot"))]
-            panic!("Upgrade202603 only for polkadot runtime.");
-
-            #[cfg(feature = "polkadot")]
-            {
-                // Upgrade logic gateway
-                let upgrade_call = commands::upgrade(&UpgradeArgs {
-                    logic_address: address!("36e74FCAAcb07773b144Ca19Ef2e32Fc972aC50b"),
-                    logic_code_hash: FixedBytes::from_slice(&hex!(
-                        "e3cfcc0042ad4c819c627fb2a84ba0822d67747a8618a4e1c4eb0c5112b17903"
-                    )),
-                    initializer_params: Default::default(),
-                    initializer_gas: 100000,
-                });
-                send_xcm_bridge_hub(&context, vec![upgrade_call]).await?
-            }
+    } else {
+        let bridge_hub_api: OnlineClient<PolkadotConfig> = OnlineClient::from_url(
+            cli.api_endpoints
+                .bridge_hub_api
+                .clone()
+                .unwrap_or(cli.network.bridge_hub_api().to_owned()),
+        )
+        .await?;
+
+        let asset_hub_api: OnlineClient<PolkadotConfig> = OnlineClient::from_url(
+            cli.api_endpoints
+                .asset_hub_api
+                .clone()
+                .unwrap_or(cli.network.asset_hub_api().to_owned()),
+        )
+        .await?;
+
+        let changed_calls = metadata_check::compare(
+            cli.network,
+            &[
+                (Chain::BridgeHub, &bridge_hub_api),
+                (Chain::AssetHub, &asset_hub_api),
+            ],
+        )?;
+
+        let weight_provider = match weight_table {
+            Some(table) => WeightProvider::Table(table),
+            None if cli.no_fallback_weight => WeightProvider::NoFallback,
+            None => WeightProvider::Rpc(Box::new(bridge_hub_api.clone())),
+        };
+
+        Context {
+            bridge_hub_api: Some(Box::new(bridge_hub_api)),
+            asset_hub_api: Some(Box::new(asset_hub_api)),
+            relay_api_url: Some(
+                cli.api_endpoints
+                    .relay_api
+                    .clone()
+                    .unwrap_or(cli.network.relay_api().to_owned()),
+            ),
+            ethereum_api,
+            weight_provider,
+            changed_calls,
+            report: Default::default(),
         }
     };
 
-    #[cfg(any(feature = "westend", feature = "paseo"))]
-    let final_call = if cli.sudo { sudo(Box::new(call)) } else { call };
-    #[cfg(not(any(feature = "westend", feature = "paseo")))]
-    let final_call = call;
+    match cli.network {
+        Network::Polkadot => polkadot::proposal::run(&cli, &context).await,
+        Network::Westend => westend::proposal::run(&cli, &context).await,
+        Network::Paseo => paseo::proposal::run(&cli, &context).await,
+    }
+}
 
-    let preimage = final_call.encode();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    generate_chopsticks_script(&preimage, "chopsticks-execute-upgrade.js".into())?;
+    fn token_transfer_fees(network: &str, create_asset_xcm: &str) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from([
+            "snowbridge-preimage",
+            "--network",
+            network,
+            "token-transfer-fees",
+            "--create-asset-xcm",
+            create_asset_xcm,
+            "--transfer-asset-xcm",
+            "1",
+            "--register-token",
+            "0.001",
+        ])
+    }
 
-    eprintln!("Preimage Hash: 0x{}", hex::encode(blake2_256(&preimage)));
-    eprintln!("Preimage Size: {}", preimage.len());
+    #[test]
+    fn relay_amounts_use_network_decimals() {
+        assert_eq!(parse_units_relay("1.5", 10).unwrap(), 15_000_000_000);
+        assert_eq!(parse_units_relay("1.5", 12).unwrap(), 1_500_000_000_000);
+        assert!(parse_units_relay("0.00000000001", 10).is_err());
+        assert!(parse_units_relay(&u128::MAX.to_string(), 12).is_err());
 
-    match cli.format {
-        Format::Hex => {
-            println!("0x{}", hex::encode(preimage));
-        }
-        Format::Binary => {
-            std::io::stdout().write_all(&preimage)?;
-        }
+        // 12 decimals are valid on Westend only
+        let cli = token_transfer_fees("westend", "0.000000000001").unwrap();
+        assert!(check_relay_amounts(&cli).is_ok());
+        let cli = token_transfer_fees("polkadot", "0.000000000001").unwrap();
+        let error = check_relay_amounts(&cli).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+        assert!(error.to_string().contains("--create-asset-xcm"));
+
+        // More decimals than any network has are rejected while parsing
+        assert!(token_transfer_fees("westend", "0.0000000000001").is_err());
     }
 
-    Ok(())
+    #[test]
+    fn verify_wraps_a_command() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        let verify = |command: &[&str]| {
+            let args = ["snowbridge-preimage", "verify", "--hash", &hash];
+            unwrap_verify(Cli::try_parse_from(args.iter().chain(command))?)
+        };
+        let cli = verify(&[
+            "gateway-operating-mode",
+            "--gateway-operating-mode",
+            "normal",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Command::GatewayOperatingMode(_)));
+        assert_eq!(cli.verify_hash, Some(FixedBytes([0xab; 32])));
+
+        // The verified command's arguments are validated as usual
+        assert!(verify(&["gateway-operating-mode", "--gateway-operating-mode", "x"]).is_err());
+        assert!(verify(&[]).is_err());
+        assert!(verify(&["decode", "preimage.hex"]).is_err());
+        assert!(verify(&["verify", "--hash", &hash, "upgrade-v2"]).is_err());
+    }
+
+    #[test]
+    fn amounts_that_overflow_are_rejected() {
+        let amount = format!("{}", u128::MAX);
+        assert!(token_transfer_fees("polkadot", &amount).is_err());
+    }
 }
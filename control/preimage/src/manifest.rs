@@ -0,0 +1,846 @@
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::commands;
+use super::helpers::{
+    force_xcm_version, parse_units_polkadot, send_xcm_bridge_hub, utility_batch_all,
+    utility_force_batch,
+};
+use super::registrations;
+use super::system_commands;
+use crate::{
+    parse_eth_address, parse_eth_address_without_validation, parse_hex_bytes, parse_hex_bytes32,
+    parse_units_eth, parse_units_gwei, Context, ForceCheckpointArgs, GatewayAddressArgs,
+    GatewayOperatingModeEnum, OperatingModeEnum, PricingParametersArgs, RegisterEtherArgs,
+    RegisterTokenArgs, RegisterTokensArgs, TokenTransferFeesArgs, UpdateAssetArgs, UpgradeArgs,
+};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A declarative governance proposal.
+///
+/// Batches are emitted in order. Each batch targets a single chain: a BridgeHub batch is
+/// sent with `send_xcm_bridge_hub`, and the calls of an AssetHub batch are combined using
+/// its `batch` semantics. If more than one batch is present, the results are combined
+/// using the top-level `batch` semantics.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Manifest {
+    /// How to combine the batches into the final call
+    #[serde(default)]
+    pub batch: BatchMode,
+    pub batches: Vec<ManifestBatch>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ManifestBatch {
+    pub chain: Chain,
+    /// Batch semantics for multiple AssetHub calls, `batch-all` by default as for the
+    /// batches themselves. Ignored for BridgeHub batches, where every call is a separate
+    /// `Transact` followed by `ExpectTransactStatus`.
+    #[serde(default)]
+    pub batch: BatchMode,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Chain {
+    BridgeHub,
+    AssetHub,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatchMode {
+    /// `utility_batch_all`: all calls succeed or all are rolled back
+    #[default]
+    BatchAll,
+    /// `utility_force_batch`: every call is attempted independently
+    ForceBatch,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(
+    tag = "action",
+    rename_all = "kebab-case",
+    rename_all_fields = "kebab-case",
+    deny_unknown_fields
+)]
+pub enum Action {
+    /// BridgeHub: `EthereumSystem(V2)::upgrade`
+    Upgrade {
+        logic_address: String,
+        logic_code_hash: String,
+        #[serde(default)]
+        initializer_params: Option<String>,
+        #[serde(default)]
+        initializer_abi: Option<PathBuf>,
+        #[serde(default)]
+        initializer_type: Option<String>,
+        #[serde(default)]
+        initializer_args: Option<serde_json::Value>,
+        /// Estimated on Ethereum if omitted
+        #[serde(default)]
+        initializer_gas: Option<u64>,
+        /// Percentage added to the estimated gas
+        #[serde(default = "default_initializer_gas_margin")]
+        initializer_gas_margin: u64,
+        #[serde(default)]
+        v2: bool,
+        /// Emit the upgrade without checking the logic code hash on Ethereum
+        #[serde(default)]
+        skip_code_check: bool,
+    },
+    /// BridgeHub: `EthereumSystem(V2)::set_operating_mode`
+    GatewayOperatingMode {
+        mode: GatewayOperatingModeEnum,
+        #[serde(default)]
+        v2: bool,
+    },
+    /// BridgeHub: `EthereumInboundQueue(V2)::set_operating_mode`
+    InboundQueueOperatingMode {
+        mode: OperatingModeEnum,
+        #[serde(default)]
+        v2: bool,
+    },
+    /// BridgeHub: `EthereumOutboundQueue::set_operating_mode`
+    OutboundQueueOperatingMode { mode: OperatingModeEnum },
+    /// BridgeHub: `EthereumBeaconClient::set_operating_mode`
+    EthereumClientOperatingMode { mode: OperatingModeEnum },
+    /// AssetHub: `SnowbridgeSystemFrontend::set_operating_mode`
+    SystemFrontendOperatingMode { mode: OperatingModeEnum },
+    /// BridgeHub: `EthereumSystem::set_pricing_parameters`. The adjusted AssetHub fee
+    /// must be set by a subsequent `asset-hub-fee` action without an explicit fee.
+    PricingParameters {
+        exchange_rate_numerator: u64,
+        exchange_rate_denominator: u64,
+        multiplier_numerator: u64,
+        multiplier_denominator: u64,
+        /// In GWEI
+        fee_per_gas: String,
+        /// In DOT (or the relay chain's native token)
+        local_reward: String,
+        /// In ETH
+        remote_reward: String,
+    },
+    /// BridgeHub: `EthereumSystem::set_token_transfer_fees`
+    TokenTransferFees {
+        /// In DOT
+        create_asset_xcm: String,
+        /// In DOT
+        transfer_asset_xcm: String,
+        /// In ETH
+        register_token: String,
+    },
+    /// AssetHub: set `BridgeHubEthereumBaseFee` (or `BridgeHubEthereumBaseFeeV2`)
+    AssetHubFee {
+        /// In DOT. Defaults to the fee computed by the preceding `pricing-parameters`.
+        #[serde(default)]
+        fee: Option<String>,
+        #[serde(default)]
+        v2: bool,
+    },
+    /// BridgeHub: `EthereumBeaconClient::force_checkpoint`
+    ForceCheckpoint {
+        checkpoint: PathBuf,
+        #[serde(default)]
+        beacon_api: Option<String>,
+    },
+    /// BridgeHub: set `EthereumGatewayAddress` storage
+    GatewayAddress { address: String },
+    /// AssetHub: `ForeignAssets::force_create` and `force_set_metadata` for Ether
+    RegisterEther {
+        #[serde(default = "default_ether_min_balance", deserialize_with = "via_json")]
+        min_balance: u128,
+        #[serde(default = "default_ether_name")]
+        name: String,
+        #[serde(default = "default_ether_symbol")]
+        symbol: String,
+        #[serde(default = "default_ether_decimals")]
+        decimals: u8,
+    },
+    /// AssetHub: `ForeignAssets::force_asset_status` and `force_set_metadata`
+    UpdateAsset {
+        contract_id: String,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        #[serde(deserialize_with = "via_json")]
+        min_balance: u128,
+        is_sufficient: bool,
+        is_frozen: bool,
+    },
+    /// BridgeHub: `EthereumSystem::register_token` for a Polkadot native asset. With
+    /// `v2 = true`, AssetHub: `SnowbridgeSystemFrontend::register_token`.
+    RegisterToken {
+        /// Relative to AssetHub or BridgeHub, as in a token list's `extensions.location`
+        #[serde(deserialize_with = "via_json")]
+        location: snowbridge_token_list::Location,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        #[serde(default)]
+        v2: bool,
+        /// In ETH, required with `v2 = true`
+        #[serde(default)]
+        fee: Option<String>,
+    },
+    /// AssetHub: register the tokens of a token list that are not registered yet, as
+    /// with the `register-tokens` command
+    RegisterTokens { token_list: PathBuf },
+    /// AssetHub: `PolkadotXcm::force_xcm_version` for the Ethereum location
+    ForceXcmVersion,
+    /// Either chain: `System::set_storage` with raw hex keys and values
+    SetStorage { items: Vec<(String, String)> },
+}
+
+/// Deserialize a field of an action through a JSON value. The actions are buffered to find
+/// their `action` tag, and serde's buffer does not support `u128`.
+fn via_json<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    serde_json::from_value(value).map_err(serde::de::Error::custom)
+}
+
+fn default_initializer_gas_margin() -> u64 {
+    crate::initializer::DEFAULT_GAS_MARGIN_PERCENT
+}
+
+fn default_ether_min_balance() -> u128 {
+    1
+}
+
+fn default_ether_name() -> String {
+    String::from("Ether")
+}
+
+fn default_ether_symbol() -> String {
+    String::from("ETH")
+}
+
+fn default_ether_decimals() -> u8 {
+    18
+}
+
+impl Action {
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Upgrade { .. } => "upgrade",
+            Action::GatewayOperatingMode { .. } => "gateway-operating-mode",
+            Action::InboundQueueOperatingMode { .. } => "inbound-queue-operating-mode",
+            Action::OutboundQueueOperatingMode { .. } => "outbound-queue-operating-mode",
+            Action::EthereumClientOperatingMode { .. } => "ethereum-client-operating-mode",
+            Action::SystemFrontendOperatingMode { .. } => "system-frontend-operating-mode",
+            Action::PricingParameters { .. } => "pricing-parameters",
+            Action::TokenTransferFees { .. } => "token-transfer-fees",
+            Action::AssetHubFee { .. } => "asset-hub-fee",
+            Action::ForceCheckpoint { .. } => "force-checkpoint",
+            Action::GatewayAddress { .. } => "gateway-address",
+            Action::RegisterEther { .. } => "register-ether",
+            Action::UpdateAsset { .. } => "update-asset",
+            Action::RegisterToken { .. } => "register-token",
+            Action::RegisterTokens { .. } => "register-tokens",
+            Action::ForceXcmVersion => "force-xcm-version",
+            Action::SetStorage { .. } => "set-storage",
+        }
+    }
+}
+
+pub fn load_manifest(path: &Path) -> Result<Manifest, Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read manifest {}: {e}", path.display()))?;
+    let manifest: Manifest = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&data)?,
+        Some("toml") => toml::from_str(&data)?,
+        _ => return Err("Manifest must have a .toml or .json extension".into()),
+    };
+    if manifest.batches.is_empty() {
+        return Err("Manifest contains no batches".into());
+    }
+    Ok(manifest)
+}
+
+/// Build the final AssetHub call for a manifest.
+pub async fn build_manifest(
+    context: &Context,
+    manifest: &Manifest,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    // AssetHub fee computed by the most recent `pricing-parameters` action
+    let mut pricing_fee: Option<AssetHubRuntimeCall> = None;
+    let mut calls: Vec<AssetHubRuntimeCall> = vec![];
+
+    for (index, batch) in manifest.batches.iter().enumerate() {
+        if batch.actions.is_empty() {
+            return Err(format!("Batch #{index} contains no actions").into());
+        }
+        match batch.chain {
+            Chain::BridgeHub => {
+                let mut bh_calls: Vec<BridgeHubRuntimeCall> = vec![];
+                for action in batch.actions.iter() {
+                    bh_calls.push(bridge_hub_call(context, action, &mut pricing_fee).await?);
+                }
+                calls.push(send_xcm_bridge_hub(context, bh_calls).await?);
+            }
+            Chain::AssetHub => {
+                let mut ah_calls: Vec<AssetHubRuntimeCall> = vec![];
+                for action in batch.actions.iter() {
+                    ah_calls.append(&mut asset_hub_calls(context, action, &mut pricing_fee).await?);
+                }
+                calls.push(batch_calls(batch.batch, ah_calls));
+            }
+        }
+    }
+
+    // The CLI always sets the base fee together with the pricing parameters
+    if pricing_fee.is_some() {
+        return Err(UNSET_PRICING_FEE.into());
+    }
+
+    Ok(batch_calls(manifest.batch, calls))
+}
+
+const UNSET_PRICING_FEE: &str = "pricing-parameters must be followed by an asset-hub-fee action \
+     without a fee, which sets the AssetHub base fee computed from the new parameters";
+
+fn batch_calls(mode: BatchMode, calls: Vec<AssetHubRuntimeCall>) -> AssetHubRuntimeCall {
+    if calls.len() == 1 {
+        return calls.into_iter().next().unwrap();
+    }
+    match mode {
+        BatchMode::BatchAll => utility_batch_all(calls),
+        BatchMode::ForceBatch => utility_force_batch(calls),
+    }
+}
+
+async fn bridge_hub_call(
+    context: &Context,
+    action: &Action,
+    pricing_fee: &mut Option<AssetHubRuntimeCall>,
+) -> Result<BridgeHubRuntimeCall, Box<dyn std::error::Error>> {
+    let call = match action {
+        Action::Upgrade {
+            logic_address,
+            logic_code_hash,
+            initializer_params,
+            initializer_abi,
+            initializer_type,
+            initializer_args,
+            initializer_gas,
+            initializer_gas_margin,
+            v2,
+            skip_code_check,
+        } => {
+            if initializer_params.is_some() && initializer_abi.is_some() {
+                return Err("upgrade: set initializer-params or initializer-abi, not both".into());
+            }
+            if initializer_abi.is_some() != initializer_type.is_some() {
+                return Err("upgrade: initializer-abi and initializer-type go together".into());
+            }
+            commands::upgrade(
+                context,
+                &UpgradeArgs {
+                    logic_address: parse_eth_address(logic_address)?,
+                    logic_code_hash: parse_hex_bytes32(logic_code_hash)?,
+                    initializer_params: initializer_params
+                        .as_deref()
+                        .map(parse_hex_bytes)
+                        .transpose()?,
+                    initializer_abi: initializer_abi.clone(),
+                    initializer_type: initializer_type.clone(),
+                    initializer_args: initializer_args.clone(),
+                    initializer_gas: *initializer_gas,
+                    initializer_gas_margin: *initializer_gas_margin,
+                    v2: *v2,
+                    skip_code_check: *skip_code_check,
+                },
+            )
+            .await?
+        }
+        Action::GatewayOperatingMode { mode, v2: false } => commands::gateway_operating_mode(mode),
+        Action::GatewayOperatingMode { mode, v2: true } => {
+            commands::gateway_operating_mode_v2(mode)
+        }
+        Action::InboundQueueOperatingMode { mode, v2: false } => {
+            commands::inbound_queue_operating_mode(mode)
+        }
+        Action::InboundQueueOperatingMode { mode, v2: true } => {
+            commands::inbound_queue_v2_operating_mode(mode)
+        }
+        Action::OutboundQueueOperatingMode { mode } => {
+            commands::outbound_queue_operating_mode(mode)
+        }
+        Action::EthereumClientOperatingMode { mode } => {
+            commands::ethereum_client_operating_mode(mode)
+        }
+        Action::PricingParameters {
+            exchange_rate_numerator,
+            exchange_rate_denominator,
+            multiplier_numerator,
+            multiplier_denominator,
+            fee_per_gas,
+            local_reward,
+            remote_reward,
+        } => {
+            let params = PricingParametersArgs {
+                exchange_rate_numerator: *exchange_rate_numerator,
+                exchange_rate_denominator: *exchange_rate_denominator,
+                multiplier_numerator: *multiplier_numerator,
+                multiplier_denominator: *multiplier_denominator,
+                fee_per_gas: parse_units_gwei(fee_per_gas)?,
+                local_reward: local_reward.clone(),
+                remote_reward: parse_units_eth(remote_reward)?,
+                bridge_hub_at: None,
+                asset_hub_at: None,
+            };
+            if pricing_fee.is_some() {
+                return Err(UNSET_PRICING_FEE.into());
+            }
+            let (set_pricing_parameters, set_ethereum_fee) =
+                commands::pricing_parameters(context, &params).await?;
+            *pricing_fee = Some(set_ethereum_fee);
+            set_pricing_parameters
+        }
+        Action::ForceCheckpoint {
+            checkpoint,
+            beacon_api,
+        } => {
+            commands::force_checkpoint(&ForceCheckpointArgs {
+                checkpoint: checkpoint.clone(),
+                beacon_api: beacon_api.clone(),
+            })
+            .await?
+        }
+        Action::TokenTransferFees {
+            create_asset_xcm,
+            transfer_asset_xcm,
+            register_token,
+        } => system_commands::set_token_transfer_fees(&TokenTransferFeesArgs {
+            create_asset_xcm: create_asset_xcm.clone(),
+            transfer_asset_xcm: transfer_asset_xcm.clone(),
+            register_token: parse_units_eth(register_token)?,
+        })?,
+        Action::GatewayAddress { address } => commands::set_gateway_address(&GatewayAddressArgs {
+            gateway_address: parse_eth_address(address)?,
+        }),
+        Action::RegisterToken {
+            location,
+            name,
+            symbol,
+            decimals,
+            v2: false,
+            fee,
+        } => system_commands::register_token(&register_token_args(
+            location, name, symbol, *decimals, false, fee,
+        )?)?,
+        Action::SetStorage { items } => BridgeHubRuntimeCall::System(
+            super::bridge_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
+                items: parse_storage_items(items)?,
+            },
+        ),
+        _ => {
+            return Err(format!(
+                "Action '{}' cannot be included in a bridge-hub batch",
+                action.name()
+            )
+            .into())
+        }
+    };
+    Ok(call)
+}
+
+async fn asset_hub_calls(
+    context: &Context,
+    action: &Action,
+    pricing_fee: &mut Option<AssetHubRuntimeCall>,
+) -> Result<Vec<AssetHubRuntimeCall>, Box<dyn std::error::Error>> {
+    let calls =
+        match action {
+            Action::SystemFrontendOperatingMode { mode } => {
+                vec![commands::system_frontend_operating_mode(mode)]
+            }
+            Action::AssetHubFee { fee: Some(fee), v2 } => {
+                let fee = parse_units_polkadot(fee)?;
+                if *v2 {
+                    vec![commands::set_assethub_fee_v2(fee)]
+                } else {
+                    vec![commands::set_assethub_fee(fee)]
+                }
+            }
+            Action::AssetHubFee {
+                fee: None,
+                v2: false,
+            } => match pricing_fee.take() {
+                Some(call) => vec![call],
+                None => return Err(
+                    "asset-hub-fee without an explicit fee must follow a pricing-parameters action"
+                        .into(),
+                ),
+            },
+            Action::AssetHubFee {
+                fee: None,
+                v2: true,
+            } => return Err("asset-hub-fee with v2 = true requires an explicit fee".into()),
+            Action::RegisterEther {
+                min_balance,
+                name,
+                symbol,
+                decimals,
+            } => {
+                let (register_ether_call, set_ether_metadata_call) =
+                    commands::register_ether(&RegisterEtherArgs {
+                        ether_min_balance: *min_balance,
+                        ether_name: name.clone(),
+                        ether_symbol: symbol.clone(),
+                        ether_decimals: *decimals,
+                    });
+                vec![register_ether_call, set_ether_metadata_call]
+            }
+            Action::UpdateAsset {
+                contract_id,
+                name,
+                symbol,
+                decimals,
+                min_balance,
+                is_sufficient,
+                is_frozen,
+            } => {
+                let params = UpdateAssetArgs {
+                    contract_id: parse_eth_address_without_validation(contract_id)?,
+                    name: name.clone(),
+                    symbol: symbol.clone(),
+                    decimals: *decimals,
+                    min_balance: *min_balance,
+                    is_sufficient: *is_sufficient,
+                    is_frozen: *is_frozen,
+                };
+                vec![
+                    commands::make_asset_sufficient(&params),
+                    commands::force_set_metadata(&params),
+                ]
+            }
+            Action::RegisterToken {
+                location,
+                name,
+                symbol,
+                decimals,
+                v2: true,
+                fee,
+            } => {
+                if fee.is_none() {
+                    return Err("register-token with v2 = true requires a fee".into());
+                }
+                vec![system_commands::register_token_v2(&register_token_args(
+                    location, name, symbol, *decimals, true, fee,
+                )?)?]
+            }
+            Action::RegisterTokens { token_list } => vec![
+                registrations::register_tokens(
+                    context,
+                    &RegisterTokensArgs {
+                        token_list: token_list.clone(),
+                    },
+                )
+                .await?,
+            ],
+            Action::ForceXcmVersion => vec![force_xcm_version()],
+            Action::SetStorage { items } => vec![AssetHubRuntimeCall::System(
+                super::asset_hub_runtime::runtime_types::frame_system::pallet::Call::set_storage {
+                    items: parse_storage_items(items)?,
+                },
+            )],
+            _ => {
+                return Err(format!(
+                    "Action '{}' cannot be included in an asset-hub batch",
+                    action.name()
+                )
+                .into())
+            }
+        };
+    Ok(calls)
+}
+
+fn register_token_args(
+    location: &snowbridge_token_list::Location,
+    name: &str,
+    symbol: &str,
+    decimals: u8,
+    v2: bool,
+    fee: &Option<String>,
+) -> Result<RegisterTokenArgs, Box<dyn std::error::Error>> {
+    Ok(RegisterTokenArgs {
+        location: location.clone(),
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        decimals,
+        v2,
+        fee: fee.as_deref().map(parse_units_eth).transpose()?,
+    })
+}
+
+fn parse_storage_items(
+    items: &[(String, String)],
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Box<dyn std::error::Error>> {
+    items
+        .iter()
+        .map(|(key, value)| {
+            Ok((
+                parse_hex_bytes(key)?.to_vec(),
+                parse_hex_bytes(value)?.to_vec(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Result<Manifest, String> {
+        toml::from_str(toml).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn parse_every_action() {
+        let manifest = parse(
+            r#"
+            batch = "force-batch"
+
+            [[batches]]
+            chain = "bridge-hub"
+            actions = [
+              { action = "upgrade", logic-address = "0x36e74FCAAcb07773b144Ca19Ef2e32Fc972aC50b", logic-code-hash = "0xe3cfcc0042ad4c819c627fb2a84ba0822d67747a8618a4e1c4eb0c5112b17903", initializer-gas = 100000 },
+              { action = "upgrade", logic-address = "0x36e74FCAAcb07773b144Ca19Ef2e32Fc972aC50b", logic-code-hash = "0xe3cfcc0042ad4c819c627fb2a84ba0822d67747a8618a4e1c4eb0c5112b17903", initializer-abi = "Gateway.json", initializer-type = "Config", initializer-args = { mode = 0 }, v2 = true },
+              { action = "gateway-operating-mode", mode = "rejecting-outbound-messages", v2 = true },
+              { action = "inbound-queue-operating-mode", mode = "halted" },
+              { action = "outbound-queue-operating-mode", mode = "normal" },
+              { action = "ethereum-client-operating-mode", mode = "halted" },
+              { action = "pricing-parameters", exchange-rate-numerator = 1, exchange-rate-denominator = 400, multiplier-numerator = 4, multiplier-denominator = 3, fee-per-gas = "80", local-reward = "0.01", remote-reward = "0.0001" },
+              { action = "token-transfer-fees", create-asset-xcm = "0.1", transfer-asset-xcm = "0.2", register-token = "0.001" },
+              { action = "force-checkpoint", checkpoint = "checkpoint.json", beacon-api = "http://127.0.0.1:5052" },
+              { action = "gateway-address", address = "0x27ca963C279c93801941e1eB8799c23f407d68e7" },
+              { action = "register-token", location = { parents = 1, interior = [{ Parachain = 2034 }, { GeneralIndex = 0 }] }, name = "Hydration", symbol = "HDX", decimals = 12 },
+              { action = "set-storage", items = [["0x01", "0x02"]] },
+            ]
+
+            [[batches]]
+            chain = "asset-hub"
+            actions = [
+              { action = "system-frontend-operating-mode", mode = "halted" },
+              { action = "asset-hub-fee" },
+              { action = "asset-hub-fee", fee = "0.1", v2 = true },
+              { action = "register-ether" },
+              { action = "update-asset", contract-id = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", name = "Wrapped Ether", symbol = "WETH", decimals = 18, min-balance = 1, is-sufficient = true, is-frozen = false },
+              { action = "register-token", location = { parents = 0, interior = [{ PalletInstance = 50 }, { GeneralIndex = 1984 }] }, name = "Tether USD", symbol = "USDT", decimals = 6, v2 = true, fee = "0.001" },
+              { action = "register-tokens", token-list = "tokens.json" },
+              { action = "force-xcm-version" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.batch, BatchMode::ForceBatch);
+        let [bridge_hub, asset_hub] = &manifest.batches[..] else {
+            panic!("expected two batches");
+        };
+        assert_eq!(bridge_hub.chain, Chain::BridgeHub);
+        assert_eq!(asset_hub.chain, Chain::AssetHub);
+        let names =
+            |batch: &ManifestBatch| batch.actions.iter().map(Action::name).collect::<Vec<_>>();
+        assert_eq!(
+            names(bridge_hub),
+            [
+                "upgrade",
+                "upgrade",
+                "gateway-operating-mode",
+                "inbound-queue-operating-mode",
+                "outbound-queue-operating-mode",
+                "ethereum-client-operating-mode",
+                "pricing-parameters",
+                "token-transfer-fees",
+                "force-checkpoint",
+                "gateway-address",
+                "register-token",
+                "set-storage",
+            ]
+        );
+        assert_eq!(
+            names(asset_hub),
+            [
+                "system-frontend-operating-mode",
+                "asset-hub-fee",
+                "asset-hub-fee",
+                "register-ether",
+                "update-asset",
+                "register-token",
+                "register-tokens",
+                "force-xcm-version",
+            ]
+        );
+        // u128 fields, which serde cannot buffer directly
+        assert!(matches!(
+            &asset_hub.actions[4],
+            Action::UpdateAsset { min_balance: 1, .. }
+        ));
+        let Action::RegisterToken { location, .. } = &bridge_hub.actions[10] else {
+            panic!("expected register-token");
+        };
+        assert_eq!(
+            location.interior,
+            [
+                snowbridge_token_list::Junction::Parachain(2034),
+                snowbridge_token_list::Junction::GeneralIndex(0)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_defaults() {
+        let manifest = parse(
+            r#"
+            [[batches]]
+            chain = "asset-hub"
+            actions = [{ action = "register-ether" }, { action = "asset-hub-fee" }]
+
+            [[batches]]
+            chain = "bridge-hub"
+            actions = [
+              { action = "upgrade", logic-address = "0x36e74FCAAcb07773b144Ca19Ef2e32Fc972aC50b", logic-code-hash = "0xe3cfcc0042ad4c819c627fb2a84ba0822d67747a8618a4e1c4eb0c5112b17903" },
+              { action = "gateway-operating-mode", mode = "normal" },
+            ]
+            "#,
+        )
+        .unwrap();
+        // The batches and the calls of a batch are combined the same way by default
+        assert_eq!(manifest.batch, BatchMode::BatchAll);
+        assert_eq!(manifest.batches[0].batch, BatchMode::BatchAll);
+        assert!(matches!(
+            &manifest.batches[0].actions[..],
+            [
+                Action::RegisterEther {
+                    min_balance: 1,
+                    decimals: 18,
+                    ..
+                },
+                Action::AssetHubFee {
+                    fee: None,
+                    v2: false
+                }
+            ]
+        ));
+        assert!(matches!(
+            &manifest.batches[1].actions[..],
+            [
+                Action::Upgrade {
+                    initializer_params: None,
+                    initializer_gas: None,
+                    initializer_gas_margin: crate::initializer::DEFAULT_GAS_MARGIN_PERCENT,
+                    v2: false,
+                    skip_code_check: false,
+                    ..
+                },
+                Action::GatewayOperatingMode {
+                    mode: GatewayOperatingModeEnum::Normal,
+                    v2: false
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn parse_json() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{
+              "batches": [{
+                "chain": "bridge-hub",
+                "actions": [{ "action": "gateway-operating-mode", "mode": "normal", "v2": true }]
+              }]
+            }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &manifest.batches[0].actions[..],
+            [Action::GatewayOperatingMode {
+                mode: GatewayOperatingModeEnum::Normal,
+                v2: true
+            }]
+        ));
+    }
+
+    #[test]
+    fn reject_unknown_fields() {
+        let batch = r#"
+            [[batches]]
+            chain = "bridge-hub"
+            actions = [{ action = "gateway-operating-mode", mode = "normal" }]
+        "#;
+        assert!(parse(batch).is_ok());
+
+        let err = parse(&format!("batch-mode = \"batch-all\"\n{batch}")).unwrap_err();
+        assert!(err.contains("unknown field `batch-mode`"), "{err}");
+
+        let err = parse(&batch.replace("chain =", "note = \"x\"\nchain =")).unwrap_err();
+        assert!(err.contains("unknown field `note`"), "{err}");
+
+        let err =
+            parse(&batch.replace("mode = \"normal\"", "mode = \"normal\", v3 = true")).unwrap_err();
+        assert!(err.contains("unknown field `v3`"), "{err}");
+
+        // snake_case is not accepted for kebab-case fields
+        let err = parse(
+            r#"
+            [[batches]]
+            chain = "asset-hub"
+            actions = [{ action = "register-tokens", token_list = "tokens.json" }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("unknown field `token_list`"), "{err}");
+
+        let err = parse(
+            r#"
+            [[batches]]
+            chain = "asset-hub"
+            actions = [{ action = "register-token", location = { parents = 1, interior = [], extra = 1 }, name = "DOT", symbol = "DOT", decimals = 10 }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("unknown field `extra`"), "{err}");
+    }
+
+    #[test]
+    fn reject_invalid_actions() {
+        let err = parse(
+            r#"
+            [[batches]]
+            chain = "bridge-hub"
+            actions = [{ action = "halt-everything" }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("unknown variant `halt-everything`"), "{err}");
+
+        let err = parse(
+            r#"
+            [[batches]]
+            chain = "relay"
+            actions = [{ action = "force-xcm-version" }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("unknown variant `relay`"), "{err}");
+
+        let err = parse(
+            r#"
+            [[batches]]
+            chain = "bridge-hub"
+            actions = [{ action = "gateway-operating-mode" }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("missing field `mode`"), "{err}");
+    }
+}
@@ -0,0 +1,249 @@
+//! Comparing the bundled runtime metadata, which the static bindings are generated from,
+//! against the live runtimes.
+//!
+//! Calls are compared by their subxt validation hash, which covers the shape of their
+//! arguments, and by their pallet and call indices. A call that differs is encoded by the
+//! bindings in a way the live runtime may reject or, worse, dispatch as another call.
+
+use crate::constants::Network;
+use crate::Chain;
+use codec::Decode;
+use std::sync::Mutex;
+use subxt::{Metadata, OnlineClient, PolkadotConfig};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// A call on one of the chains.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ChainCall {
+    pub chain: Chain,
+    /// `Pallet.call_name`
+    pub call: String,
+}
+
+/// Compare the bundled metadata of each chain against the metadata of its live runtime,
+/// warning about every pallet that differs, and return the calls whose shape or indices
+/// differ.
+pub fn compare(
+    network: Network,
+    apis: &[(Chain, &OnlineClient<PolkadotConfig>)],
+) -> Result<Vec<ChainCall>> {
+    let mut changed = vec![];
+    for &(chain, api) in apis {
+        let bundled = decode_metadata(bundled_metadata(network, chain))?;
+        let live = api.metadata();
+        let (pallets, calls) = changed_calls(&bundled, &live);
+        if pallets.is_empty() {
+            continue;
+        }
+        eprintln!(
+            "Warning: the bundled {} metadata is out of date. Pallets that changed: {}",
+            chain_label(chain),
+            pallets.join(", ")
+        );
+        if !calls.is_empty() {
+            eprintln!("  Calls that changed: {}", calls.join(", "));
+        }
+        changed.extend(calls.into_iter().map(|call| ChainCall { chain, call }));
+    }
+    Ok(changed)
+}
+
+/// Fail if any of the calls in a preimage changed, unless `allow` is set.
+pub fn check_calls(changed: &[ChainCall], used: &[ChainCall], allow: bool) -> Result<()> {
+    let mut affected: Vec<&ChainCall> = used.iter().filter(|call| changed.contains(call)).collect();
+    affected.sort();
+    affected.dedup();
+    if affected.is_empty() {
+        return Ok(());
+    }
+    let affected: Vec<String> = affected
+        .iter()
+        .map(|call| format!("{} on {}", call.call, chain_label(call.chain)))
+        .collect();
+    let message = format!(
+        "The preimage uses calls that changed since the bundled metadata was generated: {}",
+        affected.join(", ")
+    );
+    if allow {
+        eprintln!("Warning: {message}");
+        return Ok(());
+    }
+    Err(format!(
+        "{message}. Regenerate the metadata (see the README), or pass --allow-metadata-mismatch"
+    )
+    .into())
+}
+
+/// `Pallet.call_name` of an encoded call, looked up by its indices in `metadata`.
+pub fn call_name_by_index(metadata: &'static [u8], encoded: &[u8]) -> Option<String> {
+    let metadata = bundled(metadata)?;
+    let [pallet_index, call_index, ..] = encoded else {
+        return None;
+    };
+    let pallet = metadata.pallet_by_index(*pallet_index)?;
+    let variant = pallet.call_variant_by_index(*call_index)?;
+    Some(format!("{}.{}", pallet.name(), variant.name))
+}
+
+/// `Pallet.EventName` of an encoded event, looked up by its indices in `metadata`.
+pub fn event_name_by_index(metadata: &'static [u8], encoded: &[u8]) -> Option<String> {
+    let metadata = bundled(metadata)?;
+    let [pallet_index, event_index, ..] = encoded else {
+        return None;
+    };
+    let pallet = metadata.pallet_by_index(*pallet_index)?;
+    let variant = pallet.event_variant_by_index(*event_index)?;
+    Some(format!("{}.{}", pallet.name(), variant.name))
+}
+
+/// Bundled metadata, decoded once and cached by address, as every call of a preimage is
+/// named from it.
+fn bundled(metadata: &'static [u8]) -> Option<Metadata> {
+    static DECODED: Mutex<Vec<(usize, Metadata)>> = Mutex::new(Vec::new());
+    let key = metadata.as_ptr() as usize;
+    let mut decoded = DECODED.lock().unwrap();
+    if let Some((_, metadata)) = decoded.iter().find(|(address, _)| *address == key) {
+        return Some(metadata.clone());
+    }
+    let metadata = decode_metadata(metadata).ok()?;
+    decoded.push((key, metadata.clone()));
+    Some(metadata)
+}
+
+/// The pallets whose hash differs, and the calls whose hash or indices differ.
+fn changed_calls(bundled: &Metadata, live: &Metadata) -> (Vec<String>, Vec<String>) {
+    let mut pallets = vec![];
+    let mut calls = vec![];
+    for pallet in bundled.pallets() {
+        let live_pallet = live.pallet_by_name(pallet.name());
+        let same_index = live_pallet.as_ref().map(|live| live.index()) == Some(pallet.index());
+        if same_index && live_pallet.as_ref().map(|live| live.hash()) == Some(pallet.hash()) {
+            continue;
+        }
+        pallets.push(pallet.name().to_owned());
+        for variant in pallet.call_variants().unwrap_or_default() {
+            let unchanged = same_index
+                && live_pallet.as_ref().is_some_and(|live| {
+                    live.call_variant_by_name(&variant.name)
+                        .is_some_and(|live_variant| live_variant.index == variant.index)
+                        && live.call_hash(&variant.name) == pallet.call_hash(&variant.name)
+                });
+            if !unchanged {
+                calls.push(format!("{}.{}", pallet.name(), variant.name));
+            }
+        }
+    }
+    (pallets, calls)
+}
+
+fn decode_metadata(metadata: &[u8]) -> Result<Metadata> {
+    Metadata::decode(&mut &metadata[..])
+        .map_err(|e| format!("Failed to decode the bundled metadata: {e}").into())
+}
+
+fn bundled_metadata(network: Network, chain: Chain) -> &'static [u8] {
+    match (network, chain) {
+        (Network::Polkadot, Chain::BridgeHub) => crate::polkadot::bridge_hub_runtime::METADATA,
+        (Network::Polkadot, Chain::AssetHub) => crate::polkadot::asset_hub_runtime::METADATA,
+        (Network::Polkadot, Chain::Relay) => crate::polkadot::relay_runtime::METADATA,
+        (Network::Westend, Chain::BridgeHub) => crate::westend::bridge_hub_runtime::METADATA,
+        (Network::Westend, Chain::AssetHub) => crate::westend::asset_hub_runtime::METADATA,
+        (Network::Westend, Chain::Relay) => crate::westend::relay_runtime::METADATA,
+        (Network::Paseo, Chain::BridgeHub) => crate::paseo::bridge_hub_runtime::METADATA,
+        (Network::Paseo, Chain::AssetHub) => crate::paseo::asset_hub_runtime::METADATA,
+        (Network::Paseo, Chain::Relay) => crate::paseo::relay_runtime::METADATA,
+    }
+}
+
+fn chain_label(chain: Chain) -> &'static str {
+    match chain {
+        Chain::BridgeHub => "BridgeHub",
+        Chain::AssetHub => "AssetHub",
+        Chain::Relay => "relay chain",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Encode;
+    use subxt::ext::frame_metadata::RuntimeMetadataPrefixed;
+    use subxt::ext::frame_metadata::{v15::RuntimeMetadataV15, RuntimeMetadata};
+
+    /// A runtime with a single pallet, `Test` at index 7, with the calls `set_name`,
+    /// `set_limit`, `transfer` and `set_config`.
+    const METADATA: &[u8] = include_bytes!("../fixtures/dynamic-call-metadata.scale");
+
+    /// The fixture, changed by `modify` as a runtime upgrade would.
+    fn upgraded(modify: impl FnOnce(&mut RuntimeMetadataV15)) -> Metadata {
+        let mut prefixed = RuntimeMetadataPrefixed::decode(&mut &METADATA[..]).unwrap();
+        let RuntimeMetadata::V15(metadata) = &mut prefixed.1 else {
+            panic!("the fixture is V15 metadata");
+        };
+        modify(metadata);
+        decode_metadata(&prefixed.encode()).unwrap()
+    }
+
+    /// Rename the first field of the call `name` of the `Test` pallet.
+    fn rename_argument(metadata: &mut RuntimeMetadataV15, name: &str, to: &str) {
+        let calls = metadata.pallets[0].calls.as_ref().unwrap().ty.id;
+        let ty = &mut metadata.types.types[calls as usize].ty;
+        let scale_info::TypeDef::Variant(calls) = &mut ty.type_def else {
+            panic!("calls are an enum");
+        };
+        let call = calls.variants.iter_mut().find(|v| v.name == name).unwrap();
+        call.fields[0].name = Some(to.to_owned());
+    }
+
+    #[test]
+    fn unchanged_metadata_has_no_changed_calls() {
+        let bundled = decode_metadata(METADATA).unwrap();
+        let (pallets, calls) = changed_calls(&bundled, &upgraded(|_| {}));
+        assert!(pallets.is_empty());
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn finds_calls_whose_arguments_changed() {
+        let bundled = decode_metadata(METADATA).unwrap();
+        let live = upgraded(|metadata| rename_argument(metadata, "set_limit", "max"));
+        let (pallets, calls) = changed_calls(&bundled, &live);
+        assert_eq!(pallets, ["Test"]);
+        assert_eq!(calls, ["Test.set_limit"]);
+    }
+
+    #[test]
+    fn moved_or_removed_pallets_change_every_call() {
+        let bundled = decode_metadata(METADATA).unwrap();
+        let all = [
+            "Test.set_name",
+            "Test.set_limit",
+            "Test.transfer",
+            "Test.set_config",
+        ];
+        let live = upgraded(|metadata| metadata.pallets[0].index = 8);
+        assert_eq!(
+            changed_calls(&bundled, &live),
+            (vec!["Test".into()], all.map(String::from).to_vec())
+        );
+        let live = upgraded(|metadata| metadata.pallets[0].name = "Renamed".into());
+        assert_eq!(changed_calls(&bundled, &live).1, all);
+    }
+
+    #[test]
+    fn only_used_calls_that_changed_fail() {
+        let call = |chain, call: &str| ChainCall {
+            chain,
+            call: call.to_owned(),
+        };
+        let changed = [call(Chain::BridgeHub, "EthereumSystem.upgrade")];
+        let unaffected = [
+            call(Chain::AssetHub, "EthereumSystem.upgrade"),
+            call(Chain::BridgeHub, "EthereumSystem.set_operating_mode"),
+        ];
+        assert!(check_calls(&changed, &unaffected, false).is_ok());
+        assert!(check_calls(&changed, &changed, false).is_err());
+        assert!(check_calls(&changed, &changed, true).is_ok());
+    }
+}
@@ -0,0 +1,73 @@
+//! Polkadot instantiation of the network-generic proposal modules.
+//!
+//! Each network includes the same source files against its own runtime bindings, so a
+//! single binary can target any network selected with `--network`.
+
+pub mod asset_hub_runtime {
+    pub use asset_hub_polkadot_runtime::runtime_types::asset_hub_polkadot_runtime::{
+        governance::origins::pallet_custom_origins::Origin as GovernanceOrigin, OriginCaller,
+        ProxyType, RuntimeCall, RuntimeEvent,
+    };
+    pub use asset_hub_polkadot_runtime::*;
+}
+
+pub mod bridge_hub_runtime {
+    pub use bridge_hub_polkadot_runtime::runtime_types::bridge_hub_polkadot_runtime::{
+        RuntimeCall, RuntimeEvent,
+    };
+    pub use bridge_hub_polkadot_runtime::*;
+}
+
+#[allow(unused)]
+pub mod relay_runtime {
+    pub use polkadot_runtime::runtime_types::polkadot_runtime::RuntimeCall;
+    pub use polkadot_runtime::*;
+}
+
+pub mod constants {
+    pub use crate::constants::polkadot::*;
+}
+
+#[path = "commands.rs"]
+pub mod commands;
+#[path = "decode.rs"]
+pub mod decode;
+#[path = "dry_run.rs"]
+pub mod dry_run;
+#[path = "expectations.rs"]
+pub mod expectations;
+#[path = "freeze.rs"]
+pub mod freeze;
+#[path = "helpers.rs"]
+pub mod helpers;
+#[path = "manifest.rs"]
+pub mod manifest;
+#[path = "proposal.rs"]
+pub mod proposal;
+#[path = "registrations.rs"]
+pub mod registrations;
+#[path = "scheduler.rs"]
+pub mod scheduler;
+#[path = "submission.rs"]
+pub mod submission;
+#[path = "system_commands.rs"]
+pub mod system_commands;
+#[path = "treasury_commands.rs"]
+pub mod treasury_commands;
+#[path = "verify.rs"]
+pub mod verify;
+#[path = "wrappers.rs"]
+pub mod wrappers;
+
+use asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+
+/// AssetHub on Polkadot has no sudo pallet.
+pub fn sudo(
+    _call: Box<AssetHubRuntimeCall>,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    Err("--sudo is not available on Polkadot".into())
+}
+
+pub fn unwrap_sudo(_call: &AssetHubRuntimeCall) -> Option<&AssetHubRuntimeCall> {
+    None
+}
@@ -0,0 +1,710 @@
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::commands;
+use super::constants::{
+    ASSET_HUB_API, ASSET_HUB_ID, BRIDGE_HUB_API, BRIDGE_HUB_ID, NETWORK, RELAY_API,
+};
+use super::decode;
+use super::dry_run;
+use super::expectations;
+use super::freeze;
+use super::helpers::{
+    force_xcm_version, send_xcm_asset_hub, send_xcm_bridge_hub, send_xcm_relay, utility_batch_all,
+    utility_force_batch,
+};
+use super::initializer;
+use super::manifest;
+use super::registrations;
+use super::scheduler::{self, ScheduleTime};
+use super::submission;
+use super::sudo;
+use super::system_commands;
+use super::treasury_commands;
+use super::verify;
+use super::wrappers;
+use crate::constants::Network;
+use crate::dynamic_call;
+use crate::metadata_check;
+use crate::snapshot::FeeSnapshot;
+use crate::{
+    Chain, ChopsticksEnactment, Cli, Command, Context, Format, GatewayOperatingModeEnum,
+    GovUpdate202501Args, OperatingModeEnum, Submission, UpgradeArgs,
+};
+use alloy_primitives::{address, FixedBytes};
+use codec::{DecodeAll, Encode};
+use hex_literal::hex;
+use snowbridge_preimage_chopsticks::{
+    generate_chopsticks_script, generate_chopsticks_xcm, Enactment, XcmEndpoints,
+};
+use sp_crypto_hashing::blake2_256;
+use std::{io::Write, path::PathBuf};
+use subxt::utils::H256;
+
+/// Build the proposal for `cli.command` against this network's runtimes and emit it.
+pub async fn run(cli: &Cli, context: &Context) -> Result<(), Box<dyn std::error::Error>> {
+    // Fees read by `halt-bridge --snapshot-out`, saved once the preimage is emitted
+    let mut snapshot: Option<(FeeSnapshot, &PathBuf, bool)> = None;
+    // The thaw call built by `freeze-asset --thaw-out`, written once the preimage is emitted
+    let mut thaw: Option<(AssetHubRuntimeCall, &PathBuf)> = None;
+    let call = match &cli.command {
+        Command::ForceCheckpoint(params) => {
+            let call = commands::force_checkpoint(params).await?;
+            send_xcm_bridge_hub(context, vec![call]).await?
+        }
+        Command::Initialize(params) => {
+            let (set_pricing_parameters, set_ethereum_fee) =
+                commands::pricing_parameters(context, &params.pricing_parameters).await?;
+            let bridge_hub_call = send_xcm_bridge_hub(
+                context,
+                vec![
+                    commands::set_gateway_address(&params.gateway_address),
+                    set_pricing_parameters,
+                    commands::gateway_operating_mode(
+                        &params.gateway_operating_mode.gateway_operating_mode,
+                    ),
+                    commands::force_checkpoint(&params.force_checkpoint).await?,
+                ],
+            )
+            .await?;
+            let (register_ether_call, set_ether_metadata_call) =
+                commands::register_ether(&params.register_ether);
+            let asset_hub_call = send_xcm_asset_hub(
+                context,
+                vec![
+                    register_ether_call,
+                    set_ether_metadata_call,
+                    force_xcm_version(),
+                    set_ethereum_fee,
+                ],
+            )
+            .await?;
+            utility_batch_all(vec![bridge_hub_call, asset_hub_call])
+        }
+        Command::UpdateAsset(params) => {
+            send_xcm_asset_hub(
+                context,
+                vec![
+                    commands::make_asset_sufficient(params),
+                    commands::force_set_metadata(params),
+                ],
+            )
+            .await?
+        }
+        Command::GatewayOperatingMode(params) => {
+            let call = match params.v2 {
+                true => commands::gateway_operating_mode_v2(&params.gateway_operating_mode),
+                false => commands::gateway_operating_mode(&params.gateway_operating_mode),
+            };
+            send_xcm_bridge_hub(context, vec![call]).await?
+        }
+        Command::Upgrade(params) => {
+            let call = commands::upgrade(context, params).await?;
+            send_xcm_bridge_hub(context, vec![call]).await?
+        }
+        Command::TokenTransferFees(params) => {
+            let call = system_commands::set_token_transfer_fees(params)?;
+            send_xcm_bridge_hub(context, vec![call]).await?
+        }
+        Command::RegisterToken(params) if params.v2 => {
+            let call = system_commands::register_token_v2(params)?;
+            send_xcm_asset_hub(context, vec![call]).await?
+        }
+        Command::RegisterToken(params) => {
+            let call = system_commands::register_token(params)?;
+            send_xcm_bridge_hub(context, vec![call]).await?
+        }
+        Command::PricingParameters(params) => {
+            let (set_pricing_parameters, set_ethereum_fee) =
+                commands::pricing_parameters(context, params).await?;
+            let bridge_hub_call =
+                send_xcm_bridge_hub(context, vec![set_pricing_parameters]).await?;
+            let asset_hub_call = send_xcm_asset_hub(context, vec![set_ethereum_fee]).await?;
+            utility_batch_all(vec![bridge_hub_call, asset_hub_call])
+        }
+        Command::HaltBridge(params) => {
+            let mut bh_calls = vec![];
+            let mut ah_calls = vec![];
+            let mut halt_all = params.all;
+            // if no individual option specified, assume halt the whole bridge.
+            if !params.gateway
+                && !params.gateway_v2
+                && !params.inbound_queue
+                && !params.inbound_queue_v1
+                && !params.inbound_queue_v2
+                && !params.outbound_queue
+                && !params.system_frontend
+                && !params.ethereum_client
+                && !params.assethub_max_fee
+                && !params.assethub_max_fee_v2
+            {
+                halt_all = true;
+            }
+            // Gateway halt commands must be enqueued BEFORE any local outbound-queue
+            // halt takes effect, otherwise the SetOperatingMode command cannot be
+            // committed for delivery to Ethereum. Push both V1 and V2 variants so the
+            // halt is delivered via whichever outbound queue is operational.
+            if params.gateway || halt_all {
+                bh_calls.push(commands::gateway_operating_mode(
+                    &GatewayOperatingModeEnum::RejectingOutboundMessages,
+                ));
+                bh_calls.push(commands::gateway_operating_mode_v2(
+                    &GatewayOperatingModeEnum::RejectingOutboundMessages,
+                ));
+            } else if params.gateway_v2 {
+                // V2-only: leave V1 SetOperatingMode unsent.
+                bh_calls.push(commands::gateway_operating_mode_v2(
+                    &GatewayOperatingModeEnum::RejectingOutboundMessages,
+                ));
+            }
+            if params.inbound_queue || params.inbound_queue_v1 || halt_all {
+                bh_calls.push(commands::inbound_queue_operating_mode(
+                    &OperatingModeEnum::Halted,
+                ));
+            }
+            if params.inbound_queue || params.inbound_queue_v2 || halt_all {
+                bh_calls.push(commands::inbound_queue_v2_operating_mode(
+                    &OperatingModeEnum::Halted,
+                ));
+            }
+            if params.outbound_queue || halt_all {
+                // V1 local halt on BridgeHub. V2's outbound-queue-v2 has no local halt;
+                // the system-frontend halt below is the effective V2 outbound lever.
+                bh_calls.push(commands::outbound_queue_operating_mode(
+                    &OperatingModeEnum::Halted,
+                ));
+                // system-frontend halt on AssetHub: short-circuits the PausableExporter
+                // wrapping the AH->Ethereum router, blocking both V1 and V2 exports at
+                // the source regardless of user or parachain origin.
+                ah_calls.push(commands::system_frontend_operating_mode(
+                    &OperatingModeEnum::Halted,
+                ));
+            } else if params.system_frontend {
+                // Router-layer halt: AH frontend, blocks both V1 and V2 P->E at the
+                // PausableExporter. V1 BH outbound-queue left running so in-flight V1
+                // messages continue to drain.
+                ah_calls.push(commands::system_frontend_operating_mode(
+                    &OperatingModeEnum::Halted,
+                ));
+            }
+            if params.ethereum_client || halt_all {
+                bh_calls.push(commands::ethereum_client_operating_mode(
+                    &OperatingModeEnum::Halted,
+                ));
+            }
+            if let Some(path) = &params.snapshot_out {
+                // Read the fees about to be overwritten now, and save them for resume-bridge
+                // once the preimage is emitted.
+                if !(params.assethub_max_fee || params.assethub_max_fee_v2 || halt_all) {
+                    eprintln!("The AssetHub fees are not halted, not saving them to a snapshot");
+                } else if cli.verify_hash.is_some() {
+                    eprintln!("Verifying: not saving the AssetHub fees to a snapshot");
+                } else {
+                    let fees = commands::asset_hub_fees(context, None).await?;
+                    fees.check(path, params.force)?;
+                    snapshot = Some((fees, path, params.force));
+                }
+            }
+            if params.assethub_max_fee || halt_all {
+                // Set both V1 and V2 AssetHub outbound fee storage items to u128::MAX.
+                ah_calls.push(commands::set_assethub_fee(u128::MAX));
+                ah_calls.push(commands::set_assethub_fee_v2(u128::MAX));
+            } else if params.assethub_max_fee_v2 {
+                // V2-only: leave V1 fee untouched.
+                ah_calls.push(commands::set_assethub_fee_v2(u128::MAX));
+            }
+            force_batch_levers(context, bh_calls, ah_calls).await?
+        }
+        Command::ResumeBridge(params) => {
+            let mut bh_calls = vec![];
+            let mut ah_calls = vec![];
+            let mut resume_all = params.all;
+            // if no individual option specified, assume resume the whole bridge.
+            if !params.gateway
+                && !params.gateway_v2
+                && !params.inbound_queue
+                && !params.inbound_queue_v1
+                && !params.inbound_queue_v2
+                && !params.outbound_queue
+                && !params.system_frontend
+                && !params.ethereum_client
+                && !params.assethub_max_fee
+                && !params.assethub_max_fee_v2
+            {
+                resume_all = true;
+            }
+            // The reverse of halt-bridge: the local outbound-queue must be resumed
+            // BEFORE the Gateway resume commands are enqueued, otherwise they cannot be
+            // committed for delivery to Ethereum.
+            if params.outbound_queue || resume_all {
+                bh_calls.push(commands::outbound_queue_operating_mode(
+                    &OperatingModeEnum::Normal,
+                ));
+                ah_calls.push(commands::system_frontend_operating_mode(
+                    &OperatingModeEnum::Normal,
+                ));
+            } else if params.system_frontend {
+                ah_calls.push(commands::system_frontend_operating_mode(
+                    &OperatingModeEnum::Normal,
+                ));
+            }
+            if params.gateway || resume_all {
+                bh_calls.push(commands::gateway_operating_mode(
+                    &GatewayOperatingModeEnum::Normal,
+                ));
+                bh_calls.push(commands::gateway_operating_mode_v2(
+                    &GatewayOperatingModeEnum::Normal,
+                ));
+            } else if params.gateway_v2 {
+                bh_calls.push(commands::gateway_operating_mode_v2(
+                    &GatewayOperatingModeEnum::Normal,
+                ));
+            }
+            if params.inbound_queue || params.inbound_queue_v1 || resume_all {
+                bh_calls.push(commands::inbound_queue_operating_mode(
+                    &OperatingModeEnum::Normal,
+                ));
+            }
+            if params.inbound_queue || params.inbound_queue_v2 || resume_all {
+                bh_calls.push(commands::inbound_queue_v2_operating_mode(
+                    &OperatingModeEnum::Normal,
+                ));
+            }
+            if params.ethereum_client || resume_all {
+                bh_calls.push(commands::ethereum_client_operating_mode(
+                    &OperatingModeEnum::Normal,
+                ));
+            }
+            if params.assethub_max_fee || params.assethub_max_fee_v2 || resume_all {
+                // The fees were overwritten with u128::MAX, so restore them from before the halt.
+                let fees = match &params.fees_at {
+                    Some(block) => commands::asset_hub_fees(context, Some(H256(block.0))).await?,
+                    None => FeeSnapshot::load(&params.snapshot).map_err(|e| {
+                        format!("{e}. Restoring the AssetHub fees requires --snapshot or --fees-at")
+                    })?,
+                };
+                if fees.network != NETWORK.to_string() {
+                    return Err(
+                        format!("Snapshot was taken on {}, not {NETWORK}", fees.network).into(),
+                    );
+                }
+                eprintln!("Restoring AssetHub fees from block {}", fees.block);
+                if params.assethub_max_fee || resume_all {
+                    ah_calls.push(commands::set_assethub_fee(fees.base_fee()?));
+                    ah_calls.push(commands::set_assethub_fee_v2(fees.base_fee_v2()?));
+                } else {
+                    ah_calls.push(commands::set_assethub_fee_v2(fees.base_fee_v2()?));
+                }
+            }
+            force_batch_levers(context, bh_calls, ah_calls).await?
+        }
+        Command::RegisterEther(params) => {
+            let (register_ether_call, set_ether_metadata_call) = commands::register_ether(&params);
+            send_xcm_asset_hub(context, vec![register_ether_call, set_ether_metadata_call]).await?
+        }
+        Command::TreasuryProposal2024(params) => treasury_commands::treasury_proposal(&params),
+        Command::TreasuryProposal(params) => {
+            treasury_commands::treasury_proposal_from_schedule(params)?
+        }
+        Command::GovUpdate202501(GovUpdate202501Args {
+            pricing_parameters,
+            register_ether,
+        }) => {
+            let (set_pricing_parameters, set_ethereum_fee) =
+                commands::pricing_parameters(context, pricing_parameters).await?;
+
+            let bh_set_pricing_call =
+                send_xcm_bridge_hub(context, vec![set_pricing_parameters]).await?;
+
+            let ah_set_pricing_call = send_xcm_asset_hub(context, vec![set_ethereum_fee]).await?;
+
+            let (register_ether_call, set_ether_metadata_call) =
+                commands::register_ether(&register_ether);
+            let ah_register_ether_call =
+                send_xcm_asset_hub(context, vec![register_ether_call, set_ether_metadata_call])
+                    .await?;
+
+            utility_batch_all(vec![
+                bh_set_pricing_call,
+                ah_set_pricing_call,
+                ah_register_ether_call,
+            ])
+        }
+        Command::RegisterPnaBatch202503 => {
+            if NETWORK != Network::Polkadot {
+                return Err("RegisterPnaBatch202503 is only available on polkadot".into());
+            }
+            send_xcm_bridge_hub(context, commands::token_registrations()).await?
+        }
+        Command::RegisterErc20TokenMetadata => {
+            if NETWORK != Network::Polkadot {
+                return Err("RegisterErc20TokenMetadata is only available on polkadot".into());
+            }
+            let metadata_calls = commands::register_erc20_token_metadata();
+            let reg_call = commands::frequency_token_registrations();
+            utility_batch_all(vec![
+                send_xcm_asset_hub(context, metadata_calls).await?,
+                send_xcm_bridge_hub(context, reg_call).await?,
+            ])
+        }
+        Command::RegisterTokens(params) => registrations::register_tokens(context, params).await?,
+        Command::FreezeAsset(params) => {
+            if let Some(path) = &params.thaw_out {
+                let thaw_call = freeze::freeze_asset(context, &params.target, false, false).await?;
+                thaw = Some((thaw_call, path));
+            }
+            freeze::freeze_asset(context, &params.target, true, true).await?
+        }
+        Command::ThawAsset(params) => freeze::freeze_asset(context, params, false, true).await?,
+        Command::UpgradeV2 => {
+            if NETWORK != Network::Polkadot {
+                return Err("UpgradeV2 is only available on polkadot".into());
+            }
+            // Upgrade logic gateway on BH
+            let upgrade_call = commands::upgrade(
+                context,
+                &UpgradeArgs {
+                    logic_address: address!("8a887783E945233d51881e06835Ec78A8b575eCe"),
+                    logic_code_hash: FixedBytes::from_slice(&hex!(
+                        "cbabd7683b33e7d8f4b143def2d712999961b306e1f98782016439293d673849"
+                    )),
+                    initializer_params: Some(Default::default()),
+                    initializer_abi: None,
+                    initializer_type: None,
+                    initializer_args: None,
+                    initializer_gas: None,
+                    initializer_gas_margin: initializer::DEFAULT_GAS_MARGIN_PERCENT,
+                    v2: false,
+                    skip_code_check: false,
+                },
+            )
+            .await?;
+            let bh_xcm_call = send_xcm_bridge_hub(context, vec![upgrade_call]).await?;
+
+            // Set bound fee to 0.1 DOT on AH
+            let outbound_fee_call = commands::set_assethub_fee_v2(1_000_000_000);
+            let ah_xcm_call = send_xcm_asset_hub(context, vec![outbound_fee_call]).await?;
+
+            utility_batch_all(vec![bh_xcm_call, ah_xcm_call])
+        }
+        Command::ReplaySep2025 => {
+            let asset_hub_call = commands::replay_sep_2025_xcm();
+            send_xcm_asset_hub(context, vec![asset_hub_call]).await?
+        }
+        Command::MintFeb2026 => {
+            let bridge_hub_call = commands::mint_feb_2026_xcm();
+            send_xcm_bridge_hub(context, vec![bridge_hub_call]).await?
+        }
+        Command::SetPaseoFeeV2 => {
+            if NETWORK != Network::Paseo {
+                return Err("SetPaseoFeeV2 is only available on paseo".into());
+            }
+            // Set bound fee to 0.1 DOT (same as Polkadot V2) on AH
+            let outbound_fee_call = commands::set_assethub_fee_v2(1_000_000_000);
+            send_xcm_asset_hub(context, vec![outbound_fee_call]).await?
+        }
+        Command::Upgrade202603 => {
+            if NETWORK != Network::Polkadot {
+                return Err("Upgrade202603 is only available on polkadot".into());
+            }
+            // Upgrade logic gateway
+            let upgrade_call = commands::upgrade(
+                context,
+                &UpgradeArgs {
+                    logic_address: address!("36e74FCAAcb07773b144Ca19Ef2e32Fc972aC50b"),
+                    logic_code_hash: FixedBytes::from_slice(&hex!(
+                        "e3cfcc0042ad4c819c627fb2a84ba0822d67747a8618a4e1c4eb0c5112b17903"
+                    )),
+                    initializer_params: Some(Default::default()),
+                    initializer_abi: None,
+                    initializer_type: None,
+                    initializer_args: None,
+                    initializer_gas: None,
+                    initializer_gas_margin: initializer::DEFAULT_GAS_MARGIN_PERCENT,
+                    v2: false,
+                    skip_code_check: false,
+                },
+            )
+            .await?;
+            send_xcm_bridge_hub(context, vec![upgrade_call]).await?
+        }
+        Command::Call(params) => {
+            let metadata = match params.chain {
+                Chain::BridgeHub => super::bridge_hub_runtime::METADATA,
+                Chain::AssetHub => super::asset_hub_runtime::METADATA,
+                Chain::Relay => super::relay_runtime::METADATA,
+            };
+            let encoded =
+                dynamic_call::encode(metadata, &params.pallet, &params.call, &params.args)?;
+            match params.chain {
+                Chain::BridgeHub => {
+                    system_commands::check_dynamic_call(&format!(
+                        "{}.{}",
+                        params.pallet, params.call
+                    ));
+                    let call = BridgeHubRuntimeCall::decode_all(&mut &encoded[..])?;
+                    send_xcm_bridge_hub(context, vec![call]).await?
+                }
+                Chain::AssetHub => {
+                    let call = AssetHubRuntimeCall::decode_all(&mut &encoded[..])?;
+                    send_xcm_asset_hub(context, vec![call]).await?
+                }
+                Chain::Relay => send_xcm_relay(context, vec![encoded]).await?,
+            }
+        }
+        Command::FromManifest(params) => {
+            let manifest = manifest::load_manifest(&params.manifest)?;
+            manifest::build_manifest(context, &manifest).await?
+        }
+        Command::Decode(_) | Command::SuggestPricing(_) | Command::Verify(_) => {
+            unreachable!("handled before connecting")
+        }
+    };
+
+    // The call as dispatched once enacted, or once the schedule is due. The dry-run, the
+    // chopsticks scripts and their storage checks run it directly, as `Scheduler.schedule`
+    // itself forwards no XCM and writes nothing that could be checked.
+    let dispatched = if cli.sudo {
+        sudo(Box::new(call.clone()))?
+    } else {
+        call.clone()
+    };
+
+    // A multisig or proxy only adds a signed origin for the sudo key, so dry-run the call
+    // they dispatch
+    if cli.dry_run {
+        dry_run::dry_run(context, &dispatched).await?;
+    }
+
+    // The schedule is applied last, inside `--sudo`, since it needs a Root origin
+    let call = match (cli.schedule_at, cli.schedule_after) {
+        (Some(at), _) => {
+            let time = ScheduleTime::At(at);
+            scheduler::schedule(context, call, time, cli.schedule_name.as_deref()).await?
+        }
+        (None, Some(after)) => {
+            let time = ScheduleTime::After(after);
+            scheduler::schedule(context, call, time, cli.schedule_name.as_deref()).await?
+        }
+        (None, None) => call,
+    };
+    let scheduled = cli.schedule_at.is_some() || cli.schedule_after.is_some();
+
+    let call = if cli.sudo {
+        sudo(Box::new(call))?
+    } else {
+        call
+    };
+
+    let final_call = wrappers::wrap(context, call, &cli.origin).await?;
+
+    let used_calls = decode::used_calls(&final_call);
+    metadata_check::check_calls(
+        &context.changed_calls(NETWORK, &used_calls).await?,
+        &used_calls,
+        cli.allow_metadata_mismatch,
+    )?;
+
+    let preimage = final_call.encode();
+
+    if let Some(hash) = cli.verify_hash {
+        return verify::verify(context, H256(hash.0), &preimage).await;
+    }
+
+    let chopsticks_script = PathBuf::from("chopsticks-execute-upgrade.js");
+    let enactment = chopsticks_enactment(cli, &dispatched)?;
+    if scheduled {
+        eprintln!("The chopsticks scripts dispatch the call without Scheduler.schedule");
+    }
+    let enacted = dispatched.encode();
+    generate_chopsticks_script(&enacted, &enactment, chopsticks_script.clone())?;
+
+    if let Some(output_dir) = &cli.chopsticks_xcm {
+        let api = &cli.api_endpoints;
+        let endpoints = XcmEndpoints {
+            relay: api.relay_api.as_deref().unwrap_or(RELAY_API),
+            asset_hub: api.asset_hub_api.as_deref().unwrap_or(ASSET_HUB_API),
+            bridge_hub: api.bridge_hub_api.as_deref().unwrap_or(BRIDGE_HUB_API),
+            asset_hub_id: ASSET_HUB_ID,
+            bridge_hub_id: BRIDGE_HUB_ID,
+        };
+        let expectations = expectations::storage_expectations(&dispatched);
+        generate_chopsticks_xcm(&enacted, &enactment, &expectations, &endpoints, output_dir)?;
+        eprintln!(
+            "Chopsticks xcm configs and verify-preimage.mjs ({} storage checks) written to {}",
+            expectations.len(),
+            output_dir.display()
+        );
+    }
+
+    eprintln!("Preimage Hash: 0x{}", hex::encode(blake2_256(&preimage)));
+    eprintln!("Preimage Size: {}", preimage.len());
+
+    let bundle = match cli.submission.submission {
+        Some(mode) => {
+            let referendum_index = match cli.submission.referendum_index {
+                Some(index) => index,
+                None => submission::next_referendum_index(context).await?,
+            };
+            eprintln!("Referendum index: {referendum_index}");
+            Some(submission::build_bundle(
+                mode,
+                cli.submission.enactment_after,
+                referendum_index,
+                &final_call,
+            ))
+        }
+        None => None,
+    };
+    if let Some(bundle) = &bundle {
+        if matches!(cli.format, Format::Binary) {
+            return Err("--format binary cannot be used with --submission".into());
+        }
+        if cli.submission.signer.is_some() {
+            let asset_hub_url = cli
+                .api_endpoints
+                .asset_hub_api
+                .as_deref()
+                .unwrap_or(ASSET_HUB_API);
+            submission::submit_bundle(&cli.submission, asset_hub_url, bundle).await?;
+        }
+    }
+
+    match cli.format {
+        Format::Hex => match &bundle {
+            Some(bundle) => submission::print_bundle(bundle),
+            None => println!("0x{}", hex::encode(preimage)),
+        },
+        Format::Binary => {
+            std::io::stdout().write_all(&preimage)?;
+        }
+        Format::Json => {
+            let mut report = context.report.take();
+            report.network = NETWORK.to_string();
+            report.hash = format!("0x{}", hex::encode(blake2_256(&preimage)));
+            report.length = preimage.len();
+            report.call = Some(decode::decode_preimage(&preimage)?);
+            report.chopsticks_script = Some(chopsticks_script);
+            report.preimage = format!("0x{}", hex::encode(preimage));
+            report.submission = bundle
+                .as_ref()
+                .map(submission::bundle_report)
+                .unwrap_or_default();
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+
+    if let Some((fees, path, force)) = snapshot {
+        fees.save(path, force)?;
+        eprintln!(
+            "Saved AssetHub fees at block {} to {}",
+            fees.block,
+            path.display()
+        );
+    }
+
+    if let Some((call, path)) = thaw {
+        write_thaw_preimage(cli, context, call, path).await?;
+    }
+
+    Ok(())
+}
+
+/// Write the preimage of `freeze-asset --thaw-out`, wrapped like the freeze preimage but not
+/// scheduled, as the thaw is enacted by a later referendum.
+async fn write_thaw_preimage(
+    cli: &Cli,
+    context: &Context,
+    call: AssetHubRuntimeCall,
+    path: &PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let call = if cli.sudo {
+        sudo(Box::new(call))?
+    } else {
+        call
+    };
+    let call = wrappers::wrap(context, call, &cli.origin).await?;
+    let used_calls = decode::used_calls(&call);
+    metadata_check::check_calls(
+        &context.changed_calls(NETWORK, &used_calls).await?,
+        &used_calls,
+        cli.allow_metadata_mismatch,
+    )?;
+    let preimage = call.encode();
+    std::fs::write(path, format!("0x{}\n", hex::encode(&preimage)))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    eprintln!(
+        "Thaw preimage 0x{} ({} bytes) written to {}",
+        hex::encode(blake2_256(&preimage)),
+        preimage.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// The enactment path simulated by the chopsticks script, which must match how the
+/// dispatched call is wrapped.
+///
+/// A `Sudo.sudo` call wrapped by `--as-multi` or `--via-proxy` is signed directly by the
+/// sudo key in the script, as it would be dispatched by the multisig or proxied account.
+fn chopsticks_enactment(
+    cli: &Cli,
+    dispatched: &AssetHubRuntimeCall,
+) -> Result<Enactment, Box<dyn std::error::Error>> {
+    let path = match (cli.chopsticks_enactment, cli.submission.submission) {
+        (Some(path), _) => path,
+        (None, _) if cli.sudo => ChopsticksEnactment::Sudo,
+        (None, Some(Submission::Root)) | (None, None) => ChopsticksEnactment::Root,
+        (None, Some(Submission::WhitelistedCaller)) => ChopsticksEnactment::WhitelistedCaller,
+    };
+    if path == ChopsticksEnactment::Sudo && !cli.sudo {
+        return Err("--chopsticks-enactment sudo requires --sudo".into());
+    }
+    if cli.sudo && path != ChopsticksEnactment::Sudo {
+        return Err(
+            "A --sudo preimage can only be simulated with --chopsticks-enactment sudo".into(),
+        );
+    }
+    if cli.origin.as_multi || cli.origin.via_proxy.is_some() {
+        eprintln!("The chopsticks script signs the Sudo.sudo call with the sudo key directly");
+    }
+    Ok(match path {
+        ChopsticksEnactment::Root => Enactment::Root,
+        ChopsticksEnactment::WhitelistedCaller => Enactment::WhitelistedCaller {
+            dispatch: submission::whitelisted_dispatch(dispatched).encode(),
+        },
+        ChopsticksEnactment::FellowshipWhitelist => Enactment::FellowshipWhitelist {
+            dispatch: submission::whitelisted_dispatch(dispatched).encode(),
+        },
+        ChopsticksEnactment::Sudo => Enactment::Sudo,
+    })
+}
+
+/// Combine the BridgeHub and AssetHub calls of `halt-bridge` or `resume-bridge`.
+async fn force_batch_levers(
+    context: &Context,
+    bh_calls: Vec<BridgeHubRuntimeCall>,
+    ah_calls: Vec<AssetHubRuntimeCall>,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    // Use `force_batch` (not `batch_all` or `batch`) so every lever fires
+    // independently — a single failure (e.g. HRMP transport hiccup blocking
+    // pallet_xcm::send to BridgeHub) must not skip the AH-side halts, and
+    // vice versa. Per-call failures are reported via `ItemFailed` events.
+    let ah_call = if ah_calls.len() == 1 {
+        Some(ah_calls.into_iter().next().unwrap())
+    } else if ah_calls.len() > 1 {
+        Some(utility_force_batch(ah_calls))
+    } else {
+        None
+    };
+    let call = if bh_calls.len() > 0 && ah_call.is_none() {
+        send_xcm_bridge_hub(context, bh_calls).await?
+    } else if ah_call.is_some() && bh_calls.len() == 0 {
+        ah_call.unwrap()
+    } else {
+        let bh_xcm_send = send_xcm_bridge_hub(context, bh_calls).await?;
+        // BH XCM-send is the first call so the V2 Gateway halt (the first
+        // Transact inside the XCM) is processed before any AH-side halt.
+        utility_force_batch(vec![bh_xcm_send, ah_call.unwrap()])
+    };
+    Ok(call)
+}
@@ -0,0 +1,194 @@
+use super::asset_hub_runtime::runtime_types::pallet_assets;
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::bridge_hub_runtime::runtime_types::{
+    bounded_collections::bounded_vec::BoundedVec,
+    snowbridge_core::AssetMetadata,
+    snowbridge_pallet_system,
+    staging_xcm::v5::{
+        junction::{Junction, NetworkId},
+        junctions::Junctions,
+        location::Location,
+    },
+    xcm::VersionedLocation,
+};
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::commands::asset_hub_types::get_asset_id;
+use super::constants::{BRIDGE_HUB_ID, RELAY_NETWORK};
+use super::helpers::{send_xcm_asset_hub, send_xcm_bridge_hub, utility_batch_all};
+use crate::{Context, RegisterTokensArgs};
+use codec::Encode;
+use snowbridge_token_list::storage::{decode_asset_metadata, map_key};
+use snowbridge_token_list::{load_token_list, Token, TokenMetadata};
+use subxt::{OnlineClient, PolkadotConfig};
+
+/// Register the tokens of a token list that are not registered yet.
+///
+/// PNAs are registered on BridgeHub with `EthereumSystem.register_token` unless they already
+/// have a token ID in `NativeToForeignId`. ERC20s are registered on Ethereum through the
+/// Gateway, so only the `ForeignAssets` metadata of tokens that already exist on AssetHub is
+/// set, unless it is identical.
+pub async fn register_tokens(
+    context: &Context,
+    params: &RegisterTokensArgs,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    let chain_id = super::bridge_hub_runtime::CHAIN_ID;
+    let tokens = load_token_list(&params.token_list, chain_id)?;
+
+    let mut ah_calls = vec![];
+    let mut bh_calls = vec![];
+    for token in tokens.iter() {
+        let symbol = &token.metadata().symbol;
+        match token {
+            Token::Native { location, metadata } => {
+                let reanchored = location.reanchored_to_ethereum(&RELAY_NETWORK, BRIDGE_HUB_ID);
+                let key = map_key(
+                    "EthereumSystem",
+                    "NativeToForeignId",
+                    &bridge_hub_location(&reanchored)?.encode(),
+                );
+                if fetch_raw(context.bridge_hub_api()?, key).await?.is_some() {
+                    eprintln!("Skipping {symbol}: already registered on BridgeHub");
+                    continue;
+                }
+                eprintln!("Registering {symbol} on BridgeHub");
+                bh_calls.push(register_token(bridge_hub_location(location)?, metadata));
+            }
+            Token::Erc20 {
+                address,
+                metadata,
+                min_balance,
+                is_sufficient,
+            } => {
+                if min_balance.is_some() || is_sufficient.is_some() {
+                    eprintln!(
+                        "Warning: ignoring extensions.minBalance and extensions.isSufficient \
+                         of {symbol}, ERC20s are created through the Gateway on Polkadot"
+                    );
+                }
+                let asset_id = get_asset_id(chain_id, address.into_array()).encode();
+                let api = context.asset_hub_api()?;
+                if fetch_raw(api, map_key("ForeignAssets", "Asset", &asset_id))
+                    .await?
+                    .is_none()
+                {
+                    eprintln!(
+                        "Skipping {symbol}: {address} is not registered on AssetHub, \
+                         register it through the Gateway first"
+                    );
+                    continue;
+                }
+                let current = fetch_raw(api, map_key("ForeignAssets", "Metadata", &asset_id))
+                    .await?
+                    .map(|value| decode_asset_metadata(&value))
+                    .transpose()?;
+                if current.as_ref() == Some(metadata) {
+                    eprintln!("Skipping {symbol}: identical metadata already set on AssetHub");
+                    continue;
+                }
+                eprintln!("Setting the metadata of {symbol} on AssetHub");
+                ah_calls.push(force_set_metadata(chain_id, address.into_array(), metadata));
+            }
+        }
+    }
+
+    let mut calls = vec![];
+    if !ah_calls.is_empty() {
+        calls.push(send_xcm_asset_hub(context, ah_calls).await?);
+    }
+    if !bh_calls.is_empty() {
+        calls.push(send_xcm_bridge_hub(context, bh_calls).await?);
+    }
+    match calls.len() {
+        0 => Err("Every token in the list is already registered".into()),
+        1 => Ok(calls.pop().unwrap()),
+        _ => Ok(utility_batch_all(calls)),
+    }
+}
+
+async fn fetch_raw(
+    api: &OnlineClient<PolkadotConfig>,
+    key: Vec<u8>,
+) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+    Ok(api.storage().at_latest().await?.fetch_raw(key).await?)
+}
+
+fn register_token(location: Location, metadata: &TokenMetadata) -> BridgeHubRuntimeCall {
+    BridgeHubRuntimeCall::EthereumSystem(snowbridge_pallet_system::pallet::Call::register_token {
+        location: Box::new(VersionedLocation::V5(location)),
+        metadata: AssetMetadata {
+            name: BoundedVec(metadata.name.as_bytes().to_vec()),
+            symbol: BoundedVec(metadata.symbol.as_bytes().to_vec()),
+            decimals: metadata.decimals,
+        },
+    })
+}
+
+fn force_set_metadata(
+    chain_id: u64,
+    address: [u8; 20],
+    metadata: &TokenMetadata,
+) -> AssetHubRuntimeCall {
+    AssetHubRuntimeCall::ForeignAssets(pallet_assets::pallet::Call2::force_set_metadata {
+        id: get_asset_id(chain_id, address),
+        name: metadata.name.as_bytes().to_vec(),
+        symbol: metadata.symbol.as_bytes().to_vec(),
+        decimals: metadata.decimals,
+        is_frozen: false,
+    })
+}
+
+pub fn bridge_hub_location(
+    location: &snowbridge_token_list::Location,
+) -> Result<Location, Box<dyn std::error::Error>> {
+    let junctions: Vec<Junction> = location.interior.iter().map(junction).collect();
+    let interior = match junctions.len() {
+        0 => Junctions::Here,
+        1 => Junctions::X1(junctions.try_into().unwrap()),
+        2 => Junctions::X2(junctions.try_into().unwrap()),
+        3 => Junctions::X3(junctions.try_into().unwrap()),
+        4 => Junctions::X4(junctions.try_into().unwrap()),
+        5 => Junctions::X5(junctions.try_into().unwrap()),
+        6 => Junctions::X6(junctions.try_into().unwrap()),
+        7 => Junctions::X7(junctions.try_into().unwrap()),
+        8 => Junctions::X8(junctions.try_into().unwrap()),
+        n => return Err(format!("Location has {n} junctions, at most 8 are allowed").into()),
+    };
+    Ok(Location {
+        parents: location.parents,
+        interior,
+    })
+}
+
+fn junction(junction: &snowbridge_token_list::Junction) -> Junction {
+    use snowbridge_token_list::Junction as J;
+    match junction {
+        J::Parachain(id) => Junction::Parachain(*id),
+        J::PalletInstance(index) => Junction::PalletInstance(*index),
+        J::GeneralIndex(index) => Junction::GeneralIndex(*index),
+        J::GeneralKey { length, data } => Junction::GeneralKey {
+            length: *length,
+            data: *data,
+        },
+        J::GlobalConsensus(network) => Junction::GlobalConsensus(network_id(network)),
+        J::AccountKey20 { key } => Junction::AccountKey20 {
+            network: None,
+            key: *key,
+        },
+        J::AccountId32 { id } => Junction::AccountId32 {
+            network: None,
+            id: *id,
+        },
+    }
+}
+
+fn network_id(network: &snowbridge_token_list::NetworkId) -> NetworkId {
+    use snowbridge_token_list::NetworkId as N;
+    match network {
+        N::Polkadot => NetworkId::Polkadot,
+        N::Kusama => NetworkId::Kusama,
+        N::Ethereum { chain_id } => NetworkId::Ethereum {
+            chain_id: *chain_id,
+        },
+        N::ByGenesis(genesis) => NetworkId::ByGenesis(*genesis),
+    }
+}
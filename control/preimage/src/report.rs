@@ -0,0 +1,163 @@
+use crate::call_tree::CallNode;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Machine-readable summary of a generated preimage, emitted by `--format json`.
+///
+/// Amounts are decimal strings in the smallest unit (planck or wei), so that consumers
+/// without 128-bit integers do not lose precision.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub network: String,
+    pub preimage: String,
+    pub hash: String,
+    pub length: usize,
+    pub call: Option<CallNode>,
+    pub transact_weights: Vec<TransactWeight>,
+    pub chopsticks_script: Option<PathBuf>,
+    pub pricing: Vec<PricingReport>,
+    pub schedule: Option<ScheduleReport>,
+    pub multisig: Option<MultisigReport>,
+    pub upgrade: Option<UpgradeReport>,
+    pub initializer: Option<InitializerReport>,
+    /// Calls emitted by `--submission`, in submission order
+    pub submission: Vec<SubmissionCallReport>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct Weight {
+    pub ref_time: u64,
+    pub proof_size: u64,
+}
+
+impl From<(u64, u64)> for Weight {
+    fn from((ref_time, proof_size): (u64, u64)) -> Self {
+        Weight {
+            ref_time,
+            proof_size,
+        }
+    }
+}
+
+/// Weight of a `Transact` sent to BridgeHub.
+#[derive(Debug, Serialize)]
+pub struct TransactWeight {
+    /// `Pallet.call_name` of the transacted call
+    pub call: String,
+    /// Weight queried from BridgeHub, before `increase_weight`
+    pub queried: Option<Weight>,
+    /// The `fallback_max_weight` used in the `Transact`
+    pub fallback_max_weight: Option<Weight>,
+}
+
+/// Values computed by `commands::pricing_parameters`.
+#[derive(Debug, Serialize)]
+pub struct PricingReport {
+    pub exchange_rate_numerator: u64,
+    pub exchange_rate_denominator: u64,
+    pub multiplier_numerator: u64,
+    pub multiplier_denominator: u64,
+    pub fee_per_gas: String,
+    pub local_reward: String,
+    pub remote_reward: String,
+    pub delivery_fee_local: String,
+    pub delivery_fee_remote: String,
+    /// Delivery fee adjusted up by 10%, written to `BridgeHubEthereumBaseFee` on AssetHub
+    pub asset_hub_base_fee: String,
+    pub asset_hub_base_fee_storage_key: String,
+    /// Pricing on chain before the proposal is enacted
+    pub current: CurrentPricingReport,
+    /// Delivery fee of each kind of V1 command, compared against `asset_hub_base_fee`
+    pub command_fees: Vec<CommandFeeReport>,
+}
+
+/// Outbound queue fee of a V1 command with the proposed pricing parameters.
+#[derive(Debug, Serialize)]
+pub struct CommandFeeReport {
+    pub command: String,
+    pub local: String,
+    pub remote: String,
+}
+
+/// `EthereumSystem::PricingParameters` and AssetHub `BridgeHubEthereumBaseFee` read from chain.
+#[derive(Debug, Serialize)]
+pub struct CurrentPricingReport {
+    pub bridge_hub_block: String,
+    pub asset_hub_block: String,
+    /// Fixed-point decimal, in ETH per relay chain token
+    pub exchange_rate: String,
+    /// Fixed-point decimal
+    pub multiplier: String,
+    pub fee_per_gas: String,
+    pub local_reward: String,
+    pub remote_reward: String,
+    /// Delivery fee for the current parameters, calculated by the latest BridgeHub runtime
+    pub delivery_fee_local: String,
+    pub delivery_fee_remote: String,
+    /// `None` if the storage item is not set
+    pub asset_hub_base_fee: Option<String>,
+}
+
+/// Block computed by `--schedule-at` or `--schedule-after`.
+#[derive(Debug, Serialize)]
+pub struct ScheduleReport {
+    /// `schedule_named` id, if `--schedule-name` was given
+    pub id: Option<String>,
+    /// Relay chain block the call is scheduled for
+    pub when: u32,
+    /// Number of blocks the actual dispatch time may be off by
+    pub uncertainty_blocks: u32,
+    pub target_time: String,
+    /// Relay parent of the latest AssetHub block
+    pub current_block: u32,
+    /// Timestamp of the latest AssetHub block
+    pub current_time: String,
+}
+
+/// What the other signatories need for `approve_as_multi`, with `--as-multi`.
+#[derive(Debug, Serialize)]
+pub struct MultisigReport {
+    /// blake2_256 hash of the call wrapped by `as_multi`
+    pub call_hash: String,
+    pub threshold: u16,
+    /// Sorted, as passed to `as_multi`
+    pub other_signatories: Vec<String>,
+    pub max_weight: Weight,
+}
+
+/// Gateway logic verified by `commands::upgrade`.
+#[derive(Debug, Serialize)]
+pub struct UpgradeReport {
+    pub gateway: String,
+    /// The Gateway's `implementation()` before the upgrade
+    pub current_implementation: String,
+    pub logic_address: String,
+    /// keccak256 of the code deployed at `logic_address`
+    pub logic_code_hash: String,
+}
+
+/// Initializer of an `EthereumSystem::upgrade`.
+#[derive(Debug, Serialize)]
+pub struct InitializerReport {
+    /// Payload passed to `initialize(bytes)`
+    pub params: String,
+    /// One line per value, when encoded from `--initializer-abi`
+    pub decoded: Vec<String>,
+    pub maximum_required_gas: u64,
+    /// Whether `maximum_required_gas` was estimated on Ethereum
+    pub estimated: bool,
+    /// Margin included in an estimated `maximum_required_gas`
+    pub gas_margin_percent: Option<u64>,
+}
+
+/// A call of the bundle emitted by `--submission`.
+#[derive(Debug, Serialize)]
+pub struct SubmissionCallReport {
+    /// Chain the call is signed and submitted on (`AssetHub`)
+    pub origin: String,
+    /// `Pallet.call_name`
+    pub call: String,
+    pub hash: String,
+    pub length: usize,
+    pub data: String,
+}
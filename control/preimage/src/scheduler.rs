@@ -0,0 +1,163 @@
+use super::asset_hub_runtime::runtime_types::pallet_scheduler;
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use crate::report::ScheduleReport;
+use crate::time::format_rfc3339;
+use crate::Context;
+use sp_crypto_hashing::blake2_256;
+
+/// Relay chain block time. AssetHub's scheduler counts relay chain blocks.
+const RELAY_BLOCK_TIME_MS: i64 = 6_000;
+
+/// When `--schedule-at` or `--schedule-after` should dispatch the call.
+#[derive(Copy, Clone, Debug)]
+pub enum ScheduleTime {
+    /// Unix timestamp in milliseconds
+    At(i64),
+    /// Milliseconds from now
+    After(u64),
+}
+
+/// Wrap `call` in `Scheduler.schedule` (or `schedule_named`, with the blake2_256 hash of
+/// `name` as id) for the relay chain block expected at `time`.
+///
+/// The block is extrapolated from AssetHub's latest timestamp and relay parent at 6s per
+/// block. Missed relay chain slots make blocks arrive later than that, so the estimate
+/// is reported with an uncertainty of 1% of the distance plus a block.
+pub async fn schedule(
+    context: &Context,
+    call: AssetHubRuntimeCall,
+    time: ScheduleTime,
+    name: Option<&str>,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    let api = context.asset_hub_api()?;
+    let storage = api.storage().at_latest().await?;
+    let now: u64 = storage
+        .fetch(&super::asset_hub_runtime::storage().timestamp().now())
+        .await?
+        .ok_or("AssetHub timestamp is not set")?;
+    let now = now as i64;
+    let current_block: u32 = storage
+        .fetch(
+            &super::asset_hub_runtime::storage()
+                .parachain_system()
+                .last_relay_chain_block_number(),
+        )
+        .await?
+        .ok_or("AssetHub relay parent block number is not set")?;
+
+    let target = match time {
+        ScheduleTime::At(target) => target,
+        ScheduleTime::After(delay) => now.saturating_add(delay as i64),
+    };
+    let (when, uncertainty_blocks) = schedule_block(now, current_block, target)?;
+
+    let id = name.map(|name| blake2_256(name.as_bytes()));
+    eprintln!(
+        "Scheduled for relay chain block {when} (±{uncertainty_blocks} blocks), expected at {}. \
+         Current relay chain block: {current_block} at {}",
+        format_rfc3339(target),
+        format_rfc3339(now)
+    );
+    context.report.borrow_mut().schedule = Some(ScheduleReport {
+        id: id.map(|id| format!("0x{}", hex::encode(id))),
+        when,
+        uncertainty_blocks,
+        target_time: format_rfc3339(target),
+        current_block,
+        current_time: format_rfc3339(now),
+    });
+
+    let call = Box::new(call);
+    let call = match id {
+        Some(id) => pallet_scheduler::pallet::Call::schedule_named {
+            id,
+            when,
+            maybe_periodic: None,
+            priority: 0,
+            call,
+        },
+        None => pallet_scheduler::pallet::Call::schedule {
+            when,
+            maybe_periodic: None,
+            priority: 0,
+            call,
+        },
+    };
+    Ok(AssetHubRuntimeCall::Scheduler(call))
+}
+
+/// The relay chain block expected at `target`, given the relay chain block `current_block`
+/// at time `now`, and the number of blocks it may be off by.
+fn schedule_block(now: i64, current_block: u32, target: i64) -> Result<(u32, u32), String> {
+    if target <= now {
+        return Err(format!(
+            "Schedule time {} is not after the current AssetHub time {}",
+            format_rfc3339(target),
+            format_rfc3339(now)
+        ));
+    }
+    // Rounded up, so the call is never dispatched before `target`
+    let blocks = u32::try_from(
+        (target - now)
+            .unsigned_abs()
+            .div_ceil(RELAY_BLOCK_TIME_MS as u64),
+    )
+    .map_err(|_| "Schedule time is too far in the future")?;
+    let when = current_block
+        .checked_add(blocks)
+        .ok_or("Schedule time is too far in the future")?;
+    Ok((when, blocks / 100 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_772_366_400_000;
+
+    #[test]
+    fn rounds_up_to_whole_blocks() {
+        assert_eq!(schedule_block(NOW, 1000, NOW + 1), Ok((1001, 1)));
+        assert_eq!(schedule_block(NOW, 1000, NOW + 6_000), Ok((1001, 1)));
+        assert_eq!(schedule_block(NOW, 1000, NOW + 6_001), Ok((1002, 1)));
+    }
+
+    #[test]
+    fn uncertainty_grows_with_distance() {
+        // 99 blocks
+        assert_eq!(schedule_block(NOW, 0, NOW + 594_000), Ok((99, 1)));
+        // 100 blocks
+        assert_eq!(schedule_block(NOW, 0, NOW + 600_000), Ok((100, 2)));
+        // 7 days
+        assert_eq!(
+            schedule_block(NOW, 25_000_000, NOW + 604_800_000),
+            Ok((25_100_800, 1009))
+        );
+    }
+
+    #[test]
+    fn rejects_past_times() {
+        assert_eq!(
+            schedule_block(NOW, 1000, NOW),
+            Err(
+                "Schedule time 2026-03-01T12:00:00Z is not after the current AssetHub \
+                 time 2026-03-01T12:00:00Z"
+                    .into()
+            )
+        );
+        assert!(schedule_block(NOW, 1000, NOW - 1).is_err());
+    }
+
+    #[test]
+    fn rejects_blocks_beyond_u32() {
+        let too_far = Err("Schedule time is too far in the future".into());
+        // More blocks than fit in a u32
+        assert_eq!(schedule_block(NOW, 0, i64::MAX), too_far);
+        // The block count fits, but not added to the current block
+        assert_eq!(schedule_block(NOW, u32::MAX, NOW + 6_000), too_far);
+        assert_eq!(
+            schedule_block(NOW, u32::MAX - 1, NOW + 6_000),
+            Ok((u32::MAX, 1))
+        );
+    }
+}
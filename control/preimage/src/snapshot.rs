@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Written by `halt-bridge --snapshot-out` and read by `resume-bridge` unless another file is
+/// given.
+pub const DEFAULT_PATH: &str = "halt-bridge-snapshot.json";
+
+/// AssetHub outbound fees captured before `halt-bridge` overwrites them with `u128::MAX`,
+/// so that `resume-bridge --snapshot` can restore them.
+///
+/// Fees are decimal strings in planck. A missing fee was not set at the snapshot block.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeSnapshot {
+    pub network: String,
+    /// Hash of the AssetHub block the fees were read at
+    pub block: String,
+    /// `BridgeHubEthereumBaseFee`
+    pub base_fee: Option<String>,
+    /// `BridgeHubEthereumBaseFeeV2`
+    pub base_fee_v2: Option<String>,
+}
+
+impl FeeSnapshot {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read snapshot {}: {e}", path.display()))?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    /// Check that the snapshot can be saved to `path`: it refuses to replace an existing file
+    /// unless `force` is set, or to save fees that are already halted, which `resume-bridge`
+    /// could not restore.
+    pub fn check(&self, path: &Path, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+        for (fee, name) in [
+            (&self.base_fee, "BridgeHubEthereumBaseFee"),
+            (&self.base_fee_v2, "BridgeHubEthereumBaseFeeV2"),
+        ] {
+            if fee.as_deref().and_then(|fee| fee.parse::<u128>().ok()) == Some(u128::MAX) {
+                return Err(format!(
+                    "{name} is already halted (u128::MAX) at AssetHub block {}, \
+                     not saving it to a snapshot",
+                    self.block
+                )
+                .into());
+            }
+        }
+        if path.exists() && !force {
+            return Err(format!(
+                "Snapshot {} already exists and may hold the fees from before an earlier halt. \
+                 Pass --force to overwrite it",
+                path.display()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Write the snapshot to `path`, after the same checks as `check`.
+    pub fn save(&self, path: &Path, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.check(path, force)?;
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| format!("Failed to write snapshot {}: {e}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn base_fee(&self) -> Result<u128, Box<dyn std::error::Error>> {
+        parse_fee(&self.base_fee, "BridgeHubEthereumBaseFee", &self.block)
+    }
+
+    pub fn base_fee_v2(&self) -> Result<u128, Box<dyn std::error::Error>> {
+        parse_fee(&self.base_fee_v2, "BridgeHubEthereumBaseFeeV2", &self.block)
+    }
+}
+
+fn parse_fee(
+    fee: &Option<String>,
+    name: &str,
+    block: &str,
+) -> Result<u128, Box<dyn std::error::Error>> {
+    let fee = fee
+        .as_ref()
+        .ok_or_else(|| format!("{name} was not set at AssetHub block {block}"))?;
+    let fee = fee
+        .parse::<u128>()
+        .map_err(|e| format!("Invalid {name} '{fee}': {e}"))?;
+    if fee == u128::MAX {
+        return Err(
+            format!("{name} was already halted (u128::MAX) at AssetHub block {block}").into(),
+        );
+    }
+    Ok(fee)
+}
@@ -0,0 +1,262 @@
+use super::asset_hub_runtime::runtime_types::{
+    frame_support::{
+        dispatch::RawOrigin,
+        traits::{preimages::Bounded, schedule::DispatchTime},
+    },
+    pallet_preimage, pallet_referenda, pallet_whitelist,
+};
+use super::asset_hub_runtime::{
+    GovernanceOrigin, OriginCaller, RuntimeCall as AssetHubRuntimeCall,
+};
+use super::helpers::{call_name, utility_batch_all};
+use crate::report::SubmissionCallReport;
+use crate::{Context, Submission, SubmissionArgs};
+use codec::Encode;
+use sp_crypto_hashing::blake2_256;
+use std::str::FromStr;
+use subxt::{utils::H256, OnlineClient, PolkadotConfig};
+use subxt_signer::{sr25519::Keypair, SecretUri};
+
+/// Calls required to take a preimage through a governance path, in submission order.
+pub struct SubmissionBundle {
+    /// Calls to be signed and submitted on AssetHub
+    pub calls: Vec<AssetHubRuntimeCall>,
+}
+
+/// The calls submitting `call` as referendum `referendum_index`.
+///
+/// Whitelisting the call on the `whitelisted-caller` track is left to the Fellowship, whose
+/// referendum on the Collectives chain cannot be built from the bundled metadata.
+pub fn build_bundle(
+    mode: Submission,
+    enactment_after: u32,
+    referendum_index: u32,
+    call: &AssetHubRuntimeCall,
+) -> SubmissionBundle {
+    let (origin, proposal) = match mode {
+        Submission::Root => (OriginCaller::system(RawOrigin::Root), call.clone()),
+        Submission::WhitelistedCaller => (
+            OriginCaller::Origins(GovernanceOrigin::WhitelistedCaller),
+            whitelisted_dispatch(call),
+        ),
+    };
+    let mut calls = referendum(origin, &proposal, enactment_after);
+    calls.push(AssetHubRuntimeCall::Referenda(
+        pallet_referenda::pallet::Call::place_decision_deposit {
+            index: referendum_index,
+        },
+    ));
+    SubmissionBundle { calls }
+}
+
+/// The index the next referendum submitted on AssetHub will get.
+///
+/// A referendum submitted by someone else before the bundle takes this index, so the
+/// bundle must be rebuilt if `Referenda.ReferendumCount` has moved on.
+pub async fn next_referendum_index(context: &Context) -> Result<u32, Box<dyn std::error::Error>> {
+    let index = context
+        .asset_hub_api()?
+        .storage()
+        .at_latest()
+        .await?
+        .fetch_or_default(
+            &super::asset_hub_runtime::storage()
+                .referenda()
+                .referendum_count(),
+        )
+        .await?;
+    Ok(index)
+}
+
+/// Note the preimage of `call` and submit a referendum for it on the track of `origin`.
+fn referendum(
+    origin: OriginCaller,
+    call: &AssetHubRuntimeCall,
+    enactment_after: u32,
+) -> Vec<AssetHubRuntimeCall> {
+    let encoded = call.encode();
+    let note_preimage =
+        AssetHubRuntimeCall::Preimage(pallet_preimage::pallet::Call::note_preimage {
+            bytes: encoded.clone(),
+        });
+    let submit = AssetHubRuntimeCall::Referenda(pallet_referenda::pallet::Call::submit {
+        proposal_origin: Box::new(origin),
+        proposal: Bounded::Lookup {
+            hash: H256(blake2_256(&encoded)),
+            len: encoded.len() as u32,
+        },
+        enactment_moment: DispatchTime::After(enactment_after),
+    });
+    vec![note_preimage, submit]
+}
+
+/// The proposal of a `WhitelistedCaller` referendum for `call`.
+pub fn whitelisted_dispatch(call: &AssetHubRuntimeCall) -> AssetHubRuntimeCall {
+    AssetHubRuntimeCall::Whitelist(
+        pallet_whitelist::pallet::Call::dispatch_whitelisted_call_with_preimage {
+            call: Box::new(call.clone()),
+        },
+    )
+}
+
+/// The calls of the bundle, followed by the `batch_all` of the AssetHub calls if there
+/// is more than one.
+pub fn bundle_report(bundle: &SubmissionBundle) -> Vec<SubmissionCallReport> {
+    let mut calls: Vec<SubmissionCallReport> = bundle
+        .calls
+        .iter()
+        .map(|call| call_report("AssetHub", call))
+        .collect();
+    if bundle.calls.len() > 1 {
+        calls.push(call_report(
+            "AssetHub",
+            &utility_batch_all(bundle.calls.clone()),
+        ));
+    }
+    calls
+}
+
+pub fn print_bundle(bundle: &SubmissionBundle) {
+    for (index, call) in bundle_report(bundle).iter().enumerate() {
+        println!(
+            "# {}. {}: {} (length: {}, hash: {})",
+            index + 1,
+            call.origin,
+            call.call,
+            call.length,
+            call.hash
+        );
+        println!("{}", call.data);
+    }
+}
+
+fn call_report(origin: &str, call: &AssetHubRuntimeCall) -> SubmissionCallReport {
+    let encoded = call.encode();
+    SubmissionCallReport {
+        origin: origin.to_string(),
+        call: call_name(call),
+        hash: format!("0x{}", hex::encode(blake2_256(&encoded))),
+        length: encoded.len(),
+        data: format!("0x{}", hex::encode(encoded)),
+    }
+}
+
+/// Sign and submit the AssetHub calls of the bundle as a single `batch_all` extrinsic.
+pub async fn submit_bundle(
+    params: &SubmissionArgs,
+    default_url: &str,
+    bundle: &SubmissionBundle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let suri = params
+        .signer
+        .as_ref()
+        .ok_or("A signer is required to submit the bundle")?;
+    let keypair = Keypair::from_uri(&SecretUri::from_str(suri)?)?;
+    let url = params.submit_api.as_deref().unwrap_or(default_url);
+    let api: OnlineClient<PolkadotConfig> = OnlineClient::from_url(url).await?;
+
+    let payload = super::asset_hub_runtime::tx()
+        .utility()
+        .batch_all(bundle.calls.clone());
+    let events = api
+        .tx()
+        .sign_and_submit_then_watch_default(&payload, &keypair)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    eprintln!(
+        "Submitted bundle in extrinsic 0x{}",
+        hex::encode(events.extrinsic_hash())
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::commands::system_frontend_operating_mode;
+    use super::*;
+    use crate::OperatingModeEnum;
+
+    fn call() -> AssetHubRuntimeCall {
+        system_frontend_operating_mode(&OperatingModeEnum::Halted)
+    }
+
+    /// Check the `note_preimage`, `submit` and `place_decision_deposit` calls of a bundle
+    /// proposing `proposal` on the track of `is_origin`.
+    fn check_bundle(
+        bundle: &SubmissionBundle,
+        proposal: &AssetHubRuntimeCall,
+        is_origin: fn(&OriginCaller) -> bool,
+    ) {
+        let encoded = proposal.encode();
+        let [note, submit, deposit] = &bundle.calls[..] else {
+            panic!("expected 3 calls, got {}", bundle.calls.len());
+        };
+        assert!(matches!(
+            note,
+            AssetHubRuntimeCall::Preimage(pallet_preimage::pallet::Call::note_preimage { bytes })
+                if *bytes == encoded
+        ));
+        let AssetHubRuntimeCall::Referenda(pallet_referenda::pallet::Call::submit {
+            proposal_origin,
+            proposal: Bounded::Lookup { hash, len },
+            enactment_moment: DispatchTime::After(10),
+        }) = submit
+        else {
+            panic!("expected Referenda.submit, got {submit:?}");
+        };
+        assert!(is_origin(proposal_origin));
+        assert_eq!(hash.0, blake2_256(&encoded));
+        assert_eq!(*len as usize, encoded.len());
+        assert!(matches!(
+            deposit,
+            AssetHubRuntimeCall::Referenda(
+                pallet_referenda::pallet::Call::place_decision_deposit { index: 42 }
+            )
+        ));
+    }
+
+    #[test]
+    fn root_bundle() {
+        let bundle = build_bundle(Submission::Root, 10, 42, &call());
+        check_bundle(&bundle, &call(), |origin| {
+            matches!(origin, OriginCaller::system(RawOrigin::Root))
+        });
+    }
+
+    #[test]
+    fn whitelisted_caller_bundle() {
+        let bundle = build_bundle(Submission::WhitelistedCaller, 10, 42, &call());
+        check_bundle(&bundle, &whitelisted_dispatch(&call()), |origin| {
+            matches!(
+                origin,
+                OriginCaller::Origins(GovernanceOrigin::WhitelistedCaller)
+            )
+        });
+    }
+
+    #[test]
+    fn report_batches_the_calls() {
+        let bundle = build_bundle(Submission::Root, 10, 42, &call());
+        let report = bundle_report(&bundle);
+        let names: Vec<&str> = report.iter().map(|call| call.call.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Preimage.note_preimage",
+                "Referenda.submit",
+                "Referenda.place_decision_deposit",
+                "Utility.batch_all"
+            ]
+        );
+        assert!(report.iter().all(|call| call.origin == "AssetHub"));
+        assert_eq!(
+            report[3].data,
+            format!(
+                "0x{}",
+                hex::encode(utility_batch_all(bundle.calls.clone()).encode())
+            )
+        );
+    }
+}
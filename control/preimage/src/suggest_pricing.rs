@@ -0,0 +1,380 @@
+//! Suggest `pricing-parameters` arguments from market prices and recent Ethereum gas fees.
+
+use crate::ethereum::EthereumClient;
+use crate::{Format, SuggestPricingArgs};
+use alloy_primitives::{utils::format_units, U256};
+use serde::Deserialize;
+use subxt::ext::subxt_rpcs::client::rpc_params;
+
+/// Prices in USD, as read from `--price-file`.
+///
+/// ```json
+/// { "eth_usd": "3215.40", "dot_usd": "4.12" }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PriceFile {
+    eth_usd: Price,
+    dot_usd: Price,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Price {
+    Number(serde_json::Number),
+    String(String),
+}
+
+impl Price {
+    fn into_string(self) -> String {
+        match self {
+            Price::Number(number) => number.to_string(),
+            Price::String(string) => string,
+        }
+    }
+}
+
+/// Response of `eth_feeHistory`. Quantities are hex encoded.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeeHistory {
+    oldest_block: String,
+    /// One entry per block, plus the base fee of the next block
+    base_fee_per_gas: Vec<String>,
+    /// Priority fee at each requested percentile, per block
+    #[serde(default)]
+    reward: Vec<Vec<String>>,
+}
+
+pub async fn suggest_pricing(
+    params: &SuggestPricingArgs,
+    ethereum_api: &str,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (eth_usd, dot_usd) = match &params.price_file {
+        Some(path) => {
+            let data = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read price file {}: {e}", path.display()))?;
+            let prices: PriceFile = serde_json::from_str(&data)
+                .map_err(|e| format!("Invalid price file {}: {e}", path.display()))?;
+            (prices.eth_usd.into_string(), prices.dot_usd.into_string())
+        }
+        None => (
+            params.eth_usd.clone().ok_or("--eth-usd is required")?,
+            params.dot_usd.clone().ok_or("--dot-usd is required")?,
+        ),
+    };
+    let eth_usd = parse_price(&eth_usd)?;
+    let dot_usd = parse_price(&dot_usd)?;
+
+    // ETH per DOT = (DOT/USD) / (ETH/USD)
+    let (exchange_rate_numerator, exchange_rate_denominator) = match (
+        dot_usd.0.checked_mul(eth_usd.1),
+        dot_usd.1.checked_mul(eth_usd.0),
+    ) {
+        (Some(numerator), Some(denominator)) => reduce(numerator, denominator)?,
+        _ => return Err("Prices have too many digits".into()),
+    };
+
+    let history = fee_history(ethereum_api, params).await?;
+    let (base_fee, priority_fee) = fees(&history, params.base_fee_percentile)?;
+    let fee_per_gas = base_fee + priority_fee;
+    let gwei = |fee: u128| trim_decimals(format_units(U256::from(fee), "gwei").unwrap());
+
+    let oldest_block = parse_quantity(&history.oldest_block)?;
+    eprintln!(
+        "ExchangeRate: {}/{} ETH/DOT (ETH at ${}, DOT at ${})",
+        exchange_rate_numerator,
+        exchange_rate_denominator,
+        format_price(eth_usd),
+        format_price(dot_usd),
+    );
+    eprintln!(
+        "FeePerGas: {} GWEI = base fee {} GWEI (p{} of blocks {}..={}) + priority fee {} GWEI \
+         (median of the per-block p{})",
+        gwei(fee_per_gas),
+        gwei(base_fee),
+        params.base_fee_percentile,
+        oldest_block,
+        oldest_block + history.base_fee_per_gas.len().saturating_sub(2) as u128,
+        gwei(priority_fee),
+        params.priority_fee_percentile,
+    );
+    eprintln!(
+        "Multiplier: {}/{}, rewards: {} DOT local, {} ETH remote (as given)",
+        params.multiplier_numerator,
+        params.multiplier_denominator,
+        params.local_reward,
+        params.remote_reward
+    );
+
+    match format {
+        // A `pricing-parameters` action for `from-manifest`
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "action": "pricing-parameters",
+                "exchange-rate-numerator": exchange_rate_numerator,
+                "exchange-rate-denominator": exchange_rate_denominator,
+                "multiplier-numerator": params.multiplier_numerator,
+                "multiplier-denominator": params.multiplier_denominator,
+                "fee-per-gas": gwei(fee_per_gas),
+                "local-reward": params.local_reward,
+                "remote-reward": params.remote_reward,
+            }))?
+        ),
+        Format::Hex | Format::Binary => println!(
+            "--exchange-rate-numerator {} --exchange-rate-denominator {} \
+             --multiplier-numerator {} --multiplier-denominator {} --fee-per-gas {} \
+             --local-reward {} --remote-reward {}",
+            exchange_rate_numerator,
+            exchange_rate_denominator,
+            params.multiplier_numerator,
+            params.multiplier_denominator,
+            gwei(fee_per_gas),
+            params.local_reward,
+            params.remote_reward
+        ),
+    }
+    Ok(())
+}
+
+async fn fee_history(
+    ethereum_api: &str,
+    params: &SuggestPricingArgs,
+) -> Result<FeeHistory, Box<dyn std::error::Error>> {
+    let client = EthereumClient::connect(ethereum_api).await?;
+    client
+        .request(
+            "eth_feeHistory",
+            rpc_params![
+                format!("{:#x}", params.blocks),
+                "latest",
+                [params.priority_fee_percentile]
+            ],
+        )
+        .await
+}
+
+/// The base fee at `base_fee_percentile` of the blocks in `history`, and the median of their
+/// priority fees.
+fn fees(history: &FeeHistory, base_fee_percentile: f64) -> Result<(u128, u128), String> {
+    let mut base_fees = history
+        .base_fee_per_gas
+        .iter()
+        .map(|fee| parse_quantity(fee))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut priority_fees = history
+        .reward
+        .iter()
+        .filter_map(|rewards| rewards.first())
+        .map(|fee| parse_quantity(fee))
+        .collect::<Result<Vec<_>, _>>()?;
+    let base_fee = percentile(&mut base_fees, base_fee_percentile)
+        .ok_or("eth_feeHistory returned no base fees")?;
+    // Blocks without transactions report a priority fee of zero
+    let priority_fee = percentile(&mut priority_fees, 50.0).unwrap_or(0);
+    Ok((base_fee, priority_fee))
+}
+
+/// A decimal price such as `3215.40` as a fraction with a power of ten denominator.
+fn parse_price(price: &str) -> Result<(u128, u128), String> {
+    let invalid = || format!("Invalid price '{price}', expected a decimal such as 3215.40");
+    let (whole, fraction) = price.split_once('.').unwrap_or((price, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > 18
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let numerator: u128 = format!("{whole}{fraction}")
+        .parse()
+        .map_err(|_| invalid())?;
+    if numerator == 0 {
+        return Err(format!("Price '{price}' must not be zero"));
+    }
+    Ok((numerator, 10u128.pow(fraction.len() as u32)))
+}
+
+fn format_price((numerator, denominator): (u128, u128)) -> String {
+    let decimals = denominator.ilog10() as u8;
+    trim_decimals(format_units(U256::from(numerator), decimals).unwrap())
+}
+
+/// Reduce a fraction to lowest terms. If it does not fit in `u64`, precision is dropped
+/// from both terms until it does.
+fn reduce(numerator: u128, denominator: u128) -> Result<(u64, u64), String> {
+    let (mut numerator, mut denominator) = (numerator, denominator);
+    loop {
+        let divisor = gcd(numerator, denominator);
+        numerator /= divisor;
+        denominator /= divisor;
+        if let (Ok(n), Ok(d)) = (u64::try_from(numerator), u64::try_from(denominator)) {
+            return Ok((n, d));
+        }
+        let round = |value: u128| value / 10 + u128::from(value % 10 >= 5);
+        numerator = round(numerator);
+        denominator = round(denominator);
+        if numerator == 0 || denominator == 0 {
+            return Err("Exchange rate is out of range".into());
+        }
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The value at `percentile` (0-100) of `values`, using the nearest-rank method.
+fn percentile(values: &mut [u128], percentile: f64) -> Option<u128> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = (percentile / 100.0 * values.len() as f64).ceil() as usize;
+    Some(values[rank.clamp(1, values.len()) - 1])
+}
+
+fn parse_quantity(value: &str) -> Result<u128, String> {
+    u128::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid quantity '{value}' in eth_feeHistory response: {e}"))
+}
+
+fn trim_decimals(value: String) -> String {
+    match value.contains('.') {
+        true => value
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        false => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// `eth_feeHistory` of a fresh anvil node after two blocks, for 3 blocks at the 50th
+    /// percentile.
+    const ANVIL_FEE_HISTORY: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"oldestBlock":"0x0","baseFeePerGas":["0x3b9aca00","0x342770c0","0x2da282a8","0x27ee3253"],"gasUsedRatio":[0.0,0.0,0.0],"baseFeePerBlobGas":["0x1","0x1","0x1","0x1"],"blobGasUsedRatio":[0.0,0.0,0.0],"reward":[["0x0"],["0x0"],["0x0"]]}}"#;
+
+    fn args(blocks: u64) -> SuggestPricingArgs {
+        SuggestPricingArgs {
+            eth_usd: Some("3215.40".into()),
+            dot_usd: Some("4.12".into()),
+            price_file: None,
+            blocks,
+            base_fee_percentile: 75.0,
+            priority_fee_percentile: 50.0,
+            multiplier_numerator: 1,
+            multiplier_denominator: 1,
+            local_reward: "0.1".into(),
+            remote_reward: "0.001".into(),
+        }
+    }
+
+    /// Serve `response` to a single JSON-RPC request over HTTP, returning the URL and the
+    /// request body.
+    fn serve(response: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buffer = [0u8; 1024];
+            let body = loop {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap();
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                }
+            };
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+            body
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn parse_prices() {
+        assert_eq!(parse_price("3215.40").unwrap(), (321540, 100));
+        assert_eq!(parse_price("4").unwrap(), (4, 1));
+        assert_eq!(parse_price(".5").unwrap(), (5, 10));
+        assert_eq!(
+            parse_price("0.000000000000000001").unwrap(),
+            (1, 10u128.pow(18))
+        );
+        for invalid in ["", ".", "1,5", "-1", "1e3", "0.0000000000000000001"] {
+            assert!(parse_price(invalid).is_err(), "{invalid}");
+        }
+        assert_eq!(
+            parse_price("0.00").unwrap_err(),
+            "Price '0.00' must not be zero"
+        );
+    }
+
+    #[test]
+    fn reduce_fractions() {
+        assert_eq!(reduce(412 * 100, 100 * 321540).unwrap(), (103, 80385));
+        assert_eq!(reduce(7, 7).unwrap(), (1, 1));
+        // (2^64 + 1) / 30 does not fit and loses its least significant digit
+        assert_eq!(
+            reduce(u64::MAX as u128 + 2, 30).unwrap(),
+            (1844674407370955162, 3)
+        );
+        assert_eq!(
+            reduce(u128::MAX, 1).unwrap_err(),
+            "Exchange rate is out of range"
+        );
+    }
+
+    #[test]
+    fn percentiles() {
+        assert_eq!(percentile(&mut [], 50.0), None);
+        let mut values = [5, 1, 4, 2, 3];
+        assert_eq!(percentile(&mut values, 0.0), Some(1));
+        assert_eq!(percentile(&mut values, 50.0), Some(3));
+        assert_eq!(percentile(&mut values, 75.0), Some(4));
+        assert_eq!(percentile(&mut values, 100.0), Some(5));
+    }
+
+    #[tokio::test]
+    async fn anvil_fee_history() {
+        let (url, request) = serve(ANVIL_FEE_HISTORY);
+        let history = fee_history(&url, &args(3)).await.unwrap();
+        let request: serde_json::Value = serde_json::from_str(&request.join().unwrap()).unwrap();
+        assert_eq!(request["method"], "eth_feeHistory");
+        assert_eq!(
+            request["params"],
+            serde_json::json!(["0x3", "latest", [50.0]])
+        );
+
+        assert_eq!(parse_quantity(&history.oldest_block).unwrap(), 0);
+        // p75 of 1, 0.875, 0.765625 and 0.669921875 GWEI, with no priority fees
+        assert_eq!(fees(&history, 75.0).unwrap(), (875_000_000, 0));
+    }
+}
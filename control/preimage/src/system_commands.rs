@@ -0,0 +1,227 @@
+use super::asset_hub_runtime::runtime_types::{
+    snowbridge_pallet_system_frontend,
+    staging_xcm::v5::{
+        asset::{Asset, AssetId, Fungibility},
+        location::Location as AssetHubLocation,
+    },
+    xcm::VersionedLocation as AssetHubVersionedLocation,
+};
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::bridge_hub_runtime::runtime_types::{
+    bounded_collections::bounded_vec::BoundedVec, primitive_types, snowbridge_core::AssetMetadata,
+    snowbridge_pallet_system, xcm::VersionedLocation,
+};
+use super::bridge_hub_runtime::RuntimeCall as BridgeHubRuntimeCall;
+use super::commands::asset_hub_types::get_ether_id;
+use super::decode::format_polkadot_amount;
+use super::helpers::parse_units_polkadot;
+use super::registrations::bridge_hub_location;
+use crate::{RegisterTokenArgs, TokenTransferFeesArgs};
+use alloy_primitives::utils::format_units;
+use codec::{Decode, Encode};
+use snowbridge_token_list::{MAX_DECIMALS, PNA_METADATA_MAX_LEN};
+
+/// Every call of `EthereumSystem` and `EthereumSystemV2`, with the command that builds it or
+/// why it has none. A test checks this against the bundled BridgeHub metadata, so a call
+/// added to either pallet must be covered or listed here.
+pub const SYSTEM_CALLS: &[(&str, Coverage)] = &[
+    ("EthereumSystem.upgrade", Coverage::Command("upgrade")),
+    (
+        "EthereumSystem.set_operating_mode",
+        Coverage::Command("gateway-operating-mode"),
+    ),
+    (
+        "EthereumSystem.set_pricing_parameters",
+        Coverage::Command("pricing-parameters"),
+    ),
+    (
+        "EthereumSystem.set_token_transfer_fees",
+        Coverage::Command("token-transfer-fees"),
+    ),
+    (
+        "EthereumSystem.register_token",
+        Coverage::Command("register-token"),
+    ),
+    (
+        "EthereumSystemV2.upgrade",
+        Coverage::Command("upgrade --v2"),
+    ),
+    (
+        "EthereumSystemV2.set_operating_mode",
+        Coverage::Command("gateway-operating-mode --v2"),
+    ),
+    (
+        "EthereumSystemV2.register_token",
+        Coverage::Omitted(
+            "dispatched by SnowbridgeSystemFrontend on AssetHub, not by governance: see \
+             register-token --v2",
+        ),
+    ),
+    (
+        "EthereumSystemV2.add_tip",
+        Coverage::Omitted(
+            "dispatched by SnowbridgeSystemFrontend on AssetHub for a user's tip, not by \
+             governance",
+        ),
+    ),
+];
+
+/// How a call in [`SYSTEM_CALLS`] is covered.
+#[derive(Debug, Clone, Copy)]
+pub enum Coverage {
+    /// The subcommand that builds the call
+    Command(&'static str),
+    /// Why the call has no subcommand
+    Omitted(&'static str),
+}
+
+/// Point at the subcommand that builds the system call `name` (`Pallet.call`) with typed
+/// arguments, or warn that governance does not dispatch it.
+pub fn check_dynamic_call(name: &str) {
+    match SYSTEM_CALLS.iter().find(|(call, _)| *call == name) {
+        Some((_, Coverage::Command(command))) => {
+            eprintln!("Note: the `{command}` subcommand builds {name} with typed arguments")
+        }
+        Some((_, Coverage::Omitted(reason))) => eprintln!("Warning: {name} is {reason}"),
+        None => {}
+    }
+}
+
+/// `EthereumSystem::set_token_transfer_fees`, which the pallet rejects if any fee is zero.
+pub fn set_token_transfer_fees(
+    params: &TokenTransferFeesArgs,
+) -> Result<BridgeHubRuntimeCall, Box<dyn std::error::Error>> {
+    let create_asset_xcm = parse_units_polkadot(&params.create_asset_xcm)?;
+    let transfer_asset_xcm = parse_units_polkadot(&params.transfer_asset_xcm)?;
+    if create_asset_xcm == 0 || transfer_asset_xcm == 0 || params.register_token.is_zero() {
+        return Err("Token transfer fees must not be zero".into());
+    }
+    eprintln!(
+        "create_asset_xcm: {}",
+        format_polkadot_amount(create_asset_xcm)
+    );
+    eprintln!(
+        "transfer_asset_xcm: {}",
+        format_polkadot_amount(transfer_asset_xcm)
+    );
+    eprintln!(
+        "register_token: {} ETH [{} WEI]",
+        format_units(params.register_token, "ether").unwrap(),
+        params.register_token
+    );
+    Ok(BridgeHubRuntimeCall::EthereumSystem(
+        snowbridge_pallet_system::pallet::Call::set_token_transfer_fees {
+            create_asset_xcm,
+            transfer_asset_xcm,
+            register_token: primitive_types::U256(params.register_token.into_limbs()),
+        },
+    ))
+}
+
+/// Register a Polkadot native asset for export to Ethereum with
+/// `EthereumSystem::register_token` on BridgeHub.
+pub fn register_token(
+    params: &RegisterTokenArgs,
+) -> Result<BridgeHubRuntimeCall, Box<dyn std::error::Error>> {
+    check_metadata(params)?;
+    if params.fee.is_some() {
+        return Err("A fee is only paid when registering through the V2 frontend".into());
+    }
+    Ok(BridgeHubRuntimeCall::EthereumSystem(
+        snowbridge_pallet_system::pallet::Call::register_token {
+            location: Box::new(VersionedLocation::V5(bridge_hub_location(
+                &params.location,
+            )?)),
+            metadata: AssetMetadata {
+                name: BoundedVec(params.name.as_bytes().to_vec()),
+                symbol: BoundedVec(params.symbol.as_bytes().to_vec()),
+                decimals: params.decimals,
+            },
+        },
+    ))
+}
+
+/// Register a Polkadot native asset through `SnowbridgeSystemFrontend::register_token` on
+/// AssetHub, which forwards it to `EthereumSystemV2` on BridgeHub and pays `--fee` in Ether.
+pub fn register_token_v2(
+    params: &RegisterTokenArgs,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    use super::asset_hub_runtime::runtime_types::{
+        bounded_collections::bounded_vec::BoundedVec, snowbridge_core::AssetMetadata,
+    };
+
+    check_metadata(params)?;
+    let fee = params
+        .fee
+        .ok_or("Registering through the V2 frontend requires a fee in ETH")?;
+    eprintln!(
+        "fee: {} ETH [{fee} WEI]",
+        format_units(fee, "ether").unwrap()
+    );
+    let fee = u128::try_from(fee).map_err(|_| "--fee does not fit in a u128 balance")?;
+    // The bindings of both chains are generated from the same XCM types
+    let location = bridge_hub_location(&params.location)?.encode();
+    let location = AssetHubLocation::decode(&mut &location[..])?;
+    Ok(AssetHubRuntimeCall::SnowbridgeSystemFrontend(
+        snowbridge_pallet_system_frontend::pallet::Call::register_token {
+            asset_id: Box::new(AssetHubVersionedLocation::V5(location)),
+            metadata: AssetMetadata {
+                name: BoundedVec(params.name.as_bytes().to_vec()),
+                symbol: BoundedVec(params.symbol.as_bytes().to_vec()),
+                decimals: params.decimals,
+            },
+            fee_asset: Asset {
+                id: AssetId(get_ether_id(super::bridge_hub_runtime::CHAIN_ID)),
+                fun: Fungibility::Fungible(fee),
+            },
+        },
+    ))
+}
+
+fn check_metadata(params: &RegisterTokenArgs) -> Result<(), String> {
+    for (field, value) in [("name", &params.name), ("symbol", &params.symbol)] {
+        if value.is_empty() || value.len() > PNA_METADATA_MAX_LEN {
+            return Err(format!(
+                "--{field} must be between 1 and {PNA_METADATA_MAX_LEN} bytes"
+            ));
+        }
+    }
+    if params.decimals > MAX_DECIMALS {
+        return Err(format!("--decimals must not exceed {MAX_DECIMALS}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::Metadata;
+
+    #[test]
+    fn covers_every_system_call() {
+        let metadata =
+            Metadata::decode(&mut &super::super::bridge_hub_runtime::METADATA[..]).unwrap();
+        for pallet in ["EthereumSystem", "EthereumSystemV2"] {
+            let Some(pallet) = metadata.pallet_by_name(pallet) else {
+                continue;
+            };
+            let calls: Vec<String> = pallet
+                .call_variants()
+                .unwrap_or_default()
+                .iter()
+                .map(|variant| format!("{}.{}", pallet.name(), variant.name))
+                .collect();
+            for call in calls.iter() {
+                assert!(
+                    SYSTEM_CALLS.iter().any(|(name, _)| name == call),
+                    "{call} is neither covered nor listed as omitted"
+                );
+            }
+            for (name, _) in SYSTEM_CALLS {
+                if name.split('.').next() == Some(pallet.name()) {
+                    assert!(calls.contains(&name.to_string()), "{name} does not exist");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Minimal UTC date and time handling for schedules, in milliseconds since the Unix epoch.
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Days since 1970-01-01 of a proleptic Gregorian date,
+/// see http://howardhinnant.github.io/date_algorithms.html
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Parse the numeric fields of `value` separated by `separator`, each within its
+/// inclusive `(min, max)` limits.
+fn fields<const N: usize>(
+    value: &str,
+    separator: char,
+    limits: [(i64, i64); N],
+) -> Option<[i64; N]> {
+    let mut parts = value.split(separator);
+    let mut result = [0; N];
+    for (field, (min, max)) in result.iter_mut().zip(limits) {
+        let part = parts.next()?;
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *field = part.parse().ok()?;
+        if !(min..=max).contains(field) {
+            return None;
+        }
+    }
+    parts.next().is_none().then_some(result)
+}
+
+/// Days since 1970-01-01 of a `YYYY-MM-DD` date.
+pub fn parse_date(date: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid date '{date}', expected YYYY-MM-DD");
+    let [year, month, day] =
+        fields(date, '-', [(1, 9999), (1, 12), (1, 31)]).ok_or_else(invalid)?;
+    let days = days_from_civil(year, month, day);
+    // Days past the end of the month, e.g. 2026-02-29, would roll over into the next
+    if civil_from_days(days) != (year, month, day) {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+/// Parse an RFC 3339 timestamp such as `2026-03-01T12:00:00Z` or
+/// `2026-03-01T14:00:00+02:00`.
+pub fn parse_rfc3339(value: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid time '{value}', expected e.g. 2026-03-01T12:00:00Z");
+    let (date, time) = value.split_once(['T', 't', ' ']).ok_or_else(invalid)?;
+    let days = parse_date(date).map_err(|_| invalid())?;
+
+    let (time, offset_ms) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let split = time.rfind(['+', '-']).ok_or_else(invalid)?;
+        let (time, offset) = time.split_at(split);
+        let [hours, minutes] = fields(&offset[1..], ':', [(0, 23), (0, 59)]).ok_or_else(invalid)?;
+        let offset_ms = (hours * 60 + minutes) * 60_000;
+        match offset.starts_with('-') {
+            true => (time, -offset_ms),
+            false => (time, offset_ms),
+        }
+    };
+
+    // Fractional seconds are truncated to milliseconds
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let millis: i64 = format!("{fraction:0<3}")[..3].parse().unwrap_or(0);
+    let [hours, minutes, seconds] =
+        fields(time, ':', [(0, 23), (0, 59), (0, 60)]).ok_or_else(invalid)?;
+
+    Ok(days * MS_PER_DAY + ((hours * 60 + minutes) * 60 + seconds) * 1000 + millis - offset_ms)
+}
+
+/// Format a Unix timestamp in milliseconds as RFC 3339 in UTC.
+pub fn format_rfc3339(timestamp_ms: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp_ms.div_euclid(MS_PER_DAY));
+    let seconds = timestamp_ms.rem_euclid(MS_PER_DAY) / 1000;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Parse a duration such as `90s`, `45m`, `36h` or `7d` into milliseconds.
+pub fn parse_duration(value: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid duration '{value}', expected e.g. 45m, 36h or 7d");
+    let (amount, unit_ms) = [
+        ("s", 1_000),
+        ("m", 60_000),
+        ("h", 3_600_000),
+        ("d", 86_400_000),
+    ]
+    .into_iter()
+    .find_map(|(unit, unit_ms)| Some((value.strip_suffix(unit)?, unit_ms)))
+    .ok_or_else(invalid)?;
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    amount.checked_mul(unit_ms).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_days_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        for days in [-719468, -1, 0, 11016, 11017, 20513, 2932896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn leap_days() {
+        assert_eq!(
+            parse_date("2024-02-29"),
+            Ok(days_from_civil(2024, 3, 1) - 1)
+        );
+        assert_eq!(
+            parse_date("2000-02-29"),
+            Ok(days_from_civil(2000, 3, 1) - 1)
+        );
+        for date in [
+            "2026-02-29",
+            "1900-02-29",
+            "2100-02-29",
+            "2024-02-30",
+            "2026-04-31",
+        ] {
+            assert_eq!(
+                parse_date(date),
+                Err(format!("Invalid date '{date}', expected YYYY-MM-DD"))
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_dates() {
+        for date in [
+            "2026-3-1x",
+            "2026-13-01",
+            "2026-00-10",
+            "2026-03",
+            "2026-03-01-01",
+            "+2026-03-01",
+            "",
+        ] {
+            assert!(parse_date(date).is_err(), "{date}");
+        }
+    }
+
+    #[test]
+    fn rfc3339_offsets() {
+        let noon = parse_rfc3339("2026-03-01T12:00:00Z").unwrap();
+        assert_eq!(noon, 1772366400000);
+        assert_eq!(parse_rfc3339("2026-03-01T14:00:00+02:00"), Ok(noon));
+        assert_eq!(parse_rfc3339("2026-03-01t12:00:00z"), Ok(noon));
+        assert_eq!(parse_rfc3339("2026-03-01 12:00:00Z"), Ok(noon));
+        // A negative offset moves the instant later, across midnight and into a new month
+        assert_eq!(parse_rfc3339("2026-02-28T19:30:00-16:30"), Ok(noon));
+        assert_eq!(format_rfc3339(noon), "2026-03-01T12:00:00Z");
+    }
+
+    #[test]
+    fn rfc3339_fractions() {
+        let noon = parse_rfc3339("2026-03-01T12:00:00Z").unwrap();
+        assert_eq!(parse_rfc3339("2026-03-01T12:00:00.5Z"), Ok(noon + 500));
+        assert_eq!(
+            parse_rfc3339("2026-03-01T12:00:00.123456789Z"),
+            Ok(noon + 123)
+        );
+        assert_eq!(format_rfc3339(noon + 999), "2026-03-01T12:00:00Z");
+        assert_eq!(format_rfc3339(-1), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn invalid_rfc3339() {
+        for value in [
+            "2026-03-01",
+            "2026-03-01T12:00:00",
+            "2026-03-01T12:00Z",
+            "2026-03-01T24:00:00Z",
+            "2026-03-01T12:00:00+2:00:00",
+            "2026-03-01T12:00:00+24:00",
+            "2026-03-01T12:00:00.5xZ",
+            "2026-02-29T12:00:00Z",
+        ] {
+            assert_eq!(
+                parse_rfc3339(value),
+                Err(format!(
+                    "Invalid time '{value}', expected e.g. 2026-03-01T12:00:00Z"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90s"), Ok(90_000));
+        assert_eq!(parse_duration("45m"), Ok(2_700_000));
+        assert_eq!(parse_duration("36h"), Ok(129_600_000));
+        assert_eq!(parse_duration("7d"), Ok(604_800_000));
+        assert_eq!(parse_duration("0d"), Ok(0));
+        let max_days = u64::MAX / 86_400_000;
+        assert_eq!(
+            parse_duration(&format!("{max_days}d")),
+            Ok(max_days * 86_400_000)
+        );
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for value in [
+            "7".to_string(),
+            "d".to_string(),
+            "-1h".to_string(),
+            "1.5h".to_string(),
+            "7w".to_string(),
+            format!("{}d", u64::MAX / 86_400_000 + 1),
+            format!("{}0s", u64::MAX),
+        ] {
+            assert_eq!(
+                parse_duration(&value),
+                Err(format!(
+                    "Invalid duration '{value}', expected e.g. 45m, 36h or 7d"
+                ))
+            );
+        }
+    }
+}
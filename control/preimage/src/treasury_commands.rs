@@ -1,6 +1,8 @@
-use crate::TreasuryProposal2024Args;
+use crate::time::{parse_date, parse_duration};
+use crate::treasury_schedule::{load_schedule, ScheduleEntry};
+use crate::{TreasuryProposal2024Args, TreasuryProposalArgs};
 
-use crate::asset_hub_runtime::runtime_types::{
+use super::asset_hub_runtime::runtime_types::{
     pallet_treasury,
     parachains_common::pay::VersionedLocatableAccount,
     polkadot_runtime_common::impls::VersionedLocatableAsset,
@@ -8,10 +10,16 @@ use crate::asset_hub_runtime::runtime_types::{
         asset::AssetId, junction::Junction, junctions::Junctions, location::Location,
     },
 };
-use crate::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
-use crate::helpers::utility_batch_all;
+use super::asset_hub_runtime::RuntimeCall as AssetHubRuntimeCall;
+use super::constants::{POLKADOT_DECIMALS, POLKADOT_SYMBOL, TREASURY_STABLECOINS};
+use super::helpers::utility_batch_all;
+use alloy_primitives::{
+    utils::{format_units, parse_units},
+    U256,
+};
 use polkadot_runtime_constants::currency::UNITS;
-use polkadot_runtime_constants::time::DAYS;
+use polkadot_runtime_constants::time::{DAYS, MILLISECS_PER_BLOCK};
+use std::collections::BTreeMap;
 
 // USDC has 6 decimal places
 pub const USDC_UNITS: u128 = 1_000_000;
@@ -192,7 +200,159 @@ pub fn treasury_proposal(params: &TreasuryProposal2024Args) -> AssetHubRuntimeCa
     utility_batch_all(calls)
 }
 
-fn make_treasury_spend(
+/// Build a `batch_all` of treasury spends from the schedule file of `treasury-proposal`.
+pub fn treasury_proposal_from_schedule(
+    params: &TreasuryProposalArgs,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    let schedule = load_schedule(&params.schedule)?;
+    let start_date = params.start_date.as_deref().map(parse_date).transpose()?;
+
+    let mut calls: Vec<AssetHubRuntimeCall> = vec![];
+    // Symbol => (decimals, total)
+    let mut totals: BTreeMap<String, (u8, u128)> = BTreeMap::new();
+
+    for (index, spend) in schedule.spends.iter().enumerate() {
+        let context = |e: String| format!("Spend #{index} ({}): {e}", spend.name);
+        let (symbol, location, decimals) = schedule_asset(spend).map_err(context)?;
+        let amount = parse_units(&spend.amount, decimals)
+            .map_err(|e| context(format!("invalid amount '{}': {e}", spend.amount)))?;
+        let amount: U256 = amount.into();
+        let amount: u128 = amount
+            .try_into()
+            .map_err(|_| context(format!("amount '{}' is too large", spend.amount)))?;
+        let valid_from =
+            schedule_valid_from(spend, params.start_block, start_date).map_err(context)?;
+
+        calls.push(make_treasury_spend(
+            params.beneficiary.into(),
+            location,
+            amount,
+            valid_from,
+        ));
+        eprintln!(
+            "Spend: {}, {} {symbol}, valid from: {}",
+            spend.name,
+            format_units(U256::from(amount), decimals)?,
+            valid_from.map_or("now".to_string(), |block| format!("block {block}"))
+        );
+
+        let total = totals.entry(symbol).or_insert((decimals, 0));
+        total.1 = total
+            .1
+            .checked_add(amount)
+            .ok_or_else(|| context("total overflows u128".into()))?;
+    }
+
+    for (symbol, (decimals, total)) in totals {
+        eprintln!(
+            "Total: {} {symbol} [{total}]",
+            format_units(U256::from(total), decimals)?
+        );
+    }
+
+    Ok(utility_batch_all(calls))
+}
+
+/// Symbol, AssetHub location and decimals of the asset of a schedule entry.
+fn schedule_asset(spend: &ScheduleEntry) -> Result<(String, Location, u8), String> {
+    let asset_hub_asset = |index: u128| Location {
+        parents: 0,
+        interior: Junctions::X2([Junction::PalletInstance(50), Junction::GeneralIndex(index)]),
+    };
+    let stablecoin = TREASURY_STABLECOINS
+        .iter()
+        .find(|(symbol, ..)| *symbol == spend.asset);
+    let (symbol, location, decimals) = match (spend.asset.as_str(), stablecoin) {
+        (symbol, _) if symbol == POLKADOT_SYMBOL => (
+            symbol.to_string(),
+            Location {
+                parents: 1,
+                interior: Junctions::Here,
+            },
+            POLKADOT_DECIMALS,
+        ),
+        (symbol, Some((_, index, decimals))) => {
+            (symbol.to_string(), asset_hub_asset(*index), *decimals)
+        }
+        (location, None) => {
+            let decimals = spend
+                .decimals
+                .ok_or("decimals are required for an asset given by location")?;
+            (location.to_string(), parse_location(location)?, decimals)
+        }
+    };
+    if spend.decimals.is_some_and(|d| d != decimals) {
+        return Err(format!("{symbol} has {decimals} decimals"));
+    }
+    Ok((symbol, location, decimals))
+}
+
+/// Parse a location relative to AssetHub, e.g. `PalletInstance(50)/GeneralIndex(1984)` or
+/// `../Parachain(2034)/GeneralIndex(0)`.
+pub fn parse_location(location: &str) -> Result<Location, String> {
+    let invalid = || format!("Invalid asset location '{location}'");
+    let mut parents = 0u8;
+    let mut junctions = vec![];
+    for segment in location.split('/').map(str::trim) {
+        if segment == ".." && junctions.is_empty() {
+            parents += 1;
+            continue;
+        }
+        let (kind, value) = segment
+            .strip_suffix(')')
+            .and_then(|segment| segment.split_once('('))
+            .ok_or_else(invalid)?;
+        let junction = match kind {
+            "Parachain" => Junction::Parachain(value.parse().map_err(|_| invalid())?),
+            "PalletInstance" => Junction::PalletInstance(value.parse().map_err(|_| invalid())?),
+            "GeneralIndex" => Junction::GeneralIndex(value.parse().map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        };
+        junctions.push(junction);
+    }
+    let interior = match junctions.len() {
+        0 => Junctions::Here,
+        1 => Junctions::X1(junctions.try_into().unwrap()),
+        2 => Junctions::X2(junctions.try_into().unwrap()),
+        3 => Junctions::X3(junctions.try_into().unwrap()),
+        4 => Junctions::X4(junctions.try_into().unwrap()),
+        _ => return Err(invalid()),
+    };
+    Ok(Location { parents, interior })
+}
+
+/// The relay chain block from which a spend is valid, or `None` if it is valid immediately.
+fn schedule_valid_from(
+    spend: &ScheduleEntry,
+    start_block: u32,
+    start_date: Option<i64>,
+) -> Result<Option<u32>, String> {
+    let offset = match (&spend.delay, &spend.date) {
+        (Some(_), Some(_)) => return Err("only one of delay and date may be given".into()),
+        (None, None) => return Ok(None),
+        (Some(delay), None) => {
+            // Rounded up, so the spend never becomes valid early
+            let blocks = parse_duration(delay)?.div_ceil(MILLISECS_PER_BLOCK);
+            u32::try_from(blocks).ok()
+        }
+        (None, Some(date)) => {
+            let start_date = start_date.ok_or("--start-date is required for dated spends")?;
+            let days = parse_date(date)? - start_date;
+            if days < 0 {
+                return Err(format!("{date} is before --start-date"));
+            }
+            u32::try_from(days)
+                .ok()
+                .and_then(|days| days.checked_mul(DAYS))
+        }
+    };
+    offset
+        .and_then(|offset| start_block.checked_add(offset))
+        .map(Some)
+        .ok_or_else(|| "valid-from block overflows u32".into())
+}
+
+pub(crate) fn make_treasury_spend(
     beneficiary: [u8; 32],
     asset: Location,
     amount: u128,
@@ -225,3 +385,188 @@ fn make_treasury_spend(
 
     call
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polkadot_runtime_constants::time::HOURS;
+
+    #[test]
+    fn parse_local_and_sibling_locations() {
+        assert!(matches!(
+            parse_location("PalletInstance(50)/GeneralIndex(1984)").unwrap(),
+            Location {
+                parents: 0,
+                interior: Junctions::X2([
+                    Junction::PalletInstance(50),
+                    Junction::GeneralIndex(1984)
+                ]),
+            }
+        ));
+        assert!(matches!(
+            parse_location("../Parachain(2034) / GeneralIndex(0)").unwrap(),
+            Location {
+                parents: 1,
+                interior: Junctions::X2([Junction::Parachain(2034), Junction::GeneralIndex(0)]),
+            }
+        ));
+        assert!(matches!(
+            parse_location("../..").unwrap(),
+            Location {
+                parents: 2,
+                interior: Junctions::Here,
+            }
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_locations() {
+        for location in [
+            "PalletInstance(50)/../GeneralIndex(1)",
+            "PalletInstance(256)",
+            "Parachain(-1)",
+            "GeneralKey(1)",
+            "Parachain 1000",
+            "Parachain(1)/Parachain(2)/Parachain(3)/Parachain(4)/Parachain(5)",
+        ] {
+            assert_eq!(
+                parse_location(location).unwrap_err(),
+                format!("Invalid asset location '{location}'")
+            );
+        }
+    }
+
+    fn asset(asset: &str, decimals: Option<u8>) -> Result<(String, Location, u8), String> {
+        schedule_asset(&ScheduleEntry {
+            asset: asset.into(),
+            decimals,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn schedule_assets() {
+        let (symbol, location, decimals) = asset(POLKADOT_SYMBOL, None).unwrap();
+        assert_eq!(
+            (symbol.as_str(), decimals),
+            (POLKADOT_SYMBOL, POLKADOT_DECIMALS)
+        );
+        assert!(matches!(
+            location,
+            Location {
+                parents: 1,
+                interior: Junctions::Here
+            }
+        ));
+        for (stablecoin, index, stablecoin_decimals) in TREASURY_STABLECOINS {
+            let (symbol, location, decimals) = asset(stablecoin, None).unwrap();
+            assert_eq!(
+                (symbol.as_str(), decimals),
+                (*stablecoin, *stablecoin_decimals)
+            );
+            assert!(matches!(
+                location.interior,
+                Junctions::X2([Junction::PalletInstance(50), Junction::GeneralIndex(i)]) if i == *index
+            ));
+        }
+        let (_, _, decimals) = asset("../Parachain(2034)/GeneralIndex(0)", Some(12)).unwrap();
+        assert_eq!(decimals, 12);
+        assert_eq!(
+            asset("../Parachain(2034)/GeneralIndex(0)", None).unwrap_err(),
+            "decimals are required for an asset given by location"
+        );
+        assert_eq!(
+            asset(POLKADOT_SYMBOL, Some(18)).unwrap_err(),
+            format!("{POLKADOT_SYMBOL} has {POLKADOT_DECIMALS} decimals")
+        );
+    }
+
+    fn entry(delay: Option<&str>, date: Option<&str>) -> ScheduleEntry {
+        ScheduleEntry {
+            delay: delay.map(Into::into),
+            date: date.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn valid_from_delay() {
+        assert_eq!(schedule_valid_from(&entry(None, None), 100, None), Ok(None));
+        assert_eq!(
+            schedule_valid_from(&entry(Some("12h"), None), 100, None),
+            Ok(Some(100 + 12 * HOURS))
+        );
+        assert_eq!(
+            schedule_valid_from(&entry(Some("90d"), None), 100, None),
+            Ok(Some(100 + 90 * DAYS))
+        );
+        assert_eq!(
+            schedule_valid_from(&entry(Some("90m"), None), 100, None),
+            Ok(Some(1000))
+        );
+        // Rounded up to whole blocks
+        assert_eq!(
+            schedule_valid_from(&entry(Some("7s"), None), 100, None),
+            Ok(Some(102))
+        );
+        // The same grammar as --schedule-after, so a bare number of blocks is rejected
+        for delay in ["50", "90 days"] {
+            assert_eq!(
+                schedule_valid_from(&entry(Some(delay), None), 100, None),
+                Err(format!(
+                    "Invalid duration '{delay}', expected e.g. 45m, 36h or 7d"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn valid_from_date() {
+        let start_date = parse_date("2026-02-27").ok();
+        assert_eq!(
+            schedule_valid_from(&entry(None, Some("2026-02-27")), 100, start_date),
+            Ok(Some(100))
+        );
+        assert_eq!(
+            schedule_valid_from(&entry(None, Some("2026-03-01")), 100, start_date),
+            Ok(Some(100 + 2 * DAYS))
+        );
+        assert_eq!(
+            schedule_valid_from(&entry(None, Some("2026-02-26")), 100, start_date),
+            Err("2026-02-26 is before --start-date".into())
+        );
+        assert_eq!(
+            schedule_valid_from(&entry(None, Some("2026-03-01")), 100, None),
+            Err("--start-date is required for dated spends".into())
+        );
+    }
+
+    #[test]
+    fn valid_from_rejects_delay_and_date() {
+        let spend = entry(Some("90d"), Some("2026-03-01"));
+        assert_eq!(
+            schedule_valid_from(&spend, 100, parse_date("2026-02-27").ok()),
+            Err("only one of delay and date may be given".into())
+        );
+    }
+
+    #[test]
+    fn valid_from_overflow() {
+        let overflow: Result<Option<u32>, String> = Err("valid-from block overflows u32".into());
+        // The delay itself overflows
+        assert_eq!(
+            schedule_valid_from(&entry(Some("4294967295d"), None), 0, None),
+            overflow
+        );
+        // The delay fits, but not added to the start block
+        assert_eq!(
+            schedule_valid_from(&entry(Some("12s"), None), u32::MAX - 1, None),
+            overflow
+        );
+        let start_date = parse_date("2026-01-01").ok();
+        assert_eq!(
+            schedule_valid_from(&entry(None, Some("9999-12-31")), 0, start_date),
+            overflow
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use serde::Deserialize;
+use std::path::Path;
+
+/// Treasury spends for `treasury-proposal`, read from a TOML or CSV file.
+///
+/// ```toml
+/// [[spends]]
+/// name = "Launch reward"
+/// asset = "DOT"
+/// amount = "158656"
+/// delay = "90d"
+/// ```
+///
+/// A CSV file has a header row naming the same columns, in any order. Empty fields are
+/// treated as missing.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    pub spends: Vec<ScheduleEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleEntry {
+    pub name: String,
+    /// The relay chain token (e.g. `DOT`), a stablecoin of the network's AssetHub (`USDC`
+    /// or `USDT` on Polkadot), or the location of an asset on AssetHub, e.g.
+    /// `PalletInstance(50)/GeneralIndex(1984)`
+    pub asset: String,
+    /// Amount in whole units of the asset, e.g. `0.5`
+    pub amount: String,
+    /// Decimals of an asset given by location
+    pub decimals: Option<u8>,
+    /// Delay after `--start-block` before the spend is valid, a duration such as `90d` or
+    /// `12h` as for `--schedule-after`
+    pub delay: Option<String>,
+    /// Date (`YYYY-MM-DD`) from which the spend is valid
+    pub date: Option<String>,
+}
+
+pub fn load_schedule(path: &Path) -> Result<Schedule, Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read schedule {}: {e}", path.display()))?;
+    let schedule = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&data)?,
+        Some("csv") => parse_csv(&data).map_err(|e| format!("Invalid schedule: {e}"))?,
+        _ => return Err("Schedule must have a .toml or .csv extension".into()),
+    };
+    if schedule.spends.is_empty() {
+        return Err("Schedule contains no spends".into());
+    }
+    Ok(schedule)
+}
+
+fn parse_csv(data: &str) -> Result<Schedule, csv::Error> {
+    let spends = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes())
+        .deserialize()
+        .collect::<Result<_, _>>()?;
+    Ok(Schedule { spends })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &str) -> Result<Vec<ScheduleEntry>, String> {
+        parse_csv(data)
+            .map(|schedule| schedule.spends)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn parse_columns_in_any_order() {
+        let spends = parse(
+            "asset,name,amount,decimals,delay,date\n\
+             DOT, Launch reward ,158656,,90d,\n\
+             PalletInstance(50)/GeneralIndex(30),Other,0.5,10,,2026-03-01\n",
+        )
+        .unwrap();
+        let [first, second] = &spends[..] else {
+            panic!("expected two spends, found {}", spends.len());
+        };
+        assert_eq!(first.name, "Launch reward");
+        assert_eq!(first.asset, "DOT");
+        assert_eq!(first.amount, "158656");
+        assert_eq!(first.decimals, None);
+        assert_eq!(first.delay.as_deref(), Some("90d"));
+        assert_eq!(first.date, None);
+        assert_eq!(second.asset, "PalletInstance(50)/GeneralIndex(30)");
+        assert_eq!(second.decimals, Some(10));
+        assert_eq!(second.delay, None);
+        assert_eq!(second.date.as_deref(), Some("2026-03-01"));
+    }
+
+    #[test]
+    fn parse_quoted_commas_and_escaped_quotes() {
+        let spends = parse(
+            "name,asset,amount\n\
+             \"Audit, phase 2\",USDC,\"1,000\"\n\
+             \"The \"\"final\"\" payout\",DOT,1\n\
+             \n\
+             \"\",DOT,2\n",
+        )
+        .unwrap();
+        assert_eq!(spends.len(), 3);
+        assert_eq!(spends[0].name, "Audit, phase 2");
+        assert_eq!(spends[0].amount, "1,000");
+        assert_eq!(spends[1].name, r#"The "final" payout"#);
+        assert_eq!(spends[2].name, "");
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let err = parse("name,asset,amount\nA,DOT\n").unwrap_err();
+        assert!(err.contains("line: 2"), "{err}");
+        let err = parse("name,asset,amount,decimals\nA,DOT,1,\nB,DOT,1,ten\n").unwrap_err();
+        assert!(
+            err.contains("line: 3") && err.contains("invalid digit"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn reject_unknown_and_missing_columns() {
+        let err = parse("name,asset,amount,memo\nA,DOT,1,x\n").unwrap_err();
+        assert!(err.contains("unknown field `memo`"), "{err}");
+        let err = parse("name,asset\nA,DOT\n").unwrap_err();
+        assert!(err.contains("missing field `amount`"), "{err}");
+    }
+
+    #[test]
+    fn parse_toml() {
+        let schedule: Schedule = toml::from_str(
+            r#"
+            [[spends]]
+            name = "Launch reward"
+            asset = "DOT"
+            amount = "158656"
+            delay = "90d"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(schedule.spends[0].delay.as_deref(), Some("90d"));
+        assert!(toml::from_str::<Schedule>(
+            "[[spends]]\nname = \"A\"\nasset = \"DOT\"\namount = \"1\"\nvalid = \"now\"\n"
+        )
+        .is_err());
+    }
+}
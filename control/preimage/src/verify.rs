@@ -0,0 +1,73 @@
+use super::asset_hub_runtime::runtime_types::pallet_preimage::RequestStatus;
+use super::decode::decode_preimage;
+use crate::Context;
+use sp_crypto_hashing::blake2_256;
+use subxt::utils::H256;
+
+/// Compare the preimage noted on AssetHub under `hash` with the regenerated `preimage`,
+/// showing a diff of the decoded calls if they differ.
+pub async fn verify(
+    context: &Context,
+    hash: H256,
+    preimage: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let storage = context.asset_hub_api()?.storage().at_latest().await?;
+    let status = storage
+        .fetch(
+            &super::asset_hub_runtime::storage()
+                .preimage()
+                .request_status_for(hash),
+        )
+        .await?
+        .ok_or_else(|| format!("No preimage {hash:?} is noted or requested on AssetHub"))?;
+    let len = match status {
+        RequestStatus::Unrequested { len, .. } => {
+            eprintln!("Preimage {hash:?} is noted ({len} bytes) and not requested");
+            len
+        }
+        RequestStatus::Requested {
+            count, maybe_len, ..
+        } => {
+            eprintln!("Preimage {hash:?} is requested {count} time(s)");
+            maybe_len.ok_or("The preimage is requested but not noted yet")?
+        }
+    };
+    let on_chain = storage
+        .fetch(
+            &super::asset_hub_runtime::storage()
+                .preimage()
+                .preimage_for((hash, len)),
+        )
+        .await?
+        .ok_or_else(|| format!("Preimage {hash:?} has a status but no data on AssetHub"))?
+        .0;
+
+    let regenerated = H256(blake2_256(preimage));
+    if on_chain == preimage {
+        eprintln!("The preimage on AssetHub matches the regenerated preimage byte for byte");
+        return Ok(());
+    }
+    eprintln!(
+        "The preimage on AssetHub ({len} bytes) differs from the regenerated preimage \
+         {regenerated:?} ({} bytes)",
+        preimage.len()
+    );
+    let regenerated = decode_preimage(preimage)?;
+    match decode_preimage(&on_chain) {
+        Ok(on_chain) => {
+            let diff = on_chain.diff(&regenerated);
+            if diff.is_empty() {
+                eprintln!(
+                    "The decoded calls are identical, so the difference is in undecoded bytes"
+                );
+            } else {
+                eprintln!("Diff of the decoded calls (- on chain, + regenerated):");
+            }
+            for line in diff {
+                eprintln!("{line}");
+            }
+        }
+        Err(e) => eprintln!("{e}: 0x{}", hex::encode(&on_chain)),
+    }
+    Err("The preimage on AssetHub does not match".into())
+}
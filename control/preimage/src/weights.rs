@@ -0,0 +1,142 @@
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+use subxt::{OnlineClient, PolkadotConfig};
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FixedWeight {
+    pub ref_time: u64,
+    pub proof_size: u64,
+}
+
+/// Fixed weights for BridgeHub calls, keyed by `Pallet.call_name`,
+/// e.g. `EthereumSystem.set_pricing_parameters`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeightTable {
+    /// Weight for calls without an explicit entry
+    #[serde(default)]
+    pub default: Option<FixedWeight>,
+    #[serde(default)]
+    pub calls: BTreeMap<String, FixedWeight>,
+}
+
+impl WeightTable {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read weights file {}: {e}", path.display()))?;
+        Self::parse(&data, path.extension().and_then(|ext| ext.to_str()))
+    }
+
+    fn parse(data: &str, extension: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        let table = match extension {
+            Some("json") => serde_json::from_str(data)?,
+            Some("toml") => toml::from_str(data)?,
+            _ => return Err("Weights file must have a .toml or .json extension".into()),
+        };
+        Ok(table)
+    }
+
+    /// Weight for the call named `Pallet.call_name`, falling back to the default.
+    pub fn weight(&self, name: &str) -> Result<FixedWeight, Box<dyn std::error::Error>> {
+        self.calls
+            .get(name)
+            .or(self.default.as_ref())
+            .copied()
+            .ok_or_else(|| format!("No weight for '{name}' in weights file").into())
+    }
+}
+
+/// Source of the `fallback_max_weight` for each `Transact` sent to BridgeHub.
+pub enum WeightProvider {
+    /// Query `TransactionPaymentCallApi::query_call_info` on a live BridgeHub. The
+    /// result is doubled by `increase_weight` as a buffer against runtime upgrades.
+    Rpc(Box<OnlineClient<PolkadotConfig>>),
+    /// Use fixed weights loaded from a file, as is.
+    Table(WeightTable),
+    /// Omit the fallback weight (XCM v5), leaving BridgeHub to weigh the call itself.
+    NoFallback,
+    /// Offline without `--weights` or `--no-fallback-weight`, so a `Transact` cannot be built.
+    Unavailable,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+default = { ref_time = 1000, proof_size = 10 }
+
+[calls]
+"EthereumSystem.upgrade" = { ref_time = 2000, proof_size = 20 }
+"#;
+
+    #[test]
+    fn parse_toml_and_json() {
+        let table = WeightTable::parse(TOML, Some("toml")).unwrap();
+        let json = r#"{
+            "default": { "ref_time": 1000, "proof_size": 10 },
+            "calls": { "EthereumSystem.upgrade": { "ref_time": 2000, "proof_size": 20 } }
+        }"#;
+        let from_json = WeightTable::parse(json, Some("json")).unwrap();
+        assert_eq!(table.default, from_json.default);
+        assert_eq!(table.calls, from_json.calls);
+    }
+
+    #[test]
+    fn weight_falls_back_to_default() {
+        let table = WeightTable::parse(TOML, Some("toml")).unwrap();
+        assert_eq!(
+            table.weight("EthereumSystem.upgrade").unwrap(),
+            FixedWeight {
+                ref_time: 2000,
+                proof_size: 20
+            }
+        );
+        assert_eq!(
+            table.weight("EthereumSystem.set_operating_mode").unwrap(),
+            FixedWeight {
+                ref_time: 1000,
+                proof_size: 10
+            }
+        );
+    }
+
+    #[test]
+    fn weight_without_default() {
+        let table = WeightTable::parse(
+            "[calls]\n\"EthereumSystem.upgrade\" = { ref_time = 2000, proof_size = 20 }\n",
+            Some("toml"),
+        )
+        .unwrap();
+        assert!(table.weight("EthereumSystem.upgrade").is_ok());
+        assert_eq!(
+            table
+                .weight("EthereumSystem.set_operating_mode")
+                .unwrap_err()
+                .to_string(),
+            "No weight for 'EthereumSystem.set_operating_mode' in weights file"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_tables() {
+        for (data, extension) in [
+            ("defaults = { ref_time = 1, proof_size = 1 }", Some("toml")),
+            ("default = { ref_time = 1 }", Some("toml")),
+            ("default = { ref_time = -1, proof_size = 1 }", Some("toml")),
+            (
+                "[calls]\n\"A.b\" = { ref_time = 1, proof_size = 1, pov = 1 }",
+                Some("toml"),
+            ),
+            (r#"{ "calls": [] }"#, Some("json")),
+            (TOML, Some("yaml")),
+            (TOML, None),
+        ] {
+            assert!(
+                WeightTable::parse(data, extension).is_err(),
+                "{data} ({extension:?})"
+            );
+        }
+    }
+}
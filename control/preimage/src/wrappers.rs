@@ -0,0 +1,127 @@
+use super::asset_hub_runtime::runtime_types::{
+    pallet_multisig, pallet_proxy, sp_weights::weight_v2::Weight,
+};
+use super::asset_hub_runtime::{
+    ProxyType as AssetHubProxyType, RuntimeCall as AssetHubRuntimeCall,
+};
+use super::helpers::query_weight_asset_hub;
+use crate::report::MultisigReport;
+use crate::{Context, OriginArgs, ProxyType};
+use codec::Encode;
+use sp_crypto_hashing::blake2_256;
+use subxt::utils::{AccountId32, MultiAddress};
+
+/// Wrap `call`, a `Sudo.sudo` call, in `Proxy.proxy` and then `Multisig.as_multi`, as
+/// requested by `--via-proxy` and `--as-multi`. The real account of the proxy, or else the
+/// multisig, is the sudo key. With both, the multisig is expected to be a proxy of the real
+/// account.
+pub async fn wrap(
+    context: &Context,
+    call: AssetHubRuntimeCall,
+    params: &OriginArgs,
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    let call = match &params.via_proxy {
+        Some(real) => proxy(call, *real, params.proxy_type),
+        None => call,
+    };
+    if !params.as_multi {
+        return Ok(call);
+    }
+    as_multi(
+        context,
+        call,
+        params.threshold.unwrap(),
+        &params.signatories,
+    )
+    .await
+}
+
+fn proxy(
+    call: AssetHubRuntimeCall,
+    real: [u8; 32],
+    proxy_type: Option<ProxyType>,
+) -> AssetHubRuntimeCall {
+    let force_proxy_type = proxy_type.map(|proxy_type| match proxy_type {
+        ProxyType::Any => AssetHubProxyType::Any,
+        ProxyType::NonTransfer => AssetHubProxyType::NonTransfer,
+        ProxyType::CancelProxy => AssetHubProxyType::CancelProxy,
+        ProxyType::Assets => AssetHubProxyType::Assets,
+        ProxyType::AssetOwner => AssetHubProxyType::AssetOwner,
+        ProxyType::AssetManager => AssetHubProxyType::AssetManager,
+        ProxyType::Collator => AssetHubProxyType::Collator,
+    });
+    AssetHubRuntimeCall::Proxy(pallet_proxy::pallet::Call::proxy {
+        real: MultiAddress::<AccountId32, ()>::Id(real.into()),
+        force_proxy_type,
+        call: Box::new(call),
+    })
+}
+
+/// The first approval of a multisig call. `max_weight` is the weight AssetHub reports for
+/// `call`, which the final approval must cover.
+///
+/// Other signatories approve with `approve_as_multi`, passing the call hash and the
+/// timepoint of the block and extrinsic index in which this call was included.
+async fn as_multi(
+    context: &Context,
+    call: AssetHubRuntimeCall,
+    threshold: u16,
+    other_signatories: &[[u8; 32]],
+) -> Result<AssetHubRuntimeCall, Box<dyn std::error::Error>> {
+    // The pallet requires the other signatories to be sorted and unique
+    let mut other_signatories = other_signatories.to_vec();
+    other_signatories.sort();
+    other_signatories.dedup();
+    if threshold < 2 || usize::from(threshold) > other_signatories.len() + 1 {
+        return Err(format!(
+            "--threshold must be between 2 and the number of signatories ({})",
+            other_signatories.len() + 1
+        )
+        .into());
+    }
+
+    let (ref_time, proof_size) =
+        query_weight_asset_hub(context.asset_hub_api()?, call.clone()).await?;
+    let call_hash = blake2_256(&call.encode());
+    eprintln!(
+        "Multisig call hash for approve_as_multi: 0x{}",
+        hex::encode(call_hash)
+    );
+    eprintln!("Multisig max_weight: ref_time {ref_time}, proof_size {proof_size}");
+
+    context.report.borrow_mut().multisig = Some(MultisigReport {
+        call_hash: format!("0x{}", hex::encode(call_hash)),
+        threshold,
+        other_signatories: other_signatories
+            .iter()
+            .map(|account| format!("0x{}", hex::encode(account)))
+            .collect(),
+        max_weight: (ref_time, proof_size).into(),
+    });
+
+    Ok(AssetHubRuntimeCall::Multisig(
+        pallet_multisig::pallet::Call::as_multi {
+            threshold,
+            other_signatories: other_signatories.into_iter().map(AccountId32).collect(),
+            maybe_timepoint: None,
+            call: Box::new(call),
+            max_weight: Weight {
+                ref_time,
+                proof_size,
+            },
+        },
+    ))
+}
+
+/// The call dispatched by a `Proxy.proxy` or `Multisig.as_multi` call, if `call` is one.
+pub fn unwrap(call: &AssetHubRuntimeCall) -> Option<(&'static str, &AssetHubRuntimeCall)> {
+    match call {
+        AssetHubRuntimeCall::Proxy(pallet_proxy::pallet::Call::proxy { call, .. }) => {
+            Some(("Proxy.proxy", call))
+        }
+        AssetHubRuntimeCall::Multisig(pallet_multisig::pallet::Call::as_multi { call, .. }) => {
+            Some(("Multisig.as_multi", call))
+        }
+        _ => None,
+    }
+}
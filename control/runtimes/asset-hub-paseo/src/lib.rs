@@ -3,7 +3,7 @@
     derive_for_all_types = "Clone",
     derive_for_type(
         path = "asset_hub_paseo_runtime::RuntimeCall",
-        derive = "codec::Encode",
+        derive = "codec::Encode, codec::Decode",
         recursive
     ),
     derive_for_type(
@@ -22,3 +22,6 @@
 mod runtime {}
 
 pub use runtime::*;
+
+/// The metadata the bindings are generated from, for building calls dynamically.
+pub const METADATA: &[u8] = include_bytes!("../asset-hub-metadata.bin");
@@ -3,7 +3,7 @@
     derive_for_all_types = "Clone",
     derive_for_type(
         path = "bridge_hub_polkadot_runtime::RuntimeCall",
-        derive = "codec::Encode",
+        derive = "codec::Encode, codec::Decode",
         recursive
     ),
     derive_for_type(
@@ -27,4 +27,7 @@ mod runtime {}
 
 pub use runtime::*;
 
+/// The metadata the bindings are generated from, for building calls dynamically.
+pub const METADATA: &[u8] = include_bytes!("../bridge-hub-metadata.bin");
+
 pub const CHAIN_ID: u64 = 1;
@@ -22,3 +22,6 @@
 mod runtime {}
 
 pub use runtime::*;
+
+/// The metadata the bindings are generated from, for building calls dynamically.
+pub const METADATA: &[u8] = include_bytes!("../polkadot-metadata.bin");
@@ -0,0 +1,650 @@
+//! Token lists for the Snowbridge registration commands.
+//!
+//! The format is the Uniswap token list schema (https://tokenlists.org), with Polkadot native
+//! assets (PNAs) identified by an XCM location in `extensions.location` instead of an address:
+//!
+//! ```json
+//! {
+//!   "name": "Snowbridge",
+//!   "tokens": [
+//!     { "chainId": 1, "address": "0x7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0", "name": "Wrapped liquid staked Ether 2.0", "symbol": "wstETH", "decimals": 18 },
+//!     { "chainId": 1, "name": "Hydration", "symbol": "HDX", "decimals": 12,
+//!       "extensions": { "location": { "parents": 1, "interior": [{ "Parachain": 2034 }, { "GeneralIndex": 0 }] } } }
+//!   ]
+//! }
+//! ```
+//!
+//! Locations are relative to BridgeHub, where PNAs are registered:
+//! `{ "parents": 1, "interior": [] }` is the relay chain token,
+//! `{ "parents": 1, "interior": [{ "Parachain": 1000 }, ...] }` an AssetHub asset, and
+//! `parents: 0` an asset of BridgeHub itself.
+//!
+//! ERC20 entries may set `extensions.minBalance` and `extensions.isSufficient`. Only the Kusama
+//! tool uses them, as it creates missing ERC20s on Kusama AssetHub. On Polkadot, ERC20s are
+//! created by registering them through the Gateway, so they are ignored with a warning.
+
+pub mod storage;
+
+use alloy_primitives::Address;
+use codec::{Compact, Encode, Output};
+use serde::Deserialize;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Maximum length of the name and symbol of a PNA, from `snowbridge_core::AssetMetadata`.
+pub const PNA_METADATA_MAX_LEN: usize = 32;
+
+/// Maximum length of the name and symbol of a `ForeignAssets` asset on AssetHub
+/// (`AssetsStringLimit`).
+pub const ASSET_HUB_STRING_LIMIT: usize = 50;
+
+/// A whole token must be representable in a `u128` balance.
+pub const MAX_DECIMALS: u8 = 38;
+
+/// Maximum number of junctions of an XCM location.
+pub const MAX_JUNCTIONS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenList {
+    tokens: Vec<TokenEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenEntry {
+    chain_id: Option<u64>,
+    address: Option<String>,
+    name: String,
+    symbol: String,
+    decimals: u64,
+    #[serde(default)]
+    extensions: Extensions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Extensions {
+    location: Option<Location>,
+    min_balance: Option<u128>,
+    is_sufficient: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Location {
+    pub parents: u8,
+    pub interior: Vec<Junction>,
+}
+
+impl Location {
+    /// This location, relative to BridgeHub, as seen from Ethereum. `EthereumSystem` keys
+    /// `NativeToForeignId` by it. `parents: 0` is BridgeHub itself, so its interior is
+    /// prefixed with BridgeHub's parachain junction.
+    pub fn reanchored_to_ethereum(
+        &self,
+        relay_network: &NetworkId,
+        bridge_hub_id: u32,
+    ) -> Location {
+        let prefix = match self.parents {
+            0 => vec![
+                Junction::GlobalConsensus(relay_network.clone()),
+                Junction::Parachain(bridge_hub_id),
+            ],
+            1 => vec![Junction::GlobalConsensus(relay_network.clone())],
+            _ => vec![],
+        };
+        Location {
+            parents: 1,
+            interior: prefix
+                .into_iter()
+                .chain(self.interior.iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+/// A location written as a path, e.g. `../Parachain(2034)/GeneralIndex(0)` or
+/// `../../GlobalConsensus(Ethereum(1))/AccountKey20(0x7f39…)`. Networks are `Polkadot`,
+/// `Kusama`, `Ethereum(<chain id>)` and `ByGenesis(<hash>)`, byte values are hex.
+impl FromStr for Location {
+    type Err = String;
+
+    fn from_str(location: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| format!("Invalid location '{location}': {reason}");
+        let mut parents = 0u8;
+        let mut interior = vec![];
+        for segment in location.split('/').map(str::trim) {
+            if segment == ".." && interior.is_empty() {
+                parents += 1;
+                continue;
+            }
+            interior.push(segment.parse::<Junction>().map_err(invalid)?);
+        }
+        if interior.len() > MAX_JUNCTIONS {
+            return Err(invalid(format!("more than {MAX_JUNCTIONS} junctions")));
+        }
+        Ok(Location { parents, interior })
+    }
+}
+
+/// Encoded as an XCM v5 `Location`, so that it decodes into the `Location` type of any
+/// runtime on XCM v5.
+impl Encode for Location {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        self.parents.encode_to(dest);
+        // `Junctions::Here` and `X1` to `X8` are variants 0 to 8
+        (self.interior.len() as u8).encode_to(dest);
+        for junction in &self.interior {
+            junction.encode_to(dest);
+        }
+    }
+}
+
+/// The XCM junctions used by PNA locations.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum Junction {
+    Parachain(u32),
+    PalletInstance(u8),
+    GeneralIndex(u128),
+    GeneralKey {
+        length: u8,
+        #[serde(deserialize_with = "hex_bytes32")]
+        data: [u8; 32],
+    },
+    GlobalConsensus(NetworkId),
+    AccountKey20 {
+        #[serde(deserialize_with = "hex_bytes20")]
+        key: [u8; 20],
+    },
+    AccountId32 {
+        #[serde(deserialize_with = "hex_bytes32")]
+        id: [u8; 32],
+    },
+}
+
+impl FromStr for Junction {
+    type Err = String;
+
+    fn from_str(junction: &str) -> Result<Self, Self::Err> {
+        let (kind, value) = junction
+            .strip_suffix(')')
+            .and_then(|junction| junction.split_once('('))
+            .ok_or_else(|| format!("'{junction}' is not a junction such as Parachain(1000)"))?;
+        let number = |_| format!("invalid number in '{junction}'");
+        Ok(match kind {
+            "Parachain" => Junction::Parachain(value.parse().map_err(number)?),
+            "PalletInstance" => Junction::PalletInstance(value.parse().map_err(number)?),
+            "GeneralIndex" => Junction::GeneralIndex(value.parse().map_err(number)?),
+            "GeneralKey" => {
+                let key = hex::decode(value.trim_start_matches("0x"))
+                    .ok()
+                    .filter(|key| !key.is_empty() && key.len() <= 32)
+                    .ok_or_else(|| format!("'{junction}' needs 1 to 32 hex bytes"))?;
+                let mut data = [0u8; 32];
+                data[..key.len()].copy_from_slice(&key);
+                Junction::GeneralKey {
+                    length: key.len() as u8,
+                    data,
+                }
+            }
+            "GlobalConsensus" => Junction::GlobalConsensus(value.parse()?),
+            "AccountKey20" => Junction::AccountKey20 {
+                key: parse_hex(value).map_err(|e| format!("'{junction}': {e}"))?,
+            },
+            "AccountId32" => Junction::AccountId32 {
+                id: parse_hex(value).map_err(|e| format!("'{junction}': {e}"))?,
+            },
+            _ => return Err(format!("unknown junction '{kind}'")),
+        })
+    }
+}
+
+impl Encode for Junction {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        // Variant indices of the XCM v5 `Junction`, with no network for accounts
+        match self {
+            Junction::Parachain(id) => (0u8, Compact(*id)).encode_to(dest),
+            Junction::AccountId32 { id } => (1u8, None::<NetworkId>, id).encode_to(dest),
+            Junction::AccountKey20 { key } => (3u8, None::<NetworkId>, key).encode_to(dest),
+            Junction::PalletInstance(index) => (4u8, index).encode_to(dest),
+            Junction::GeneralIndex(index) => (5u8, Compact(*index)).encode_to(dest),
+            Junction::GeneralKey { length, data } => (6u8, length, data).encode_to(dest),
+            Junction::GlobalConsensus(network) => (9u8, network).encode_to(dest),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum NetworkId {
+    Polkadot,
+    Kusama,
+    Ethereum {
+        #[serde(rename = "chainId")]
+        chain_id: u64,
+    },
+    ByGenesis(#[serde(deserialize_with = "hex_bytes32")] [u8; 32]),
+}
+
+impl FromStr for NetworkId {
+    type Err = String;
+
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "unknown network '{network}', expected Polkadot, Kusama, Ethereum(<chain id>) \
+                 or ByGenesis(<hash>)"
+            )
+        };
+        match network.strip_suffix(')').and_then(|n| n.split_once('(')) {
+            None if network == "Polkadot" => Ok(NetworkId::Polkadot),
+            None if network == "Kusama" => Ok(NetworkId::Kusama),
+            Some(("Ethereum", chain_id)) => Ok(NetworkId::Ethereum {
+                chain_id: chain_id.parse().map_err(|_| invalid())?,
+            }),
+            Some(("ByGenesis", genesis)) => Ok(NetworkId::ByGenesis(
+                parse_hex(genesis).map_err(|_| invalid())?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Encode for NetworkId {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        // Variant indices of the XCM v5 `NetworkId`, which keeps those of removed networks
+        match self {
+            NetworkId::ByGenesis(genesis) => (0u8, genesis).encode_to(dest),
+            NetworkId::Polkadot => 2u8.encode_to(dest),
+            NetworkId::Kusama => 3u8.encode_to(dest),
+            NetworkId::Ethereum { chain_id } => (7u8, Compact(*chain_id)).encode_to(dest),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Clone, Debug)]
+pub enum Token {
+    /// An ERC20 token, registered on AssetHub as a foreign asset
+    Erc20 {
+        address: Address,
+        metadata: TokenMetadata,
+        min_balance: Option<u128>,
+        is_sufficient: Option<bool>,
+    },
+    /// A Polkadot native asset, registered on BridgeHub for export to Ethereum
+    Native {
+        location: Location,
+        metadata: TokenMetadata,
+    },
+}
+
+impl Token {
+    pub fn metadata(&self) -> &TokenMetadata {
+        match self {
+            Token::Erc20 { metadata, .. } | Token::Native { metadata, .. } => metadata,
+        }
+    }
+}
+
+/// Load the tokens of a token list for the Ethereum chain `chain_id`, skipping entries for
+/// other chains. Every entry is validated, and all invalid entries are reported together.
+pub fn load_token_list(
+    path: &Path,
+    chain_id: u64,
+) -> Result<Vec<Token>, Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read token list {}: {e}", path.display()))?;
+    let list: TokenList = serde_json::from_str(&data)
+        .map_err(|e| format!("Invalid token list {}: {e}", path.display()))?;
+
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    for (index, entry) in list.tokens.into_iter().enumerate() {
+        if entry.chain_id.is_some_and(|id| id != chain_id) {
+            continue;
+        }
+        let label = format!("Token #{index} ({})", entry.symbol);
+        match validate(entry) {
+            Ok(token) => tokens.push(token),
+            Err(e) => errors.push(format!("{label}: {e}")),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n").into());
+    }
+    if tokens.is_empty() {
+        return Err(format!("Token list contains no tokens for chain {chain_id}").into());
+    }
+    Ok(tokens)
+}
+
+fn validate(entry: TokenEntry) -> Result<Token, String> {
+    let decimals = u8::try_from(entry.decimals)
+        .ok()
+        .filter(|decimals| *decimals <= MAX_DECIMALS)
+        .ok_or_else(|| format!("decimals {} exceed {MAX_DECIMALS}", entry.decimals))?;
+    let metadata = TokenMetadata {
+        name: entry.name,
+        symbol: entry.symbol,
+        decimals,
+    };
+
+    match (entry.address, entry.extensions.location) {
+        (Some(_), Some(_)) => {
+            Err("only one of address and extensions.location may be given".into())
+        }
+        (None, None) => Err("either address or extensions.location is required".into()),
+        (Some(address), None) => {
+            let address = Address::parse_checksummed(&address, None)
+                .map_err(|e| format!("address {address} is not checksummed: {e}"))?;
+            check_length(&metadata, ASSET_HUB_STRING_LIMIT)?;
+            Ok(Token::Erc20 {
+                address,
+                metadata,
+                min_balance: entry.extensions.min_balance,
+                is_sufficient: entry.extensions.is_sufficient,
+            })
+        }
+        (None, Some(location)) => {
+            if location.interior.len() > MAX_JUNCTIONS || location.parents > 2 {
+                return Err("location is not a valid XCM location".into());
+            }
+            check_length(&metadata, PNA_METADATA_MAX_LEN)?;
+            Ok(Token::Native { location, metadata })
+        }
+    }
+}
+
+fn check_length(metadata: &TokenMetadata, limit: usize) -> Result<(), String> {
+    for (field, value) in [("name", &metadata.name), ("symbol", &metadata.symbol)] {
+        if value.is_empty() || value.len() > limit {
+            return Err(format!(
+                "{field} '{value}' must be between 1 and {limit} bytes long"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn parse_hex<const N: usize>(value: &str) -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    hex::decode_to_slice(value.trim_start_matches("0x"), &mut bytes)
+        .map_err(|e| format!("invalid {N}-byte hex value: {e}"))?;
+    Ok(bytes)
+}
+
+fn hex_bytes<'de, D: serde::Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_hex(&value).map_err(serde::de::Error::custom)
+}
+
+fn hex_bytes20<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<[u8; 20], D::Error> {
+    hex_bytes(deserializer)
+}
+
+fn hex_bytes32<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+    hex_bytes(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WSTETH: &str = "0x7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0";
+
+    fn entry(json: &str) -> TokenEntry {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn erc20(address: &str, name: &str, decimals: u64) -> TokenEntry {
+        entry(&format!(
+            r#"{{ "chainId": 1, "address": "{address}", "name": "{name}", "symbol": "TKN", "decimals": {decimals} }}"#
+        ))
+    }
+
+    fn native(name: &str, location: &str) -> TokenEntry {
+        entry(&format!(
+            r#"{{ "name": "{name}", "symbol": "TKN", "decimals": 12, "extensions": {{ "location": {location} }} }}"#
+        ))
+    }
+
+    #[test]
+    fn validate_erc20() {
+        let token = validate(erc20(WSTETH, "Wrapped liquid staked Ether 2.0", 18)).unwrap();
+        let Token::Erc20 {
+            address, metadata, ..
+        } = token
+        else {
+            panic!("expected an ERC20");
+        };
+        assert_eq!(address, WSTETH.parse::<Address>().unwrap());
+        assert_eq!(metadata.decimals, 18);
+    }
+
+    #[test]
+    fn validate_native() {
+        let token = validate(native(
+            "Hydration",
+            r#"{ "parents": 1, "interior": [{ "Parachain": 2034 }, { "GeneralIndex": 0 }] }"#,
+        ))
+        .unwrap();
+        let Token::Native { location, .. } = token else {
+            panic!("expected a PNA");
+        };
+        assert_eq!(
+            location.interior,
+            vec![Junction::Parachain(2034), Junction::GeneralIndex(0)]
+        );
+    }
+
+    #[test]
+    fn validate_requires_checksummed_address() {
+        let lowercase = WSTETH.to_lowercase();
+        let error = validate(erc20(&lowercase, "Token", 18)).unwrap_err();
+        assert!(error.contains("is not checksummed"), "{error}");
+        let wrong_case = WSTETH.replace("7f39C", "7F39c");
+        assert!(validate(erc20(&wrong_case, "Token", 18)).is_err());
+    }
+
+    #[test]
+    fn validate_decimals() {
+        assert!(validate(erc20(WSTETH, "Token", u64::from(MAX_DECIMALS))).is_ok());
+        let error = validate(erc20(WSTETH, "Token", 39)).unwrap_err();
+        assert_eq!(error, "decimals 39 exceed 38");
+        assert!(validate(erc20(WSTETH, "Token", 256)).is_err());
+    }
+
+    #[test]
+    fn validate_length_limits() {
+        let location = r#"{ "parents": 1, "interior": [] }"#;
+        let at_pna_limit = "a".repeat(PNA_METADATA_MAX_LEN);
+        let over_pna_limit = "a".repeat(PNA_METADATA_MAX_LEN + 1);
+        assert!(validate(native(&at_pna_limit, location)).is_ok());
+        let error = validate(native(&over_pna_limit, location)).unwrap_err();
+        assert!(error.contains("between 1 and 32 bytes"), "{error}");
+        assert!(validate(native("", location)).is_err());
+
+        // ERC20s only need to fit into AssetHub's longer limit.
+        assert!(validate(erc20(WSTETH, &over_pna_limit, 18)).is_ok());
+        let at_asset_hub_limit = "a".repeat(ASSET_HUB_STRING_LIMIT);
+        let over_asset_hub_limit = "a".repeat(ASSET_HUB_STRING_LIMIT + 1);
+        assert!(validate(erc20(WSTETH, &at_asset_hub_limit, 18)).is_ok());
+        let error = validate(erc20(WSTETH, &over_asset_hub_limit, 18)).unwrap_err();
+        assert!(error.contains("between 1 and 50 bytes"), "{error}");
+    }
+
+    #[test]
+    fn validate_address_or_location() {
+        let both = entry(&format!(
+            r#"{{ "address": "{WSTETH}", "name": "Token", "symbol": "TKN", "decimals": 18,
+                 "extensions": {{ "location": {{ "parents": 1, "interior": [] }} }} }}"#
+        ));
+        assert_eq!(
+            validate(both).unwrap_err(),
+            "only one of address and extensions.location may be given"
+        );
+        let neither = entry(r#"{ "name": "Token", "symbol": "TKN", "decimals": 18 }"#);
+        assert_eq!(
+            validate(neither).unwrap_err(),
+            "either address or extensions.location is required"
+        );
+        let too_many_parents = native("Token", r#"{ "parents": 3, "interior": [] }"#);
+        assert!(validate(too_many_parents).is_err());
+    }
+
+    #[test]
+    fn reanchor_to_ethereum() {
+        let network = NetworkId::Polkadot;
+        let relay = Location {
+            parents: 1,
+            interior: vec![],
+        };
+        assert_eq!(
+            relay.reanchored_to_ethereum(&network, 1002),
+            Location {
+                parents: 1,
+                interior: vec![Junction::GlobalConsensus(NetworkId::Polkadot)],
+            }
+        );
+
+        let asset_hub_asset = Location {
+            parents: 1,
+            interior: vec![
+                Junction::Parachain(1000),
+                Junction::PalletInstance(50),
+                Junction::GeneralIndex(1984),
+            ],
+        };
+        assert_eq!(
+            asset_hub_asset.reanchored_to_ethereum(&network, 1002),
+            Location {
+                parents: 1,
+                interior: vec![
+                    Junction::GlobalConsensus(NetworkId::Polkadot),
+                    Junction::Parachain(1000),
+                    Junction::PalletInstance(50),
+                    Junction::GeneralIndex(1984),
+                ],
+            }
+        );
+
+        let bridge_hub_asset = Location {
+            parents: 0,
+            interior: vec![Junction::PalletInstance(10)],
+        };
+        assert_eq!(
+            bridge_hub_asset.reanchored_to_ethereum(&NetworkId::Kusama, 1002),
+            Location {
+                parents: 1,
+                interior: vec![
+                    Junction::GlobalConsensus(NetworkId::Kusama),
+                    Junction::Parachain(1002),
+                    Junction::PalletInstance(10),
+                ],
+            }
+        );
+
+        let other_consensus = Location {
+            parents: 2,
+            interior: vec![Junction::GlobalConsensus(NetworkId::Kusama)],
+        };
+        assert_eq!(
+            other_consensus.reanchored_to_ethereum(&network, 1002),
+            Location {
+                parents: 1,
+                interior: vec![Junction::GlobalConsensus(NetworkId::Kusama)],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_locations() {
+        assert_eq!(
+            "../Parachain(2034) / GeneralIndex(0)"
+                .parse::<Location>()
+                .unwrap(),
+            Location {
+                parents: 1,
+                interior: vec![Junction::Parachain(2034), Junction::GeneralIndex(0)],
+            }
+        );
+        assert_eq!(
+            format!("../../GlobalConsensus(Ethereum(1))/AccountKey20({WSTETH})")
+                .parse::<Location>()
+                .unwrap(),
+            Location {
+                parents: 2,
+                interior: vec![
+                    Junction::GlobalConsensus(NetworkId::Ethereum { chain_id: 1 }),
+                    Junction::AccountKey20 {
+                        key: WSTETH.parse::<Address>().unwrap().into_array(),
+                    },
+                ],
+            }
+        );
+        let mut data = [0u8; 32];
+        data[..2].copy_from_slice(&[0xab, 0xcd]);
+        assert_eq!(
+            "GeneralKey(0xabcd)".parse::<Location>().unwrap().interior,
+            vec![Junction::GeneralKey { length: 2, data }]
+        );
+        assert_eq!(
+            "../..".parse::<Location>().unwrap(),
+            Location {
+                parents: 2,
+                interior: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn reject_invalid_locations() {
+        for location in [
+            "PalletInstance(50)/../GeneralIndex(1)",
+            "PalletInstance(256)",
+            "Parachain 1000",
+            "OnlyChild",
+            "GlobalConsensus(Westend)",
+            "GlobalConsensus(Ethereum(-1))",
+            "AccountKey20(0x1234)",
+            "GeneralKey(0x)",
+            "Parachain(1)/Parachain(2)/Parachain(3)/Parachain(4)/Parachain(5)/Parachain(6)/\
+             Parachain(7)/Parachain(8)/Parachain(9)",
+        ] {
+            let error = location.parse::<Location>().unwrap_err();
+            assert!(
+                error.starts_with(&format!("Invalid location '{location}'")),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn encode_as_xcm_location() {
+        let ether = format!("../../GlobalConsensus(Ethereum(1))/AccountKey20({WSTETH})");
+        assert_eq!(
+            hex::encode(ether.parse::<Location>().unwrap().encode()),
+            "020209070403007f39c581f595b53c5cb19bd0b3f8da6c935e2ca0"
+        );
+        let hydration = "../Parachain(2034)/GeneralIndex(0)";
+        assert_eq!(
+            hex::encode(hydration.parse::<Location>().unwrap().encode()),
+            "010200c91f0500"
+        );
+        let mut genesis = format!("../GlobalConsensus(ByGenesis(0x{}))", "01".repeat(32));
+        genesis += &format!("/AccountId32(0x{})/PalletInstance(50)", "02".repeat(32));
+        assert_eq!(
+            hex::encode(genesis.parse::<Location>().unwrap().encode()),
+            format!("01030900{}0100{}0432", "01".repeat(32), "02".repeat(32))
+        );
+    }
+}
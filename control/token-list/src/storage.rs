@@ -0,0 +1,27 @@
+//! Raw AssetHub storage used by the registration and freeze commands of both tools.
+
+use crate::TokenMetadata;
+use codec::Decode;
+use sp_crypto_hashing::{blake2_128, twox_128};
+
+/// Storage key of a `Blake2_128Concat` map entry.
+pub fn map_key(pallet: &str, storage: &str, key: &[u8]) -> Vec<u8> {
+    [
+        &twox_128(pallet.as_bytes())[..],
+        &twox_128(storage.as_bytes()),
+        &blake2_128(key),
+        key,
+    ]
+    .concat()
+}
+
+/// Name, symbol and decimals of an encoded `pallet_assets::AssetMetadata`.
+pub fn decode_asset_metadata(value: &[u8]) -> Result<TokenMetadata, Box<dyn std::error::Error>> {
+    let (_deposit, name, symbol, decimals, _is_frozen) =
+        <(u128, Vec<u8>, Vec<u8>, u8, bool)>::decode(&mut &value[..])?;
+    Ok(TokenMetadata {
+        name: String::from_utf8_lossy(&name).into_owned(),
+        symbol: String::from_utf8_lossy(&symbol).into_owned(),
+        decimals,
+    })
+}